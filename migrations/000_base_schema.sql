@@ -0,0 +1,54 @@
+-- Migration: Base schema
+-- Description: Roles, employees, users and the users_extended view. Matches
+-- what setup_database.sql creates, without the sample data. Databases set up
+-- by hand are marked as migrated with `rfc-admin migrate baseline` instead.
+
+-- UP Migration
+CREATE TABLE IF NOT EXISTS app_role (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(50) NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS employee (
+    id SERIAL PRIMARY KEY,
+    first_name VARCHAR(100) NOT NULL,
+    last_name VARCHAR(100) NOT NULL,
+    email VARCHAR(100) NOT NULL UNIQUE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- employee_id is added by 001
+CREATE TABLE IF NOT EXISTS users (
+    id SERIAL PRIMARY KEY,
+    email VARCHAR(100) NOT NULL UNIQUE,
+    password_hash VARCHAR(255) NOT NULL,
+    role_id INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+
+    CONSTRAINT fk_users_role
+        FOREIGN KEY (role_id)
+        REFERENCES app_role(id)
+        ON DELETE RESTRICT
+);
+
+CREATE INDEX IF NOT EXISTS idx_users_email ON users(email);
+CREATE INDEX IF NOT EXISTS idx_users_role_id ON users(role_id);
+CREATE INDEX IF NOT EXISTS idx_employee_email ON employee(email);
+
+-- Roles are reference data: the application and SCIM rely on these names
+INSERT INTO app_role (id, name) VALUES
+    (1, 'ROLE_ADMIN'),
+    (2, 'ROLE_MANAGER'),
+    (3, 'ROLE_HR'),
+    (4, 'ROLE_IT'),
+    (5, 'ROLE_EMPLOYEE')
+ON CONFLICT (name) DO NOTHING;
+
+SELECT setval('app_role_id_seq', (SELECT MAX(id) FROM app_role));
+
+-- DOWN Migration (if you need to rollback)
+-- DROP TABLE IF EXISTS users;
+-- DROP TABLE IF EXISTS employee;
+-- DROP TABLE IF EXISTS app_role;
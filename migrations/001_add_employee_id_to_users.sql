@@ -3,22 +3,43 @@
 
 -- UP Migration
 ALTER TABLE users
-ADD COLUMN employee_id INTEGER;
+ADD COLUMN IF NOT EXISTS employee_id INTEGER;
 
--- Add foreign key constraint
-ALTER TABLE users
-ADD CONSTRAINT fk_users_employee
-FOREIGN KEY (employee_id)
-REFERENCES employee(id)
-ON DELETE SET NULL;
+-- Add foreign key constraint (setup_database.sql already creates it)
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint WHERE conname = 'fk_users_employee'
+    ) THEN
+        ALTER TABLE users
+        ADD CONSTRAINT fk_users_employee
+        FOREIGN KEY (employee_id)
+        REFERENCES employee(id)
+        ON DELETE SET NULL;
+    END IF;
+END;
+$$;
 
 -- Add index for better performance
-CREATE INDEX idx_users_employee_id ON users(employee_id);
+CREATE INDEX IF NOT EXISTS idx_users_employee_id ON users(employee_id);
+
+-- Joined view used by the users page and the readiness check
+CREATE OR REPLACE VIEW users_extended AS
+SELECT
+    u.id AS user_id,
+    u.email,
+    r.name AS role_name,
+    u.employee_id,
+    e.first_name || ' ' || e.last_name AS employee_name
+FROM users u
+JOIN app_role r ON r.id = u.role_id
+LEFT JOIN employee e ON e.id = u.employee_id;
 
 -- Optional: Add unique constraint if one employee should only have one user account
 -- ALTER TABLE users ADD CONSTRAINT unique_employee_id UNIQUE (employee_id);
 
 -- DOWN Migration (if you need to rollback)
+-- DROP VIEW IF EXISTS users_extended;
 -- ALTER TABLE users DROP CONSTRAINT fk_users_employee;
 -- DROP INDEX idx_users_employee_id;
 -- ALTER TABLE users DROP COLUMN employee_id;
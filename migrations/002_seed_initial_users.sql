@@ -4,6 +4,11 @@
 -- Default password for all test accounts: "password123"
 -- IMPORTANT: Change these passwords in production!
 
+-- Roles are looked up by name and employees by email, so missing employees
+-- leave employee_id NULL.
+-- `rfc-admin migrate up` records this migration without running it;
+-- `rfc-admin seed --demo` runs it after inserting the demo employees.
+
 -- Note: These bcrypt hashes are for password "password123" with cost 12
 -- Generated using: bcrypt::hash("password123", 12)
 
@@ -12,9 +17,9 @@ INSERT INTO users (email, password_hash, role_id, employee_id, created_at)
 VALUES (
     'admin@company.com',
     '$2b$12$LQv3c1yqBWVHxkd0LHAkCOYz6TtxMQJqhN8/LewY5BI6YOO2/R7kS',
-    1, -- ROLE_ADMIN
+    (SELECT id FROM app_role WHERE name = 'ROLE_ADMIN'),
     NULL,
-    NOW()
+    CURRENT_TIMESTAMP
 ) ON CONFLICT (email) DO NOTHING;
 
 -- 2. HR User - Leslie Andrews (employee_id = 1)
@@ -22,9 +27,9 @@ INSERT INTO users (email, password_hash, role_id, employee_id, created_at)
 VALUES (
     'leslie@luv2code.com',
     '$2b$12$LQv3c1yqBWVHxkd0LHAkCOYz6TtxMQJqhN8/LewY5BI6YOO2/R7kS',
-    3, -- ROLE_HR
-    1,
-    NOW()
+    (SELECT id FROM app_role WHERE name = 'ROLE_HR'),
+    (SELECT id FROM employee WHERE email = 'leslie@luv2code.com'),
+    CURRENT_TIMESTAMP
 ) ON CONFLICT (email) DO NOTHING;
 
 -- 3. IT User - Emma Baumgarten (employee_id = 2)
@@ -32,9 +37,9 @@ INSERT INTO users (email, password_hash, role_id, employee_id, created_at)
 VALUES (
     'emma@luv2code.com',
     '$2b$12$LQv3c1yqBWVHxkd0LHAkCOYz6TtxMQJqhN8/LewY5BI6YOO2/R7kS',
-    4, -- ROLE_IT
-    2,
-    NOW()
+    (SELECT id FROM app_role WHERE name = 'ROLE_IT'),
+    (SELECT id FROM employee WHERE email = 'emma@luv2code.com'),
+    CURRENT_TIMESTAMP
 ) ON CONFLICT (email) DO NOTHING;
 
 -- 4. Employee User - Avani Gupta (employee_id = 3)
@@ -42,9 +47,9 @@ INSERT INTO users (email, password_hash, role_id, employee_id, created_at)
 VALUES (
     'avani@luv2code.com',
     '$2b$12$LQv3c1yqBWVHxkd0LHAkCOYz6TtxMQJqhN8/LewY5BI6YOO2/R7kS',
-    5, -- ROLE_EMPLOYEE
-    3,
-    NOW()
+    (SELECT id FROM app_role WHERE name = 'ROLE_EMPLOYEE'),
+    (SELECT id FROM employee WHERE email = 'avani@luv2code.com'),
+    CURRENT_TIMESTAMP
 ) ON CONFLICT (email) DO NOTHING;
 
 -- Verify the inserted users
@@ -0,0 +1,18 @@
+-- Migration: SCIM provisioning support
+-- Description: Lets an identity provider deactivate accounts and track its own ids
+
+-- UP Migration
+ALTER TABLE users
+ADD COLUMN is_active BOOLEAN NOT NULL DEFAULT TRUE;
+
+-- Identifier assigned by the identity provider (SCIM "externalId")
+ALTER TABLE users
+ADD COLUMN external_id VARCHAR(255);
+
+ALTER TABLE users
+ADD CONSTRAINT unique_users_external_id UNIQUE (external_id);
+
+-- DOWN Migration (if you need to rollback)
+-- ALTER TABLE users DROP CONSTRAINT unique_users_external_id;
+-- ALTER TABLE users DROP COLUMN external_id;
+-- ALTER TABLE users DROP COLUMN is_active;
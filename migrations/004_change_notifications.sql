@@ -0,0 +1,41 @@
+-- Migration: Change notifications
+-- Description: Publishes employee and users row changes on the `app_changes`
+-- channel so open browser tabs can refresh without polling
+
+-- UP Migration
+CREATE OR REPLACE FUNCTION public.notify_app_change()
+RETURNS trigger
+LANGUAGE plpgsql
+AS $$
+DECLARE
+    row_id INTEGER;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        row_id := OLD.id;
+    ELSE
+        row_id := NEW.id;
+    END IF;
+
+    PERFORM pg_notify(
+        'app_changes',
+        json_build_object('table', TG_TABLE_NAME, 'op', TG_OP, 'id', row_id)::text
+    );
+
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER trg_notify_employee_change
+AFTER INSERT OR UPDATE OR DELETE ON public.employee
+FOR EACH ROW
+EXECUTE FUNCTION public.notify_app_change();
+
+CREATE TRIGGER trg_notify_users_change
+AFTER INSERT OR UPDATE OR DELETE ON public.users
+FOR EACH ROW
+EXECUTE FUNCTION public.notify_app_change();
+
+-- DOWN Migration (if you need to rollback)
+-- DROP TRIGGER IF EXISTS trg_notify_users_change ON public.users;
+-- DROP TRIGGER IF EXISTS trg_notify_employee_change ON public.employee;
+-- DROP FUNCTION IF EXISTS public.notify_app_change();
@@ -0,0 +1,118 @@
+-- Migration: Outbound webhooks
+-- Description: Webhook registrations plus a persistent outbox/delivery log.
+-- Triggers enqueue one delivery per matching webhook in the same transaction
+-- as the change, so no event is lost if the server is down.
+
+-- UP Migration
+CREATE TABLE webhook (
+    id          SERIAL PRIMARY KEY,
+    url         TEXT NOT NULL,
+    event_type  VARCHAR(64) NOT NULL CHECK (event_type IN (
+                    'employee.created',
+                    'employee.updated',
+                    'employee.deleted',
+                    'user.role_changed'
+                )),
+    -- HMAC-SHA256 key shared with the receiver
+    secret      VARCHAR(128) NOT NULL,
+    is_active   BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE webhook_delivery (
+    id               SERIAL PRIMARY KEY,
+    webhook_id       INTEGER NOT NULL REFERENCES webhook(id) ON DELETE CASCADE,
+    event_type       VARCHAR(64) NOT NULL,
+    payload          JSONB NOT NULL,
+    -- pending -> delivered | failed (after the last retry)
+    status           VARCHAR(16) NOT NULL DEFAULT 'pending'
+                     CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts         INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_status      INTEGER,
+    last_error       TEXT,
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at     TIMESTAMPTZ
+);
+
+CREATE INDEX idx_webhook_delivery_due
+    ON webhook_delivery (next_attempt_at)
+    WHERE status = 'pending';
+
+-- ================================================
+--  FUNCTION: enqueue one delivery per active webhook
+-- ================================================
+CREATE OR REPLACE FUNCTION public.enqueue_webhook_event(p_event TEXT, p_data JSONB)
+RETURNS void
+LANGUAGE sql
+AS $$
+    INSERT INTO public.webhook_delivery (webhook_id, event_type, payload)
+    SELECT id,
+           p_event,
+           jsonb_build_object('event', p_event, 'occurred_at', NOW(), 'data', p_data)
+    FROM public.webhook
+    WHERE event_type = p_event
+      AND is_active;
+$$;
+
+-- ================================================
+--  TRIGGER: employee lifecycle
+-- ================================================
+CREATE OR REPLACE FUNCTION public.webhook_employee_change()
+RETURNS trigger
+LANGUAGE plpgsql
+AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM public.enqueue_webhook_event('employee.created', to_jsonb(NEW));
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF NEW IS DISTINCT FROM OLD THEN
+            PERFORM public.enqueue_webhook_event('employee.updated', to_jsonb(NEW));
+        END IF;
+    ELSE
+        PERFORM public.enqueue_webhook_event('employee.deleted', to_jsonb(OLD));
+    END IF;
+
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER trg_webhook_employee_change
+AFTER INSERT OR UPDATE OR DELETE ON public.employee
+FOR EACH ROW
+EXECUTE FUNCTION public.webhook_employee_change();
+
+-- ================================================
+--  TRIGGER: user role changes (never exposes password_hash)
+-- ================================================
+CREATE OR REPLACE FUNCTION public.webhook_user_role_change()
+RETURNS trigger
+LANGUAGE plpgsql
+AS $$
+BEGIN
+    PERFORM public.enqueue_webhook_event('user.role_changed', jsonb_build_object(
+        'user_id',     NEW.id,
+        'email',       NEW.email,
+        'employee_id', NEW.employee_id,
+        'old_role',    (SELECT name FROM public.app_role WHERE id = OLD.role_id),
+        'new_role',    (SELECT name FROM public.app_role WHERE id = NEW.role_id)
+    ));
+
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER trg_webhook_user_role_change
+AFTER UPDATE OF role_id ON public.users
+FOR EACH ROW
+WHEN (NEW.role_id IS DISTINCT FROM OLD.role_id)
+EXECUTE FUNCTION public.webhook_user_role_change();
+
+-- DOWN Migration (if you need to rollback)
+-- DROP TRIGGER IF EXISTS trg_webhook_user_role_change ON public.users;
+-- DROP TRIGGER IF EXISTS trg_webhook_employee_change ON public.employee;
+-- DROP FUNCTION IF EXISTS public.webhook_user_role_change();
+-- DROP FUNCTION IF EXISTS public.webhook_employee_change();
+-- DROP FUNCTION IF EXISTS public.enqueue_webhook_event(TEXT, JSONB);
+-- DROP TABLE IF EXISTS webhook_delivery;
+-- DROP TABLE IF EXISTS webhook;
@@ -0,0 +1,24 @@
+-- Migration: Schema version tracking
+-- Description: Records which migrations have been applied so the server can
+-- check the schema version at readiness time. Backfills 000-006; every later
+-- migration inserts its own row as its last statement.
+
+-- UP Migration
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version     INTEGER PRIMARY KEY,
+    name        VARCHAR(255) NOT NULL,
+    applied_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO schema_migrations (version, name) VALUES
+    (0, 'base_schema'),
+    (1, 'add_employee_id_to_users'),
+    (2, 'seed_initial_users'),
+    (3, 'scim_provisioning'),
+    (4, 'change_notifications'),
+    (5, 'webhooks'),
+    (6, 'schema_migrations')
+ON CONFLICT (version) DO NOTHING;
+
+-- DOWN Migration (if you need to rollback)
+-- DROP TABLE IF EXISTS schema_migrations;
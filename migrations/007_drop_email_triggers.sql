@@ -0,0 +1,21 @@
+-- Migration: Drop the email triggers
+-- Description: Email sync between employee and users, and the duplicate-email
+-- check, are done by the application (server::directory) so they behave the same on
+-- PostgreSQL and SQLite. Duplicates are still rejected by the unique index on
+-- users.email. Rolling back only removes the bookkeeping; the application
+-- keeps syncing either way, and migrations/triggers.sql restores the triggers.
+
+-- UP Migration
+DROP TRIGGER IF EXISTS trg_sync_employee_email ON public.employee;
+DROP TRIGGER IF EXISTS trg_sync_user_email ON public.users;
+DROP TRIGGER IF EXISTS trg_prevent_duplicate_user_emails ON public.users;
+
+DROP FUNCTION IF EXISTS public.sync_employee_email_to_users();
+DROP FUNCTION IF EXISTS public.sync_user_email_to_employee();
+DROP FUNCTION IF EXISTS public.prevent_duplicate_user_emails();
+
+INSERT INTO schema_migrations (version, name) VALUES (7, 'drop_email_triggers')
+ON CONFLICT (version) DO NOTHING;
+
+-- DOWN Migration (if you need to rollback)
+-- SELECT 1;
@@ -0,0 +1,14 @@
+-- Migration: Per-user interface language
+-- Description: The language chosen with the switcher in the header, as a
+-- code such as 'en' or 'de'. NULL means the user never picked one and the
+-- browser's Accept-Language decides.
+
+-- UP Migration
+ALTER TABLE users
+ADD COLUMN IF NOT EXISTS locale VARCHAR(5);
+
+INSERT INTO schema_migrations (version, name) VALUES (8, 'user_locale')
+ON CONFLICT (version) DO NOTHING;
+
+-- DOWN Migration (if you need to rollback)
+-- ALTER TABLE users DROP COLUMN IF EXISTS locale;
@@ -0,0 +1,13 @@
+-- Migration: Per-user color theme
+-- Description: The theme chosen with the toggle in the header: 'light',
+-- 'dark' or 'system'. NULL means the user never picked one.
+
+-- UP Migration
+ALTER TABLE users
+ADD COLUMN IF NOT EXISTS theme VARCHAR(6);
+
+INSERT INTO schema_migrations (version, name) VALUES (9, 'user_theme')
+ON CONFLICT (version) DO NOTHING;
+
+-- DOWN Migration (if you need to rollback)
+-- ALTER TABLE users DROP COLUMN IF EXISTS theme;
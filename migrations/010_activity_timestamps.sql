@@ -0,0 +1,46 @@
+-- Migration: Activity timestamps
+-- Description: Keeps employee.updated_at and users.updated_at current and
+-- records each user's last successful login, for the dashboard's recent
+-- changes and recent logins.
+
+-- UP Migration
+CREATE OR REPLACE FUNCTION public.touch_updated_at()
+RETURNS trigger
+LANGUAGE plpgsql
+AS $$
+BEGIN
+    NEW.updated_at := NOW();
+    RETURN NEW;
+END;
+$$;
+
+DROP TRIGGER IF EXISTS trg_touch_employee ON public.employee;
+CREATE TRIGGER trg_touch_employee
+BEFORE UPDATE ON public.employee
+FOR EACH ROW
+EXECUTE FUNCTION public.touch_updated_at();
+
+DROP TRIGGER IF EXISTS trg_touch_users ON public.users;
+CREATE TRIGGER trg_touch_users
+BEFORE UPDATE ON public.users
+FOR EACH ROW
+EXECUTE FUNCTION public.touch_updated_at();
+
+ALTER TABLE users
+ADD COLUMN IF NOT EXISTS last_login_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_employee_created_at ON employee(created_at);
+CREATE INDEX IF NOT EXISTS idx_employee_updated_at ON employee(updated_at);
+CREATE INDEX IF NOT EXISTS idx_users_last_login_at ON users(last_login_at);
+
+INSERT INTO schema_migrations (version, name) VALUES (10, 'activity_timestamps')
+ON CONFLICT (version) DO NOTHING;
+
+-- DOWN Migration (if you need to rollback)
+-- DROP INDEX IF EXISTS idx_users_last_login_at;
+-- DROP INDEX IF EXISTS idx_employee_updated_at;
+-- DROP INDEX IF EXISTS idx_employee_created_at;
+-- ALTER TABLE users DROP COLUMN IF EXISTS last_login_at;
+-- DROP TRIGGER IF EXISTS trg_touch_users ON public.users;
+-- DROP TRIGGER IF EXISTS trg_touch_employee ON public.employee;
+-- DROP FUNCTION IF EXISTS public.touch_updated_at();
@@ -0,0 +1,40 @@
+-- Migration: Role permissions
+-- Description: Maps roles to the permissions the application checks
+-- (`employee.read`, `user.manage`, ...), so roles can be created and edited
+-- at runtime instead of being hard-coded. Grants the reference roles what
+-- their names allowed before (see DEFAULT_PERMISSIONS in src/server/auth.rs).
+
+-- UP Migration
+CREATE TABLE IF NOT EXISTS role_permission (
+    role_id INT NOT NULL REFERENCES app_role(id) ON DELETE CASCADE,
+    permission VARCHAR(50) NOT NULL,
+    PRIMARY KEY (role_id, permission)
+);
+
+INSERT INTO role_permission (role_id, permission)
+SELECT r.id, p.permission
+FROM app_role r
+JOIN (VALUES
+    ('ROLE_ADMIN', 'employee.read'),
+    ('ROLE_ADMIN', 'employee.write'),
+    ('ROLE_ADMIN', 'user.read'),
+    ('ROLE_ADMIN', 'user.manage'),
+    ('ROLE_ADMIN', 'role.manage'),
+    ('ROLE_ADMIN', 'webhook.manage'),
+    ('ROLE_ADMIN', 'audit.read'),
+    ('ROLE_MANAGER', 'employee.read'),
+    ('ROLE_HR', 'employee.read'),
+    ('ROLE_HR', 'employee.write'),
+    ('ROLE_HR', 'user.read'),
+    ('ROLE_IT', 'employee.read'),
+    ('ROLE_IT', 'user.read'),
+    ('ROLE_IT', 'user.manage'),
+    ('ROLE_EMPLOYEE', 'employee.read')
+) AS p (role, permission) ON p.role = r.name
+ON CONFLICT (role_id, permission) DO NOTHING;
+
+INSERT INTO schema_migrations (version, name) VALUES (11, 'role_permissions')
+ON CONFLICT (version) DO NOTHING;
+
+-- DOWN Migration (if you need to rollback)
+-- DROP TABLE IF EXISTS role_permission;
@@ -0,0 +1,17 @@
+-- Migration: User deactivation
+-- Description: When a user account was last deactivated. Sessions issued
+-- before that moment stay revoked, even after the account is reactivated.
+-- Accounts that are already inactive get the time of this migration.
+
+-- UP Migration
+ALTER TABLE users
+ADD COLUMN IF NOT EXISTS deactivated_at TIMESTAMP WITH TIME ZONE;
+
+UPDATE users SET deactivated_at = NOW()
+WHERE NOT is_active AND deactivated_at IS NULL;
+
+INSERT INTO schema_migrations (version, name) VALUES (12, 'user_deactivation')
+ON CONFLICT (version) DO NOTHING;
+
+-- DOWN Migration (if you need to rollback)
+-- ALTER TABLE users DROP COLUMN IF EXISTS deactivated_at;
@@ -0,0 +1,35 @@
+-- Migration: Unique user per employee
+-- Description: An employee has at most one user account. The application
+-- checks this before linking, but two requests linking the same employee at
+-- once could both pass that check; the constraint stops the second one.
+-- Should duplicates already exist, the oldest account keeps the link and the
+-- others are unlinked.
+
+-- UP Migration
+UPDATE users u SET employee_id = NULL
+WHERE employee_id IS NOT NULL
+  AND EXISTS (
+    SELECT 1 FROM users older
+    WHERE older.employee_id = u.employee_id AND older.id < u.id
+  );
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint WHERE conname = 'unique_users_employee_id'
+    ) THEN
+        ALTER TABLE users
+        ADD CONSTRAINT unique_users_employee_id UNIQUE (employee_id);
+    END IF;
+END;
+$$;
+
+-- The constraint's own index replaces the plain one from 001
+DROP INDEX IF EXISTS idx_users_employee_id;
+
+INSERT INTO schema_migrations (version, name) VALUES (13, 'unique_user_employee')
+ON CONFLICT (version) DO NOTHING;
+
+-- DOWN Migration (if you need to rollback)
+-- ALTER TABLE users DROP CONSTRAINT IF EXISTS unique_users_employee_id;
+-- CREATE INDEX IF NOT EXISTS idx_users_employee_id ON users(employee_id);
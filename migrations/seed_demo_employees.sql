@@ -0,0 +1,11 @@
+-- Seed Script: Demo employees
+-- Description: Sample employees for development; run by `rfc-admin seed --demo`
+-- before 002_seed_initial_users.sql links the demo accounts to them.
+
+INSERT INTO employee (first_name, last_name, email) VALUES
+    ('Leslie', 'Andrews', 'leslie@luv2code.com'),
+    ('Emma', 'Baumgarten', 'emma@luv2code.com'),
+    ('Avani', 'Gupta', 'avani@luv2code.com'),
+    ('Yuri', 'Petrov', 'yuri@luv2code.com'),
+    ('Juan', 'Vega', 'juan@luv2code.com')
+ON CONFLICT (email) DO NOTHING;
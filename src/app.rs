@@ -1,45 +1,77 @@
 #![allow(non_snake_case)]
+use crate::components::toast::{ToastStack, Toasts};
 use crate::components::{
-    employees::Employees, 
-    footer::Footer, 
-    header::Header, 
-    home::Home, 
-    users::Users,
-    login::Login,
+    employee_detail::EmployeeDetail, employees::Employees, footer::Footer, header::Header,
+    home::Home, login::Login, roles::Roles, users::Users, webhooks::Webhooks,
 };
+use crate::i18n::{I18n, Locale};
+use crate::server::auth::{current_locale, current_session, current_theme};
+use crate::server::state::AuthState;
+use crate::theme::{Theme, ThemeState};
 use dioxus::prelude::*;
 use dioxus_router::{Routable, Router};
-use crate::server::state::AuthState;
 
 #[derive(Routable, Clone)]
 pub enum Route {
     #[layout(Layout)]
     #[route("/")]
-    
     Home {},
-    
+
     #[route("/employees")]
     Employees {},
-    
+
+    #[route("/employees/:id")]
+    EmployeeDetail { id: i32 },
+
     #[route("/users")]
     Users {},
-    
+
+    #[route("/roles")]
+    Roles {},
+
+    #[route("/webhooks")]
+    Webhooks {},
+
     #[route("/login")]
     Login {},
-
 }
 
 #[component]
 pub fn App() -> Element {
-    let logged_in = use_signal(|| false);
-    
-    use_context_provider(|| AuthState { logged_in });
-    
+    let mut user = use_signal(|| None);
+    let mut locale = use_signal(Locale::default);
+    let mut theme = use_signal(Theme::default);
+
+    use_context_provider(|| AuthState { user });
+    use_context_provider(Toasts::new);
+    use_context_provider(|| I18n { locale });
+    use_context_provider(|| ThemeState { theme });
+
+    // Pick up a session cookie that survived a reload, the language and
+    // the theme
+    use_future(move || async move {
+        if let Ok(Some(session)) = current_session().await {
+            user.set(Some(session));
+        }
+        if let Ok(saved) = current_locale().await {
+            locale.set(saved);
+        }
+        if let Ok(saved) = current_theme().await {
+            theme.set(saved);
+        }
+    });
+
     rsx! {
         document::Link { rel: "stylesheet", href: asset!("assets/tailwind.css") }
         document::Link { rel: "icon", href: asset!("assets/favicon.ico") }
 
-        Router::<Route> {}
+        // `dark:` classes key off `data-theme` (see tailwind.css)
+        div {
+            "data-theme": theme().code(),
+            class: "dark:[color-scheme:dark]",
+            Router::<Route> {}
+            ToastStack {}
+        }
     }
 }
 
@@ -47,7 +79,7 @@ pub fn App() -> Element {
 fn Layout() -> Element {
     rsx! {
         div {
-            class: "bg-gray-100 text-gray-800 min-h-screen flex flex-col",
+            class: "bg-gray-100 text-gray-800 dark:bg-gray-900 dark:text-gray-100 min-h-screen flex flex-col",
 
             Header {}
 
@@ -0,0 +1,587 @@
+//! `rfc-admin`: bootstrap and maintenance for an employee directory instance
+//!
+//! Uses the same configuration, entities and helpers as the web server.
+//! Build with `cargo run --features server --bin rfc-admin -- <COMMAND>`.
+
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use anyhow::{Context, bail};
+use clap::{Parser, Subcommand};
+use sea_orm::*;
+use serde::Deserialize;
+
+use rust_fullstack_core::entities::{employee, users};
+use rust_fullstack_core::server::config::{self, ConfigArgs};
+use rust_fullstack_core::server::db::{insert_employee, modify_employee};
+use rust_fullstack_core::server::db_connection::get_db;
+use rust_fullstack_core::server::directory::DirectoryError;
+use rust_fullstack_core::server::health::{EXPECTED_SCHEMA_VERSION, relation_exists};
+use rust_fullstack_core::server::{auth, bulk, directory, migrate};
+use rust_fullstack_core::validation;
+
+#[derive(Debug, Parser)]
+#[command(name = "rfc-admin", about = "Employee directory administration")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Apply, roll back or inspect schema migrations
+    #[command(subcommand)]
+    Migrate(MigrateCommand),
+
+    /// Insert reference roles; with --demo also sample employees and accounts
+    Seed {
+        /// Add the demo employees and the password123 demo accounts
+        #[arg(long)]
+        demo: bool,
+    },
+
+    /// Manage login accounts
+    #[command(subcommand)]
+    User(UserCommand),
+
+    /// Bulk employee data as CSV (first_name,last_name,email)
+    #[command(subcommand)]
+    Employee(EmployeeCommand),
+
+    /// Database diagnostics
+    #[command(subcommand)]
+    Db(DbCommand),
+}
+
+#[derive(Debug, Subcommand)]
+enum MigrateCommand {
+    /// Apply all pending migrations
+    Up,
+    /// Roll back the newest migrations
+    Down {
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+    },
+    /// List migrations and whether they are applied
+    Status,
+    /// Mark migrations up to VERSION as applied without running them
+    Baseline { version: i32 },
+}
+
+#[derive(Debug, Subcommand)]
+enum UserCommand {
+    /// Create an account; prints a generated password unless --password-stdin
+    Create {
+        #[arg(long)]
+        email: String,
+        /// Role name, e.g. ROLE_ADMIN or admin
+        #[arg(long)]
+        role: String,
+        /// Link the account to this employee
+        #[arg(long)]
+        employee_id: Option<i32>,
+        /// Read the password from the first line of stdin
+        #[arg(long)]
+        password_stdin: bool,
+    },
+    /// Set a new password; prints a generated one unless --password-stdin
+    ResetPassword {
+        #[arg(long)]
+        email: String,
+        #[arg(long)]
+        password_stdin: bool,
+    },
+    /// Reactivate a deactivated account
+    Unlock {
+        #[arg(long)]
+        email: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum EmployeeCommand {
+    /// Insert or update employees by email, all or nothing
+    Import { file: PathBuf },
+    /// Write all employees; to stdout without a file
+    Export { file: Option<PathBuf> },
+}
+
+#[derive(Debug, Subcommand)]
+enum DbCommand {
+    /// Compare the live schema with the migrations and entities
+    Check,
+}
+
+/// One CSV row; `export` adds an `id` column that `import` ignores
+#[derive(Debug, Deserialize)]
+struct EmployeeRow {
+    first_name: String,
+    last_name: String,
+    email: String,
+}
+
+/// Length of generated passwords, in hex characters
+const GENERATED_PASSWORD_LEN: usize = 20;
+
+const MIN_PASSWORD_LEN: usize = 8;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    dotenvy::dotenv().ok();
+    let cli = Cli::parse();
+    config::load_or_exit(&cli.config);
+
+    match run(cli.command).await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(command: Command) -> anyhow::Result<ExitCode> {
+    let db = get_db().await.context("cannot connect to the database")?;
+
+    match command {
+        Command::Migrate(cmd) => run_migrate(&db, cmd).await?,
+        Command::Seed { demo } => {
+            migrate::seed(&db, demo).await?;
+            println!(
+                "Seeded roles{}",
+                if demo {
+                    ", demo employees and demo accounts"
+                } else {
+                    ""
+                }
+            );
+            if demo {
+                println!(
+                    "Demo accounts use the password 'password123'; do not use --demo in production."
+                );
+            }
+        }
+        Command::User(cmd) => run_user(&db, cmd).await?,
+        Command::Employee(EmployeeCommand::Import { file }) => import_employees(&db, &file).await?,
+        Command::Employee(EmployeeCommand::Export { file }) => export_employees(&db, file).await?,
+        Command::Db(DbCommand::Check) => return db_check(&db).await,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+//
+// -------------------- MIGRATE --------------------
+//
+
+async fn run_migrate(db: &DatabaseConnection, cmd: MigrateCommand) -> anyhow::Result<()> {
+    match cmd {
+        MigrateCommand::Up => {
+            let applied = migrate::up(db).await?;
+            if migrate::is_sqlite(db) {
+                println!("Created any missing tables from the entities");
+            } else if applied.is_empty() {
+                println!("Already at version {:03}", migrate::LATEST_VERSION);
+            }
+            for version in applied {
+                let m = migrate::find(version).expect("applied migration is embedded");
+                let note = match m.kind {
+                    migrate::MigrationKind::Schema => "",
+                    migrate::MigrationKind::DemoData => {
+                        " (recorded only; run `seed --demo` for the data)"
+                    }
+                };
+                println!("Applied {:03}_{}{}", m.version, m.name, note);
+            }
+        }
+        MigrateCommand::Down { steps } => {
+            for version in migrate::down(db, steps).await? {
+                println!("Rolled back {:03}", version);
+            }
+        }
+        MigrateCommand::Status => {
+            if migrate::is_sqlite(db) {
+                println!("SQLite: the schema is created from the entities; nothing is tracked");
+                return Ok(());
+            }
+            let applied = migrate::applied(db).await?;
+            if applied.is_none() {
+                println!("schema_migrations does not exist: nothing is tracked yet");
+            }
+            let applied = applied.unwrap_or_default();
+            for m in migrate::MIGRATIONS {
+                match applied.iter().find(|a| a.version == m.version) {
+                    Some(a) => println!("{:03}_{:<28} applied {}", m.version, m.name, a.applied_at),
+                    None => println!("{:03}_{:<28} pending", m.version, m.name),
+                }
+            }
+            for a in applied
+                .iter()
+                .filter(|a| migrate::find(a.version).is_none())
+            {
+                println!(
+                    "{:03}_{:<28} applied, but unknown to this build",
+                    a.version, a.name
+                );
+            }
+        }
+        MigrateCommand::Baseline { version } => {
+            migrate::baseline(db, version).await?;
+            println!("Marked migrations up to {:03} as applied", version);
+        }
+    }
+    Ok(())
+}
+
+//
+// -------------------- USERS --------------------
+//
+
+async fn run_user(db: &DatabaseConnection, cmd: UserCommand) -> anyhow::Result<()> {
+    match cmd {
+        UserCommand::Create {
+            email,
+            role,
+            employee_id,
+            password_stdin,
+        } => {
+            let email = email.trim().to_string();
+            let errors = validation::user(&email);
+            if !errors.is_empty() {
+                bail!("{}", errors);
+            }
+            let role_name = normalize_role(&role);
+
+            let (password, generated) = obtain_password(password_stdin)?;
+            let password_hash = auth::hash_password(password.clone()).await?;
+            // The same rules as the Users page; the CLI holds every permission
+            let user = directory::create_user(
+                db,
+                &directory::full_rights(),
+                email,
+                &role_name,
+                employee_id,
+                password_hash,
+            )
+            .await
+            .map_err(|e| match e {
+                DirectoryError::RoleNotFound(_) => {
+                    anyhow::anyhow!("unknown role {}; run `rfc-admin seed` first?", role_name)
+                }
+                e => e.into(),
+            })?;
+
+            println!(
+                "Created user {} ({}) with role {}",
+                user.id, user.email, role_name
+            );
+            if generated {
+                println!("Password: {}", password);
+            }
+        }
+        UserCommand::ResetPassword {
+            email,
+            password_stdin,
+        } => {
+            let user = find_user(db, &email)
+                .await?
+                .with_context(|| format!("no user with email {}", email))?;
+            let (password, generated) = obtain_password(password_stdin)?;
+
+            let password_hash = auth::hash_password(password.clone()).await?;
+            let user = directory::set_password(db, user.id, password_hash).await?;
+
+            println!("Password reset for {}", user.email);
+            if generated {
+                println!("Password: {}", password);
+            }
+        }
+        UserCommand::Unlock { email } => {
+            let user = find_user(db, &email)
+                .await?
+                .with_context(|| format!("no user with email {}", email))?;
+            if user.is_active {
+                println!("{} is already active", user.email);
+                return Ok(());
+            }
+
+            let mut model: users::ActiveModel = user.into();
+            directory::set_active(&mut model, true);
+            let user = model.update(db).await?;
+            println!("Reactivated {}", user.email);
+        }
+    }
+    Ok(())
+}
+
+async fn find_user(db: &DatabaseConnection, email: &str) -> Result<Option<users::Model>, DbErr> {
+    users::Entity::find()
+        .filter(users::Column::Email.eq(email.trim()))
+        .one(db)
+        .await
+}
+
+/// `admin` and `role_admin` both mean `ROLE_ADMIN`
+fn normalize_role(role: &str) -> String {
+    let upper = role.trim().to_uppercase();
+    if upper.starts_with("ROLE_") {
+        upper
+    } else {
+        format!("ROLE_{}", upper)
+    }
+}
+
+/// The password to set, and whether it was generated (and must be shown)
+fn obtain_password(from_stdin: bool) -> anyhow::Result<(String, bool)> {
+    if !from_stdin {
+        let mut password = auth::random_password();
+        password.truncate(GENERATED_PASSWORD_LEN);
+        return Ok((password, true));
+    }
+
+    let mut line = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .context("cannot read the password from stdin")?;
+    let password = line.trim_end_matches(['\r', '\n']).to_string();
+    if password.len() < MIN_PASSWORD_LEN {
+        bail!(
+            "the password must be at least {} characters",
+            MIN_PASSWORD_LEN
+        );
+    }
+    Ok((password, false))
+}
+
+//
+// -------------------- EMPLOYEES --------------------
+//
+
+async fn import_employees(db: &DatabaseConnection, file: &Path) -> anyhow::Result<()> {
+    let mut reader =
+        csv::Reader::from_path(file).with_context(|| format!("cannot open {}", file.display()))?;
+
+    let mut rows = Vec::new();
+    for (index, record) in reader.deserialize::<EmployeeRow>().enumerate() {
+        // Line 1 is the header
+        let line = index + 2;
+        let row = record.with_context(|| format!("line {}", line))?;
+        let row = EmployeeRow {
+            first_name: row.first_name.trim().to_string(),
+            last_name: row.last_name.trim().to_string(),
+            email: row.email.trim().to_string(),
+        };
+        let errors = validation::employee(&row.first_name, &row.last_name, &row.email);
+        if !errors.is_empty() {
+            bail!("line {}: {}", line, errors);
+        }
+        rows.push((line, row));
+    }
+
+    let txn = db.begin().await?;
+    let (mut created, mut updated) = (0, 0);
+    for (line, row) in rows {
+        let existing = employee::Entity::find()
+            .filter(employee::Column::Email.eq(&row.email))
+            .one(&txn)
+            .await?;
+
+        let result = match existing {
+            Some(e) => {
+                updated += 1;
+                modify_employee(&txn, e.id, row.first_name, row.last_name, row.email).await
+            }
+            None => {
+                created += 1;
+                insert_employee(&txn, row.first_name, row.last_name, row.email).await
+            }
+        };
+        // Dropping the transaction rolls everything back
+        result.map_err(|e| anyhow::anyhow!("line {}: {}", line, e))?;
+    }
+    txn.commit().await?;
+
+    println!(
+        "Imported {}: {} created, {} updated",
+        file.display(),
+        created,
+        updated
+    );
+    Ok(())
+}
+
+async fn export_employees(db: &DatabaseConnection, file: Option<PathBuf>) -> anyhow::Result<()> {
+    let employees = employee::Entity::find()
+        .order_by_asc(employee::Column::Id)
+        .all(db)
+        .await?;
+
+    let out: Box<dyn Write> = match &file {
+        Some(path) => Box::new(
+            std::fs::File::create(path)
+                .with_context(|| format!("cannot create {}", path.display()))?,
+        ),
+        None => Box::new(std::io::stdout().lock()),
+    };
+
+    bulk::write_csv(out, &employees)?;
+
+    if let Some(path) = file {
+        println!(
+            "Exported {} employees to {}",
+            employees.len(),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+//
+// -------------------- DB CHECK --------------------
+//
+
+async fn db_check(db: &DatabaseConnection) -> anyhow::Result<ExitCode> {
+    let mut failed = false;
+
+    if migrate::is_sqlite(db) {
+        println!("note  SQLite: no schema_migrations or users_extended to check");
+    } else {
+        failed |= tracked_schema_check(db).await?;
+    }
+
+    let drift = migrate::drift(db).await?;
+    for d in &drift {
+        if d.is_error() {
+            failed = true;
+            println!("FAIL  {}", d);
+        } else {
+            println!("note  {}", d);
+        }
+    }
+    if !drift.iter().any(migrate::Drift::is_error) {
+        println!("ok    every entity column exists");
+    }
+
+    Ok(if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Version and view checks for a migrated PostgreSQL database; true on failure
+async fn tracked_schema_check(db: &DatabaseConnection) -> anyhow::Result<bool> {
+    let mut failed = false;
+
+    match migrate::current_version(db).await? {
+        Some(v) if v == EXPECTED_SCHEMA_VERSION => {
+            println!("ok    schema at version {:03}", v);
+        }
+        Some(v) => {
+            failed = true;
+            println!(
+                "FAIL  schema at version {:03}, expected {:03}",
+                v, EXPECTED_SCHEMA_VERSION
+            );
+        }
+        None => {
+            failed = true;
+            println!("FAIL  schema_migrations is missing; run `migrate up` or `migrate baseline`");
+        }
+    }
+
+    if relation_exists(db, "public.users_extended").await? {
+        println!("ok    view users_extended exists");
+    } else {
+        failed = true;
+        println!("FAIL  view users_extended is missing");
+    }
+
+    Ok(failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+    }
+
+    /// A fresh in-memory database, with the configuration pointing at it
+    async fn sqlite() -> DatabaseConnection {
+        config::load_or_exit(&ConfigArgs {
+            database_url: Some("sqlite::memory:".into()),
+            ..Default::default()
+        });
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1);
+        let db = Database::connect(options).await.expect("SQLite opens");
+        migrate::create_sqlite_schema(&db)
+            .await
+            .expect("schema is created");
+        db
+    }
+
+    fn create(email: &str, employee_id: Option<i32>) -> UserCommand {
+        UserCommand::Create {
+            email: email.into(),
+            role: "hr".into(),
+            employee_id,
+            password_stdin: false,
+        }
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "sqlite"), ignore = "requires the sqlite feature")]
+    async fn user_create_follows_the_directory_rules() {
+        let db = sqlite().await;
+        let ada = directory::insert_employee(
+            &db,
+            "Ada".into(),
+            "Lovelace".into(),
+            "ada@example.com".into(),
+        )
+        .await
+        .unwrap();
+
+        let err = run_user(&db, create("not-an-email", None))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("email"), "{}", err);
+
+        run_user(&db, create("lovelace@example.com", Some(ada.id)))
+            .await
+            .unwrap();
+        // The linked employee took the account's email
+        let ada = employee::Entity::find_by_id(ada.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ada.email.as_deref(), Some("lovelace@example.com"));
+
+        let err = run_user(&db, create("ada@example.com", Some(ada.id)))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("already has a user account"),
+            "{}",
+            err
+        );
+        assert_eq!(users::Entity::find().count(&db).await.unwrap(), 1);
+    }
+
+    #[test]
+    fn role_names_are_normalized() {
+        assert_eq!(normalize_role("admin"), "ROLE_ADMIN");
+        assert_eq!(normalize_role(" role_hr "), "ROLE_HR");
+        assert_eq!(normalize_role("ROLE_IT"), "ROLE_IT");
+    }
+}
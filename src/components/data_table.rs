@@ -0,0 +1,562 @@
+//! Generic table for the list pages
+//!
+//! A `DataTable` is driven by `Column` definitions: header, cell renderer,
+//! optional sort key and width. It owns the loading / error / empty states,
+//! sorting by clicking a header, the column picker and, when the page passes
+//! `bulk_actions`, row checkboxes with an action bar for the selection.
+//! Sorting, hidden columns and the selection live in the table and reset
+//! when the page is left. Headers, action labels and the status messages
+//! are catalog keys, translated by the table.
+
+use std::collections::{BTreeSet, HashSet};
+use std::rc::Rc;
+
+use crate::components::live::Highlights;
+use crate::i18n::use_i18n;
+use dioxus::prelude::*;
+
+pub const TH_CLASS: &str = "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider bg-gray-50 dark:text-gray-300 dark:bg-gray-700";
+pub const TD_CLASS: &str = "px-6 py-4 whitespace-nowrap text-sm text-gray-900 dark:text-gray-100";
+
+/// A row type the table can key, highlight and select
+pub trait TableRow: Clone + PartialEq + 'static {
+    fn row_id(&self) -> i32;
+}
+
+/// Value a column sorts by. Rows without a value sort first.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SortKey {
+    Missing,
+    Number(i64),
+    Text(String),
+}
+
+impl SortKey {
+    /// Case-insensitive text, `Missing` for `None`
+    pub fn text(value: Option<&str>) -> Self {
+        match value {
+            Some(value) => Self::Text(value.to_lowercase()),
+            None => Self::Missing,
+        }
+    }
+}
+
+impl From<i32> for SortKey {
+    fn from(value: i32) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+/// Maps a row to the value its column sorts by
+type SortKeyFn<T> = Rc<dyn Fn(&T) -> SortKey>;
+
+pub struct Column<T> {
+    /// Stable id for sorting and the column picker
+    pub key: &'static str,
+    /// Catalog key of the header text
+    pub header: &'static str,
+    cell: Rc<dyn Fn(&T) -> Element>,
+    sort_key: Option<SortKeyFn<T>>,
+    /// Tailwind width class, e.g. `w-16`
+    width: Option<&'static str>,
+}
+
+impl<T> Column<T> {
+    pub fn new(
+        key: &'static str,
+        header: &'static str,
+        cell: impl Fn(&T) -> Element + 'static,
+    ) -> Self {
+        Self {
+            key,
+            header,
+            cell: Rc::new(cell),
+            sort_key: None,
+            width: None,
+        }
+    }
+
+    /// Make the column sortable by `key`
+    pub fn sort_by(mut self, key: impl Fn(&T) -> SortKey + 'static) -> Self {
+        self.sort_key = Some(Rc::new(key));
+        self
+    }
+
+    pub fn width(mut self, class: &'static str) -> Self {
+        self.width = Some(class);
+        self
+    }
+}
+
+impl<T> Clone for Column<T> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key,
+            header: self.header,
+            cell: self.cell.clone(),
+            sort_key: self.sort_key.clone(),
+            width: self.width,
+        }
+    }
+}
+
+impl<T> PartialEq for Column<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+            && self.header == other.header
+            && self.width == other.width
+            && Rc::ptr_eq(&self.cell, &other.cell)
+            && match (&self.sort_key, &other.sort_key) {
+                (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+
+/// A button shown while rows are selected; receives the selected row ids,
+/// which stay selected until the page clears them
+#[derive(Clone, PartialEq)]
+pub struct BulkAction {
+    /// Catalog key of the button text
+    pub label: &'static str,
+    pub danger: bool,
+    pub on_run: EventHandler<Vec<i32>>,
+}
+
+/// Current sort: column key and whether it is ascending
+type Sort = Option<(&'static str, bool)>;
+
+/// `rows` in the order of `sort`; unsortable or unknown columns keep the
+/// server's order
+fn sort_rows<'a, T>(rows: &'a [T], columns: &[Column<T>], sort: Sort) -> Vec<&'a T> {
+    let mut ordered: Vec<&T> = rows.iter().collect();
+    let Some((key, ascending)) = sort else {
+        return ordered;
+    };
+    let Some(sort_key) = columns
+        .iter()
+        .find(|c| c.key == key)
+        .and_then(|c| c.sort_key.as_ref())
+    else {
+        return ordered;
+    };
+
+    ordered.sort_by_cached_key(|row| sort_key(row));
+    if !ascending {
+        ordered.reverse();
+    }
+    ordered
+}
+
+/// Direction marker for the header of column `key`
+fn sort_arrow(sort: Sort, key: &str) -> &'static str {
+    match sort {
+        Some((k, true)) if k == key => " ▲",
+        Some((k, false)) if k == key => " ▼",
+        _ => "",
+    }
+}
+
+/// Clicking a header sorts by it ascending, then toggles the direction
+fn next_sort(current: Sort, key: &'static str) -> Sort {
+    match current {
+        Some((k, ascending)) if k == key => Some((key, !ascending)),
+        _ => Some((key, true)),
+    }
+}
+
+#[component]
+pub fn DataTable<T: TableRow>(
+    /// `None` while loading, then the server function result
+    rows: Option<Result<Vec<T>, String>>,
+    columns: Vec<Column<T>>,
+    /// Catalog prefix of the status messages: `<noun>.loading`,
+    /// `<noun>.load_error` and `<noun>.empty`
+    noun: &'static str,
+    highlights: Highlights,
+    #[props(default)] bulk_actions: Vec<BulkAction>,
+    /// The selected row ids, for pages whose bulk actions ask first: the
+    /// table never clears it, the page does once the action went through.
+    /// Without it the table keeps its own.
+    #[props(default)]
+    selection: Option<Signal<BTreeSet<i32>>>,
+) -> Element {
+    let mut sort = use_signal(|| None::<(&'static str, bool)>);
+    let mut hidden = use_signal(HashSet::<&'static str>::new);
+    let mut show_picker = use_signal(|| false);
+    let own_selection = use_signal(BTreeSet::<i32>::new);
+    let mut selected = selection.unwrap_or(own_selection);
+    let i18n = use_i18n();
+
+    let list = match rows {
+        None => {
+            return rsx! {
+                div {
+                    class: "flex justify-center items-center py-12",
+                    p { class: "text-gray-600 dark:text-gray-300", {i18n.t(&format!("{}.loading", noun))} }
+                }
+            };
+        }
+        Some(Err(err)) => {
+            return rsx! {
+                div {
+                    class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded dark:bg-red-900/40 dark:border-red-700 dark:text-red-200",
+                    {i18n.tr(&format!("{}.load_error", noun), &[("error", err)])}
+                }
+            };
+        }
+        Some(Ok(list)) => list,
+    };
+
+    let visible: Vec<Column<T>> = columns
+        .iter()
+        .filter(|c| !hidden.read().contains(c.key))
+        .cloned()
+        .collect();
+    let ordered: Vec<T> = sort_rows(&list, &columns, sort())
+        .into_iter()
+        .cloned()
+        .collect();
+
+    // Rows that disappeared (deleted elsewhere) drop out of the selection
+    let ids: Vec<i32> = list.iter().map(TableRow::row_id).collect();
+    let chosen: Vec<i32> = ids
+        .iter()
+        .copied()
+        .filter(|id| selected.read().contains(id))
+        .collect();
+    let selectable = !bulk_actions.is_empty();
+    let all_selected = !ids.is_empty() && chosen.len() == ids.len();
+
+    rsx! {
+        div {
+            class: "bg-white shadow-md rounded-lg dark:bg-gray-800",
+
+            // Toolbar: bulk actions on the left, column picker on the right
+            div {
+                class: "flex justify-between items-center px-4 py-2 border-b border-gray-200 text-sm dark:border-gray-700",
+                div {
+                    class: "flex items-center gap-3",
+                    if !chosen.is_empty() {
+                        span { class: "text-gray-600 dark:text-gray-300", {i18n.plural("table.selected", chosen.len(), &[])} }
+                        for action in bulk_actions.iter().cloned() {
+                            button {
+                                class: if action.danger {
+                                    "px-3 py-1 rounded-md text-white bg-red-600 hover:bg-red-700"
+                                } else {
+                                    "px-3 py-1 rounded-md text-white bg-blue-600 hover:bg-blue-700"
+                                },
+                                onclick: {
+                                    let chosen = chosen.clone();
+                                    let on_run = action.on_run;
+                                    move |_| on_run.call(chosen.clone())
+                                },
+                                {i18n.t(action.label)}
+                            }
+                        }
+                    }
+                }
+                div {
+                    class: "relative",
+                    button {
+                        class: "px-3 py-1 text-gray-700 bg-gray-100 rounded-md hover:bg-gray-200 dark:text-gray-200 dark:bg-gray-700 dark:hover:bg-gray-600",
+                        onclick: move |_| show_picker.set(!show_picker()),
+                        {i18n.t("table.columns")}
+                    }
+                    if show_picker() {
+                        div {
+                            class: "absolute right-0 mt-2 w-48 bg-white border border-gray-200 rounded-md shadow-lg p-2 z-20 dark:bg-gray-800 dark:border-gray-700",
+                            for column in columns.iter() {
+                                label {
+                                    key: "{column.key}",
+                                    class: "flex items-center gap-2 px-2 py-1 text-gray-700 dark:text-gray-200",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: !hidden.read().contains(column.key),
+                                        // Keep at least one column
+                                        disabled: visible.len() == 1 && !hidden.read().contains(column.key),
+                                        onchange: {
+                                            let key = column.key;
+                                            move |_| {
+                                                let mut hidden = hidden.write();
+                                                if !hidden.remove(key) {
+                                                    hidden.insert(key);
+                                                }
+                                            }
+                                        },
+                                    }
+                                    {i18n.t(column.header)}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Scrolls under the sticky header
+            div {
+                class: "max-h-[70vh] overflow-auto",
+                table {
+                    class: "min-w-full divide-y divide-gray-200 dark:divide-gray-700",
+                    thead {
+                        class: "sticky top-0 z-10",
+                        tr {
+                            if selectable {
+                                th {
+                                    class: "{TH_CLASS} w-10",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: all_selected,
+                                        onchange: {
+                                            let ids = ids.clone();
+                                            move |_| {
+                                                if all_selected {
+                                                    selected.write().clear();
+                                                } else {
+                                                    selected.write().extend(ids.iter().copied());
+                                                }
+                                            }
+                                        },
+                                    }
+                                }
+                            }
+                            for column in visible.iter() {
+                                th {
+                                    key: "{column.key}",
+                                    class: "{TH_CLASS} {column.width.unwrap_or_default()}",
+                                    if column.sort_key.is_some() {
+                                        button {
+                                            class: "uppercase tracking-wider hover:text-gray-700 dark:hover:text-gray-100",
+                                            onclick: {
+                                                let key = column.key;
+                                                move |_| sort.set(next_sort(sort(), key))
+                                            },
+                                            {i18n.t(column.header)}
+                                            "{sort_arrow(sort(), column.key)}"
+                                        }
+                                    } else {
+                                        {i18n.t(column.header)}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    tbody {
+                        class: "bg-white divide-y divide-gray-200 dark:bg-gray-800 dark:divide-gray-700",
+                        for row in ordered.iter() {
+                            tr {
+                                key: "{row.row_id()}",
+                                class: highlights.row_class(row.row_id()),
+                                if selectable {
+                                    td {
+                                        class: "px-6 py-4",
+                                        input {
+                                            r#type: "checkbox",
+                                            checked: chosen.contains(&row.row_id()),
+                                            onchange: {
+                                                let id = row.row_id();
+                                                move |_| {
+                                                    let mut selected = selected.write();
+                                                    if !selected.remove(&id) {
+                                                        selected.insert(id);
+                                                    }
+                                                }
+                                            },
+                                        }
+                                    }
+                                }
+                                for column in visible.iter() {
+                                    td {
+                                        key: "{column.key}",
+                                        class: TD_CLASS,
+                                        {(column.cell)(row)}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if list.is_empty() {
+                div {
+                    class: "text-center py-8 text-gray-500 dark:text-gray-300",
+                    {i18n.t(&format!("{}.empty", noun))}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::live::use_highlights;
+    use crate::components::testing::render;
+    use crate::server::db::Employee;
+
+    fn employee(id: i32, last: Option<&str>) -> Employee {
+        Employee {
+            id,
+            first_name: None,
+            last_name: last.map(Into::into),
+            email: None,
+        }
+    }
+
+    fn columns() -> Vec<Column<Employee>> {
+        vec![
+            Column::new("id", "common.id", |e: &Employee| rsx! { "{e.id}" })
+                .sort_by(|e| e.id.into())
+                .width("w-16"),
+            Column::new("last", "employees.last_name", |e: &Employee| {
+                rsx! { "{e.last_name.as_deref().unwrap_or(\"-\")}" }
+            })
+            .sort_by(|e| SortKey::text(e.last_name.as_deref())),
+            Column::new("note", "Note", |_: &Employee| rsx! { "n/a" }),
+        ]
+    }
+
+    fn ids(rows: Vec<&Employee>) -> Vec<i32> {
+        rows.into_iter().map(|e| e.id).collect()
+    }
+
+    #[test]
+    fn sorts_by_the_column_key_in_both_directions() {
+        let rows = vec![
+            employee(1, Some("hopper")),
+            employee(2, None),
+            employee(3, Some("Babbage")),
+        ];
+        let columns = columns();
+
+        assert_eq!(ids(sort_rows(&rows, &columns, None)), [1, 2, 3]);
+        assert_eq!(
+            ids(sort_rows(&rows, &columns, Some(("last", true)))),
+            [2, 3, 1]
+        );
+        assert_eq!(
+            ids(sort_rows(&rows, &columns, Some(("last", false)))),
+            [1, 3, 2]
+        );
+        assert_eq!(
+            ids(sort_rows(&rows, &columns, Some(("id", false)))),
+            [3, 2, 1]
+        );
+        // Not sortable: server order
+        assert_eq!(
+            ids(sort_rows(&rows, &columns, Some(("note", true)))),
+            [1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn header_clicks_toggle_the_direction() {
+        assert_eq!(next_sort(None, "id"), Some(("id", true)));
+        assert_eq!(next_sort(Some(("id", true)), "id"), Some(("id", false)));
+        assert_eq!(next_sort(Some(("id", false)), "id"), Some(("id", true)));
+        assert_eq!(next_sort(Some(("id", false)), "last"), Some(("last", true)));
+    }
+
+    #[component]
+    fn Table(rows: Vec<Employee>, with_bulk: bool) -> Element {
+        let highlights = use_highlights();
+        let bulk_actions = if with_bulk {
+            vec![BulkAction {
+                label: "employees.delete_selected",
+                danger: true,
+                on_run: EventHandler::new(|_| {}),
+            }]
+        } else {
+            Vec::new()
+        };
+        rsx! {
+            DataTable {
+                rows: Some(Ok(rows)),
+                columns: columns(),
+                noun: "employees",
+                highlights,
+                bulk_actions,
+            }
+        }
+    }
+
+    #[test]
+    fn renders_headers_widths_and_cells() {
+        let html = render(
+            Table,
+            TableProps {
+                rows: vec![employee(1, Some("Lovelace"))],
+                with_bulk: false,
+            },
+        );
+        assert!(html.contains("w-16"));
+        assert!(html.contains("Last Name"));
+        assert!(html.contains("Lovelace"));
+        assert!(html.contains("sticky top-0"));
+        assert!(!html.contains(r#"type="checkbox""#));
+    }
+
+    #[test]
+    fn bulk_actions_add_row_checkboxes() {
+        let html = render(
+            Table,
+            TableProps {
+                rows: vec![employee(1, None), employee(2, None)],
+                with_bulk: true,
+            },
+        );
+        // One per row plus select-all
+        assert_eq!(html.matches(r#"type="checkbox""#).count(), 3);
+        // Nothing selected yet, so no action bar
+        assert!(!html.contains("Delete selected"));
+    }
+
+    #[test]
+    fn a_selection_from_the_page_shows_the_action_bar() {
+        #[component]
+        fn Selected(rows: Vec<Employee>) -> Element {
+            let highlights = use_highlights();
+            let selection = use_signal(|| BTreeSet::from([2, 99]));
+            rsx! {
+                DataTable {
+                    rows: Some(Ok(rows)),
+                    columns: columns(),
+                    noun: "employees",
+                    highlights,
+                    bulk_actions: vec![BulkAction {
+                        label: "employees.delete_selected",
+                        danger: true,
+                        on_run: EventHandler::new(|_| {}),
+                    }],
+                    selection,
+                }
+            }
+        }
+
+        let html = render(
+            Selected,
+            SelectedProps {
+                rows: vec![employee(1, None), employee(2, None)],
+            },
+        );
+        // 99 is not in the list and does not count
+        assert!(html.contains("1 selected"));
+        assert!(html.contains("Delete selected"));
+    }
+
+    #[test]
+    fn cells_carry_their_dark_variants() {
+        let html = render(
+            Table,
+            TableProps {
+                rows: vec![employee(1, Some("Lovelace"))],
+                with_bulk: false,
+            },
+        );
+        assert!(html.contains(TH_CLASS));
+        assert!(html.contains(TD_CLASS));
+        assert!(html.contains("dark:bg-gray-800"));
+    }
+}
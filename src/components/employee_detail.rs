@@ -0,0 +1,294 @@
+use crate::app::Route;
+use crate::components::employee_modal::{EmployeeModal, ModalMode};
+use crate::components::employees::{DeleteDialog, full_name};
+use crate::components::toast::use_toasts;
+use crate::i18n::use_i18n;
+use crate::server::auth::{EMPLOYEE_WRITE, USER_READ};
+use crate::server::db::{EmployeeProfile, delete_employee, get_employee_profile_server};
+use crate::server::state::AuthState;
+use dioxus::prelude::*;
+use dioxus_router::components::Link;
+
+/// `/employees/:id`. Loaded with `use_server_future`, so a fullstack build
+/// renders the record on the server and a shared link opens complete.
+#[component]
+pub fn EmployeeDetail(id: i32) -> Element {
+    let mut profile = use_server_future(use_reactive((&id,), |(id,)| async move {
+        get_employee_profile_server(id).await
+    }))?;
+    let mut editing = use_signal(|| false);
+    let mut confirm_delete = use_signal(|| false);
+    let auth = use_context::<AuthState>();
+    let toasts = use_toasts();
+    let i18n = use_i18n();
+    let nav = use_navigator();
+
+    let loaded = profile().map(|result| result.map_err(|e| e.to_string()));
+    let employee = match &loaded {
+        Some(Ok(Some(found))) => Some(found.employee.clone()),
+        _ => None,
+    };
+
+    let handle_delete = move |_| {
+        spawn(async move {
+            confirm_delete.set(false);
+            match delete_employee(id).await {
+                Ok(()) => {
+                    toasts.success(i18n.t("employee_detail.deleted"));
+                    nav.push(Route::Employees {});
+                }
+                Err(e) => toasts.error(i18n.tr("common.error", &[("error", e.to_string())])),
+            }
+        });
+    };
+
+    rsx! {
+        div {
+            class: "container mx-auto px-4 py-8 max-w-3xl",
+
+            Link {
+                to: Route::Employees {},
+                class: "text-blue-600 hover:underline dark:text-blue-400",
+                {i18n.t("employee_detail.back")}
+            }
+
+            EmployeeProfileView {
+                id,
+                profile: loaded,
+                show_account: auth.can(USER_READ),
+                can_edit: auth.can(EMPLOYEE_WRITE),
+                on_edit: move |_| editing.set(true),
+                on_delete: move |_| confirm_delete.set(true),
+            }
+        }
+
+        if let Some(employee) = employee {
+            if editing() {
+                EmployeeModal {
+                    mode: ModalMode::Edit(employee.clone()),
+                    on_close: move |_| editing.set(false),
+                    on_save: move |_| profile.restart(),
+                }
+            }
+            if confirm_delete() {
+                DeleteDialog {
+                    chosen: vec![employee.clone()],
+                    on_cancel: move |_| confirm_delete.set(false),
+                    on_confirm: handle_delete,
+                }
+            }
+        }
+    }
+}
+
+/// The profile in its loading (`None`), error, not found or loaded state
+#[component]
+pub fn EmployeeProfileView(
+    id: i32,
+    profile: Option<Result<Option<EmployeeProfile>, String>>,
+    show_account: bool,
+    can_edit: bool,
+    on_edit: EventHandler<()>,
+    on_delete: EventHandler<()>,
+) -> Element {
+    let i18n = use_i18n();
+
+    let profile = match profile {
+        None => {
+            return rsx! {
+                p { class: "py-12 text-center text-gray-600 dark:text-gray-300", {i18n.t("employee_detail.loading")} }
+            };
+        }
+        Some(Err(err)) => {
+            return rsx! {
+                div {
+                    class: "mt-6 bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded dark:bg-red-900/40 dark:border-red-700 dark:text-red-200",
+                    {i18n.tr("employee_detail.load_error", &[("error", err)])}
+                }
+            };
+        }
+        Some(Ok(None)) => {
+            return rsx! {
+                p {
+                    class: "py-12 text-center text-gray-600 dark:text-gray-300",
+                    {i18n.tr("error.employee_not_found", &[("id", id.to_string())])}
+                }
+            };
+        }
+        Some(Ok(Some(profile))) => profile,
+    };
+    let employee = &profile.employee;
+    let dash = || "-".to_string();
+
+    rsx! {
+        div {
+            class: "flex justify-between items-center mt-4 mb-6",
+            h1 {
+                class: "text-3xl font-bold text-blue-600 dark:text-blue-400",
+                {full_name(employee)}
+            }
+            if can_edit {
+                div {
+                    class: "flex gap-3",
+                    button {
+                        class: "px-4 py-2 text-white bg-blue-600 rounded-md hover:bg-blue-700",
+                        onclick: move |_| on_edit.call(()),
+                        {i18n.t("common.edit")}
+                    }
+                    button {
+                        class: "px-4 py-2 text-white bg-red-600 rounded-md hover:bg-red-700",
+                        onclick: move |_| on_delete.call(()),
+                        {i18n.t("common.delete")}
+                    }
+                }
+            }
+        }
+
+        Section { title: i18n.t("employee_detail.record"),
+            Field { label: i18n.t("common.id"), value: employee.id.to_string() }
+            Field {
+                label: i18n.t("employees.first_name"),
+                value: employee.first_name.clone().unwrap_or_else(dash),
+            }
+            Field {
+                label: i18n.t("employees.last_name"),
+                value: employee.last_name.clone().unwrap_or_else(dash),
+            }
+            Field {
+                label: i18n.t("common.email"),
+                value: employee.email.clone().unwrap_or_else(dash),
+            }
+        }
+
+        if show_account {
+            Section { title: i18n.t("employee_detail.account"),
+                if let Some(account) = &profile.account {
+                    Field { label: i18n.t("common.email"), value: account.email.clone() }
+                    Field { label: i18n.t("users.role"), value: account.role_name.clone() }
+                } else {
+                    p { class: "col-span-3 text-gray-600 dark:text-gray-300", {i18n.t("employee_detail.no_account")} }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn Section(title: String, children: Element) -> Element {
+    rsx! {
+        section {
+            class: "bg-white shadow-md rounded-lg p-6 mb-6 dark:bg-gray-800",
+            h2 { class: "text-xl font-bold text-gray-800 mb-4 dark:text-gray-100", "{title}" }
+            dl { class: "grid grid-cols-3 gap-x-4 gap-y-2", {children} }
+        }
+    }
+}
+
+#[component]
+fn Field(label: String, value: String) -> Element {
+    rsx! {
+        dt { class: "text-sm font-medium text-gray-600 dark:text-gray-300", "{label}" }
+        dd { class: "col-span-2 text-gray-900 dark:text-gray-100", "{value}" }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::testing::{render, render_in};
+    use crate::i18n::Locale;
+    use crate::server::db::{Employee, UserExtended};
+
+    #[component]
+    fn View(
+        profile: Option<Result<Option<EmployeeProfile>, String>>,
+        show_account: bool,
+        can_edit: bool,
+    ) -> Element {
+        rsx! {
+            EmployeeProfileView {
+                id: 1,
+                profile,
+                show_account,
+                can_edit,
+                on_edit: |_| {},
+                on_delete: |_| {},
+            }
+        }
+    }
+
+    fn props(profile: Option<Result<Option<EmployeeProfile>, String>>) -> ViewProps {
+        ViewProps {
+            profile,
+            show_account: true,
+            can_edit: true,
+        }
+    }
+
+    fn ada(account: bool) -> EmployeeProfile {
+        EmployeeProfile {
+            employee: Employee {
+                id: 1,
+                first_name: Some("Ada".into()),
+                last_name: Some("Lovelace".into()),
+                email: Some("ada@example.com".into()),
+            },
+            account: account.then(|| UserExtended {
+                user_id: 7,
+                email: "ada@example.com".into(),
+                role_name: "ROLE_HR".into(),
+                employee_id: Some(1),
+                employee_name: Some("Ada Lovelace".into()),
+                is_active: true,
+                deactivated_at: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn shows_the_record_and_the_linked_account() {
+        let html = render(View, props(Some(Ok(Some(ada(true))))));
+        assert!(html.contains("Ada Lovelace"));
+        assert!(html.contains("ada@example.com"));
+        assert!(html.contains("ROLE_HR"));
+        assert!(html.contains("Edit"));
+    }
+
+    #[test]
+    fn says_so_when_no_account_is_linked() {
+        let html = render(View, props(Some(Ok(Some(ada(false))))));
+        assert!(html.contains("No user account is linked to this employee."));
+    }
+
+    #[test]
+    fn hides_accounts_and_actions_by_role() {
+        let html = render(
+            View,
+            ViewProps {
+                show_account: false,
+                can_edit: false,
+                ..props(Some(Ok(Some(ada(true)))))
+            },
+        );
+        assert!(html.contains("Ada Lovelace"));
+        assert!(!html.contains("User Account"));
+        assert!(!html.contains("Delete"));
+    }
+
+    #[test]
+    fn covers_loading_missing_and_failed() {
+        assert!(render(View, props(None)).contains("Loading employee..."));
+        assert!(render(View, props(Some(Ok(None)))).contains("Employee 1 not found"));
+        assert!(
+            render(View, props(Some(Err("Not signed in".into()))))
+                .contains("Error loading employee: Not signed in")
+        );
+    }
+
+    #[test]
+    fn renders_in_german() {
+        let html = render_in(Locale::De, View, props(Some(Ok(Some(ada(false))))));
+        assert!(html.contains("Stammdaten"));
+        assert!(html.contains("Diesem Mitarbeiter ist kein Benutzerkonto zugeordnet."));
+    }
+}
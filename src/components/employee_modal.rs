@@ -1,6 +1,13 @@
+use std::collections::HashSet;
+
+use crate::components::toast::use_toasts;
+use crate::i18n::use_i18n;
 use crate::server::db::{Employee, create_employee, update_employee};
+use crate::validation::{self, FieldErrors};
 use dioxus::prelude::*;
 
+const FIELDS: [&str; 3] = ["first_name", "last_name", "email"];
+
 #[derive(Clone, PartialEq)]
 pub enum ModalMode {
     Create,
@@ -31,6 +38,31 @@ pub fn EmployeeModal(
     let mut is_saving = use_signal(|| false);
     let mut error_message = use_signal(|| None::<String>);
 
+    // Checked on every keystroke with the rules the server applies again.
+    // A new form shows a field's error once it was edited; an edit form
+    // checks the stored values right away.
+    let errors = use_memo(move || validation::employee(&first_name(), &last_name(), &email()));
+    let mut touched = use_signal(|| match &mode {
+        ModalMode::Create => HashSet::new(),
+        ModalMode::Edit(_) => HashSet::from(FIELDS),
+    });
+    let mut server_errors = use_signal(FieldErrors::default);
+
+    let i18n = use_i18n();
+    let field_error = move |field: &str| -> Option<String> {
+        let error = match server_errors.read().get(field) {
+            Some(error) => error,
+            None if touched.read().contains(field) => errors.read().get(field)?,
+            None => return None,
+        };
+        Some(i18n.message(&error))
+    };
+    let mut edited = move |field: &'static str| {
+        touched.write().insert(field);
+        server_errors.write().remove(field);
+    };
+
+    let toasts = use_toasts();
     let mode_for_save = mode.clone();
     let handle_save = move |_| {
         let mode = mode_for_save.clone();
@@ -47,27 +79,33 @@ pub fn EmployeeModal(
 
             match result {
                 Ok(_) => {
+                    toasts.success(i18n.t(match &mode {
+                        ModalMode::Create => "employee_form.created",
+                        ModalMode::Edit(_) => "employee_form.updated",
+                    }));
                     on_save.call(());
                     on_close.call(());
                 }
-                Err(e) => {
-                    error_message.set(Some(format!("Error: {}", e)));
-                }
+                Err(e) => match FieldErrors::from_server(&e) {
+                    Some(fields) => server_errors.set(fields),
+                    None => error_message
+                        .set(Some(i18n.tr("common.error", &[("error", e.to_string())]))),
+                },
             }
 
             is_saving.set(false);
         });
     };
 
-    let title = match &mode {
-        ModalMode::Create => "Add New Employee",
-        ModalMode::Edit(_) => "Edit Employee",
-    };
+    let title = i18n.t(match &mode {
+        ModalMode::Create => "employee_form.create_title",
+        ModalMode::Edit(_) => "employee_form.edit_title",
+    });
 
-    let button_text = match &mode {
-        ModalMode::Create => "Create",
-        ModalMode::Edit(_) => "Update",
-    };
+    let button_text = i18n.t(match &mode {
+        ModalMode::Create => "employee_form.create",
+        ModalMode::Edit(_) => "employee_form.update",
+    });
 
     rsx! {
         div {
@@ -76,18 +114,18 @@ pub fn EmployeeModal(
             onclick: move |_| on_close.call(()),
 
             div {
-                class: "bg-white rounded-lg shadow-xl max-w-md w-full mx-4",
+                class: "bg-white rounded-lg shadow-xl max-w-md w-full mx-4 dark:bg-gray-800",
                 onclick: move |e| e.stop_propagation(),
 
                 // Header
                 div {
-                    class: "flex justify-between items-center p-6 border-b",
+                    class: "flex justify-between items-center p-6 border-b dark:border-gray-700",
                     h2 {
-                        class: "text-xl font-bold text-gray-800",
+                        class: "text-xl font-bold text-gray-800 dark:text-gray-100",
                         "{title}"
                     }
                     button {
-                        class: "text-gray-400 hover:text-gray-600",
+                        class: "text-gray-500 hover:text-gray-600 dark:text-gray-300 dark:hover:text-gray-200",
                         onclick: move |_| on_close.call(()),
                         "✕"
                     }
@@ -99,7 +137,7 @@ pub fn EmployeeModal(
 
                     if let Some(error) = error_message() {
                         div {
-                            class: "mb-4 p-3 bg-red-100 border border-red-400 text-red-700 rounded",
+                            class: "mb-4 p-3 bg-red-100 border border-red-400 text-red-700 rounded dark:bg-red-900/40 dark:border-red-700 dark:text-red-200",
                             "{error}"
                         }
                     }
@@ -108,15 +146,21 @@ pub fn EmployeeModal(
                     div {
                         class: "mb-4",
                         label {
-                            class: "block text-sm font-medium text-gray-700 mb-2",
-                            "First Name"
+                            class: "block text-sm font-medium text-gray-700 mb-2 dark:text-gray-200",
+                            {i18n.t("employees.first_name")}
                         }
                         input {
-                            class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500",
+                            class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 dark:border-gray-600 dark:bg-gray-700 dark:text-gray-100",
                             r#type: "text",
                             value: "{first_name}",
-                            oninput: move |e| first_name.set(e.value()),
-                            placeholder: "Enter first name",
+                            oninput: move |e| {
+                                first_name.set(e.value());
+                                edited("first_name");
+                            },
+                            placeholder: i18n.t("employee_form.first_name_placeholder"),
+                        }
+                        if let Some(message) = field_error("first_name") {
+                            p { class: "mt-1 text-sm text-red-600 dark:text-red-400", "{message}" }
                         }
                     }
 
@@ -124,15 +168,21 @@ pub fn EmployeeModal(
                     div {
                         class: "mb-4",
                         label {
-                            class: "block text-sm font-medium text-gray-700 mb-2",
-                            "Last Name"
+                            class: "block text-sm font-medium text-gray-700 mb-2 dark:text-gray-200",
+                            {i18n.t("employees.last_name")}
                         }
                         input {
-                            class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500",
+                            class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 dark:border-gray-600 dark:bg-gray-700 dark:text-gray-100",
                             r#type: "text",
                             value: "{last_name}",
-                            oninput: move |e| last_name.set(e.value()),
-                            placeholder: "Enter last name",
+                            oninput: move |e| {
+                                last_name.set(e.value());
+                                edited("last_name");
+                            },
+                            placeholder: i18n.t("employee_form.last_name_placeholder"),
+                        }
+                        if let Some(message) = field_error("last_name") {
+                            p { class: "mt-1 text-sm text-red-600 dark:text-red-400", "{message}" }
                         }
                     }
 
@@ -140,34 +190,40 @@ pub fn EmployeeModal(
                     div {
                         class: "mb-4",
                         label {
-                            class: "block text-sm font-medium text-gray-700 mb-2",
-                            "Email"
+                            class: "block text-sm font-medium text-gray-700 mb-2 dark:text-gray-200",
+                            {i18n.t("common.email")}
                         }
                         input {
-                            class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500",
+                            class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 dark:border-gray-600 dark:bg-gray-700 dark:text-gray-100",
                             r#type: "email",
                             value: "{email}",
-                            oninput: move |e| email.set(e.value()),
-                            placeholder: "Enter email address",
+                            oninput: move |e| {
+                                email.set(e.value());
+                                edited("email");
+                            },
+                            placeholder: i18n.t("employee_form.email_placeholder"),
+                        }
+                        if let Some(message) = field_error("email") {
+                            p { class: "mt-1 text-sm text-red-600 dark:text-red-400", "{message}" }
                         }
                     }
                 }
 
                 // Footer
                 div {
-                    class: "flex justify-end gap-3 p-6 border-t",
+                    class: "flex justify-end gap-3 p-6 border-t dark:border-gray-700",
                     button {
-                        class: "px-4 py-2 text-gray-700 bg-gray-100 rounded-md hover:bg-gray-200",
+                        class: "px-4 py-2 text-gray-700 bg-gray-100 rounded-md hover:bg-gray-200 dark:text-gray-200 dark:bg-gray-700 dark:hover:bg-gray-600",
                         onclick: move |_| on_close.call(()),
                         disabled: is_saving(),
-                        "Cancel"
+                        {i18n.t("common.cancel")}
                     }
                     button {
                         class: "px-4 py-2 text-white bg-blue-600 rounded-md hover:bg-blue-700 disabled:opacity-50",
                         onclick: handle_save,
-                        disabled: is_saving(),
+                        disabled: is_saving() || !errors.read().is_empty(),
                         if is_saving() {
-                            "Saving..."
+                            {i18n.t("common.saving")}
                         } else {
                             "{button_text}"
                         }
@@ -176,4 +232,111 @@ pub fn EmployeeModal(
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::testing::{render, render_in};
+    use crate::i18n::Locale;
+
+    #[component]
+    fn Modal(mode: ModalMode) -> Element {
+        rsx! {
+            EmployeeModal { mode, on_close: |_| {}, on_save: |_| {} }
+        }
+    }
+
+    #[test]
+    fn create_starts_empty() {
+        let html = render(
+            Modal,
+            ModalProps {
+                mode: ModalMode::Create,
+            },
+        );
+        assert!(html.contains("Add New Employee"));
+        assert!(html.contains(">Create<"));
+        assert!(!html.contains("Edit Employee"));
+        assert_eq!(html.matches(r#"value="""#).count(), 3);
+        // Nothing entered yet: Save is off, but no field is flagged
+        assert_eq!(html.matches("disabled=true").count(), 1);
+        assert!(!html.contains("Required"));
+    }
+
+    #[test]
+    fn edit_is_prefilled_from_the_employee() {
+        let html = render(
+            Modal,
+            ModalProps {
+                mode: ModalMode::Edit(Employee {
+                    id: 7,
+                    first_name: Some("Ada".into()),
+                    last_name: None,
+                    email: Some("ada@example.com".into()),
+                }),
+            },
+        );
+        assert!(html.contains("Edit Employee"));
+        assert!(html.contains("Update"));
+        assert!(!html.contains("Add New Employee"));
+        assert!(html.contains(r#"value="Ada""#));
+        assert!(html.contains(r#"value="ada@example.com""#));
+        // A missing last name becomes an empty field
+        assert_eq!(html.matches(r#"value="""#).count(), 1);
+    }
+
+    #[test]
+    fn edit_flags_invalid_stored_values() {
+        let html = render(
+            Modal,
+            ModalProps {
+                mode: ModalMode::Edit(Employee {
+                    id: 7,
+                    first_name: Some("Ada".into()),
+                    last_name: Some("Lovelace".into()),
+                    email: Some("not-an-email".into()),
+                }),
+            },
+        );
+        assert!(html.contains("Not a valid email address"));
+        assert!(!html.contains("Required"));
+        assert_eq!(html.matches("disabled=true").count(), 1);
+    }
+
+    #[test]
+    fn valid_edit_can_be_saved() {
+        let html = render(
+            Modal,
+            ModalProps {
+                mode: ModalMode::Edit(Employee {
+                    id: 7,
+                    first_name: Some("Ada".into()),
+                    last_name: Some("Lovelace".into()),
+                    email: Some("ada@example.com".into()),
+                }),
+            },
+        );
+        assert!(!html.contains("disabled=true"));
+        assert!(!html.contains("text-red-600"));
+    }
+
+    #[test]
+    fn field_errors_speak_german() {
+        let html = render_in(
+            Locale::De,
+            Modal,
+            ModalProps {
+                mode: ModalMode::Edit(Employee {
+                    id: 7,
+                    first_name: Some("Ada".into()),
+                    last_name: Some("Lovelace".into()),
+                    email: Some("not-an-email".into()),
+                }),
+            },
+        );
+        assert!(html.contains("Mitarbeiter bearbeiten"));
+        assert!(html.contains("Keine gültige E-Mail-Adresse"));
+        assert!(html.contains(r#"placeholder="Vorname eingeben""#));
+    }
+}
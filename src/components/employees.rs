@@ -1,22 +1,214 @@
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+use crate::app::Route;
+use crate::components::data_table::{BulkAction, Column, DataTable, SortKey, TableRow};
 use crate::components::employee_modal::{EmployeeModal, ModalMode};
-use crate::server::db::{Employee, delete_employee, get_employees_server};
+use crate::components::live::{Highlights, apply_change, use_highlights, use_live_changes};
+use crate::components::toast::{ToastAction, ToastKind, use_toasts};
+use crate::components::user_modal::{UserModal, UserModalMode};
+use crate::i18n::use_i18n;
+use crate::server::auth::USER_MANAGE;
+use crate::server::bulk::{BulkResult, delete_employees, export_employees, set_employees_role};
+use crate::server::db::{Employee, create_employee, get_employee_server, get_employees_server};
+use crate::server::events::{ChangeEvent, ChangeOp, ChangeTable};
+use crate::server::roles::get_roles_server;
+use crate::server::state::AuthState;
+use dioxus::core::use_drop;
 use dioxus::prelude::*;
 
+impl TableRow for Employee {
+    fn row_id(&self) -> i32 {
+        self.id
+    }
+}
+
 #[component]
 pub fn Employees() -> Element {
     let mut employees = use_resource(move || async move { get_employees_server().await });
     let mut modal_state = use_signal(|| None::<ModalMode>);
-    let mut delete_confirm = use_signal(|| None::<Employee>);
+    // One row from its Delete button, or the bulk selection
+    let mut delete_confirm = use_signal(|| None::<Vec<Employee>>);
+    // The selection whose accounts get a new role, while the dialog is open
+    let mut role_confirm = use_signal(|| None::<Vec<Employee>>);
+    // Ticked rows; they stay ticked when a dialog is cancelled and drop out
+    // once the action went through for them
+    let mut selection = use_signal(BTreeSet::<i32>::new);
+    // The last export: how many rows and the CSV
+    let mut export = use_signal(|| None::<(usize, String)>);
+    // "Create login" opens the user editor for that employee
+    let mut login_for = use_signal(|| None::<Employee>);
+    let highlights = use_highlights();
+    let auth = use_context::<AuthState>();
+    let toasts = use_toasts();
+    let i18n = use_i18n();
+    let nav = use_navigator();
+
+    // The Undo toast refreshes this page's list, so it closes with the page
+    let undo_toast = use_hook(|| Rc::new(Cell::new(None::<u64>)));
+    use_drop({
+        let undo_toast = undo_toast.clone();
+        move || {
+            if let Some(id) = undo_toast.get() {
+                toasts.dismiss(id);
+            }
+        }
+    });
+
+    // Patch rows changed elsewhere (other tabs, REST, SCIM) in place
+    use_live_changes(move |event: ChangeEvent| {
+        if event.table != ChangeTable::Employee {
+            return;
+        }
+        spawn(async move {
+            let fresh = match event.op {
+                ChangeOp::Delete => None,
+                _ => match get_employee_server(event.id).await {
+                    Ok(fresh) => fresh,
+                    Err(_) => return,
+                },
+            };
+            if let Some(Ok(list)) = employees.write().as_mut() {
+                apply_change(list, event.id, fresh, |e| e.id);
+            }
+            if event.op != ChangeOp::Delete {
+                highlights.flash(event.id);
+            }
+        });
+    });
+
+    // Recreate deleted employees (with new ids) from the Undo toast. Only
+    // offered when none had a user account, which would not come back linked.
+    let restore = move |deleted: Vec<Employee>| {
+        spawn(async move {
+            let mut restored = 0;
+            for employee in &deleted {
+                let result = create_employee(
+                    employee.first_name.clone().unwrap_or_default(),
+                    employee.last_name.clone().unwrap_or_default(),
+                    employee.email.clone().unwrap_or_default(),
+                )
+                .await;
+                match result {
+                    Ok(_) => restored += 1,
+                    Err(e) => toasts.error(i18n.tr(
+                        "employees.restore_failed",
+                        &[("name", full_name(employee)), ("error", e.to_string())],
+                    )),
+                }
+            }
+            if restored > 0 {
+                toasts.success(i18n.tr(
+                    "employees.restored",
+                    &[
+                        ("restored", i18n.number(restored)),
+                        ("total", i18n.number(deleted.len() as i64)),
+                    ],
+                ));
+            }
+            employees.restart();
+        });
+    };
 
-    let handle_delete = move |id: i32| {
+    // Report each skipped row by name
+    let report_failures = move |key: &'static str, chosen: &[Employee], result: &BulkResult| {
+        for failure in &result.failed {
+            let name = chosen
+                .iter()
+                .find(|e| e.id == failure.id)
+                .map(full_name)
+                .unwrap_or_else(|| failure.id.to_string());
+            toasts.error(i18n.tr(key, &[("name", name), ("error", failure.error.clone())]));
+        }
+    };
+
+    // One transaction on the server; rows it could not delete are reported
+    let handle_delete = move |chosen: Vec<Employee>| {
+        let undo_toast = undo_toast.clone();
         spawn(async move {
-            if let Ok(_) = delete_employee(id).await {
-                delete_confirm.set(None);
-                employees.restart();
+            let ids = chosen.iter().map(|e| e.id).collect();
+            let result = delete_employees(ids).await;
+            delete_confirm.set(None);
+            employees.restart();
+            let result = match result {
+                Ok(result) => result,
+                Err(e) => {
+                    toasts.error(i18n.tr("common.error", &[("error", e.to_string())]));
+                    return;
+                }
+            };
+            report_failures("employees.delete_failed", &chosen, &result);
+            selection.write().retain(|id| !result.done.contains(id));
+            let deleted: Vec<Employee> = chosen
+                .into_iter()
+                .filter(|e| result.done.contains(&e.id))
+                .collect();
+
+            let message = match deleted.as_slice() {
+                [] => return,
+                [employee] => i18n.tr("employees.deleted_named", &[("name", full_name(employee))]),
+                many => i18n.plural("employees.deleted", many.len(), &[]),
+            };
+            let undoable = deleted.iter().all(|e| !result.with_account.contains(&e.id));
+            let action = undoable.then(|| ToastAction {
+                label: "common.undo",
+                on_click: Callback::new(move |_| restore(deleted.clone())),
+            });
+            let id = toasts.show(ToastKind::Success, message, action);
+            if undoable {
+                undo_toast.set(Some(id));
             }
         });
     };
 
+    let handle_set_role = move |(chosen, role): (Vec<Employee>, String)| {
+        spawn(async move {
+            let ids = chosen.iter().map(|e| e.id).collect();
+            let result = set_employees_role(ids, role.clone()).await;
+            role_confirm.set(None);
+            match result {
+                Ok(result) => {
+                    report_failures("employees.role_failed", &chosen, &result);
+                    selection.write().retain(|id| !result.done.contains(id));
+                    if !result.done.is_empty() {
+                        toasts.success(i18n.plural(
+                            "employees.role_set",
+                            result.done.len(),
+                            &[("role", role)],
+                        ));
+                    }
+                }
+                Err(e) => toasts.error(i18n.tr("common.error", &[("error", e.to_string())])),
+            }
+        });
+    };
+
+    let handle_export = move |ids: Vec<i32>| {
+        spawn(async move {
+            let count = ids.len();
+            match export_employees(ids).await {
+                Ok(csv) => {
+                    selection.write().clear();
+                    export.set(Some((count, csv)));
+                }
+                Err(e) => toasts.error(i18n.tr("common.error", &[("error", e.to_string())])),
+            }
+        });
+    };
+
+    // The rows of the current list with these ids
+    let pick = move |ids: Vec<i32>| -> Vec<Employee> {
+        match &*employees.read() {
+            Some(Ok(list)) => list
+                .iter()
+                .filter(|e| ids.contains(&e.id))
+                .cloned()
+                .collect(),
+            _ => Vec::new(),
+        }
+    };
+
     rsx! {
         div {
             class: "container mx-auto px-4 py-8",
@@ -25,89 +217,38 @@ pub fn Employees() -> Element {
             div {
                 class: "flex justify-between items-center mb-6",
                 h1 {
-                    class: "text-3xl font-bold text-blue-500",
-                    "Employee Directory"
+                    class: "text-3xl font-bold text-blue-600 dark:text-blue-400",
+                    {i18n.t("employees.title")}
                 }
                 button {
                     class: "px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700 flex items-center gap-2",
                     onclick: move |_| modal_state.set(Some(ModalMode::Create)),
                     span { "+" }
-                    span { "Add Employee" }
+                    span { {i18n.t("employees.add")} }
                 }
             }
 
-            // Table
-            match employees() {
-                None => rsx! {
-                    div {
-                        class: "flex justify-center items-center py-12",
-                        p { class: "text-gray-600", "Loading employees..." }
-                    }
-                },
-                Some(Err(err)) => rsx! {
-                    div {
-                        class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded",
-                        "Error loading employees: {err}"
-                    }
-                },
-                Some(Ok(employee_list)) => rsx! {
-                    div {
-                        class: "bg-white shadow-md rounded-lg overflow-hidden",
-                        table {
-                            class: "min-w-full divide-y divide-gray-200",
-                            thead {
-                                class: "bg-gray-50",
-                                tr {
-                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider", "ID" }
-                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider", "First Name" }
-                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider", "Last Name" }
-                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider", "Email" }
-                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider", "Actions" }
-                                }
-                            }
-                            tbody {
-                                class: "bg-white divide-y divide-gray-200",
-                                for employee in employee_list.iter() {
-                                    tr {
-                                        key: "{employee.id}",
-                                        class: "hover:bg-gray-50",
-                                        td { class: "px-6 py-4 whitespace-nowrap text-sm text-gray-900", "{employee.id}" }
-                                        td { class: "px-6 py-4 whitespace-nowrap text-sm text-gray-900", "{employee.first_name.as_deref().unwrap_or(\"-\")}" }
-                                        td { class: "px-6 py-4 whitespace-nowrap text-sm text-gray-900", "{employee.last_name.as_deref().unwrap_or(\"-\")}" }
-                                        td { class: "px-6 py-4 whitespace-nowrap text-sm text-gray-600", "{employee.email.as_deref().unwrap_or(\"-\")}" }
-
-                                        td {
-                                            class: "px-6 py-4 whitespace-nowrap text-sm font-medium flex gap-2",
-                                            button {
-                                                class: "text-blue-600 hover:text-blue-900",
-                                                onclick: {
-                                                    let emp = employee.clone();
-                                                    move |_| modal_state.set(Some(ModalMode::Edit(emp.clone())))
-                                                },
-                                                "✏️ Edit"
-                                            }
-                                            button {
-                                                class: "text-red-600 hover:text-red-900",
-                                                onclick: {
-                                                    let emp = employee.clone();
-                                                    move |_| delete_confirm.set(Some(emp.clone()))
-                                                },
-                                                "🗑️ Delete"
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
+            if let Some((count, csv)) = export() {
+                ExportReady { count, csv, on_close: move |_| export.set(None) }
+            }
 
-                        if employee_list.is_empty() {
-                            div {
-                                class: "text-center py-8 text-gray-500",
-                                "No employees found."
-                            }
-                        }
-                    }
-                }
+            EmployeeTable {
+                employees: employees().map(|result| result.map_err(|e| e.to_string())),
+                highlights: highlights,
+                selection,
+                on_view: move |id| {
+                    nav.push(Route::EmployeeDetail { id });
+                },
+                on_edit: move |emp| modal_state.set(Some(ModalMode::Edit(emp))),
+                on_delete: move |emp| delete_confirm.set(Some(vec![emp])),
+                on_create_login: auth
+                    .can(USER_MANAGE)
+                    .then_some(EventHandler::new(move |emp| login_for.set(Some(emp)))),
+                on_delete_selected: move |ids| delete_confirm.set(Some(pick(ids))),
+                on_set_role_selected: auth
+                    .can(USER_MANAGE)
+                    .then_some(EventHandler::new(move |ids| role_confirm.set(Some(pick(ids))))),
+                on_export_selected: handle_export,
             }
         }
 
@@ -120,36 +261,455 @@ pub fn Employees() -> Element {
             }
         }
 
+        if let Some(employee) = login_for() {
+            UserModal {
+                mode: UserModalMode::Create(Some(employee)),
+                on_close: move |_| login_for.set(None),
+                on_save: |_| {},
+            }
+        }
+
         // Delete Confirmation
-        if let Some(employee) = delete_confirm() {
+        if let Some(chosen) = delete_confirm() {
+            DeleteDialog {
+                chosen: chosen.clone(),
+                on_cancel: move |_| delete_confirm.set(None),
+                on_confirm: move |_| handle_delete(chosen.clone()),
+            }
+        }
+
+        if let Some(chosen) = role_confirm() {
+            RoleDialog {
+                count: chosen.len(),
+                on_cancel: move |_| role_confirm.set(None),
+                on_confirm: move |role| handle_set_role((chosen.clone(), role)),
+            }
+        }
+    }
+}
+
+/// Asks for the role to give the accounts of `count` selected employees
+#[component]
+pub fn RoleDialog(
+    count: usize,
+    on_cancel: EventHandler<()>,
+    on_confirm: EventHandler<String>,
+) -> Element {
+    let roles = use_resource(|| async move { get_roles_server().await });
+    let mut role = use_signal(String::new);
+    let i18n = use_i18n();
+
+    // The first role until one is picked
+    let names: Vec<String> = match &*roles.read() {
+        Some(Ok(list)) => list.iter().map(|r| r.name.clone()).collect(),
+        _ => Vec::new(),
+    };
+    let chosen = if role.read().is_empty() {
+        names.first().cloned().unwrap_or_default()
+    } else {
+        role()
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 flex items-center justify-center z-50",
+            style: "background-color: rgba(0, 0, 0, 0.4);",
             div {
-                class: "fixed inset-0 flex items-center justify-center z-50",
-                style: "background-color: rgba(0, 0, 0, 0.4);",
+                class: "bg-white rounded-lg p-6 max-w-sm mx-4 dark:bg-gray-800",
+                h3 { class: "text-lg font-bold mb-4",
+                    {i18n.plural("employees.role_title", count, &[])}
+                }
+                p { class: "text-gray-600 mb-4 dark:text-gray-300",
+                    {i18n.plural("employees.role_confirm", count, &[])}
+                }
+                if names.is_empty() {
+                    p { class: "text-gray-500 text-sm mb-6 dark:text-gray-300", {i18n.t("roles.loading")} }
+                } else {
+                    select {
+                        class: "w-full mb-6 px-3 py-2 border border-gray-300 rounded-md dark:border-gray-600 dark:bg-gray-700 dark:text-gray-100",
+                        value: "{chosen}",
+                        onchange: move |e| role.set(e.value()),
+                        for name in names.iter() {
+                            option { value: "{name}", "{name}" }
+                        }
+                    }
+                }
                 div {
-                    class: "bg-white rounded-lg p-6 max-w-sm mx-4",
-                    h3 { class: "text-lg font-bold mb-4", "Delete Employee?" }
-                    p { class: "text-gray-600 mb-4",
-                        "Are you sure you want to delete {employee.first_name.as_deref().unwrap_or(\"\")} {employee.last_name.as_deref().unwrap_or(\"\")}?"
+                    class: "flex justify-end gap-3",
+                    button {
+                        class: "px-4 py-2 text-gray-700 bg-gray-100 rounded-md hover:bg-gray-200 dark:text-gray-200 dark:bg-gray-700 dark:hover:bg-gray-600",
+                        onclick: move |_| on_cancel.call(()),
+                        {i18n.t("common.cancel")}
                     }
-                    p { class: "text-gray-500 text-sm mb-6", "This action cannot be undone." }
-                    div {
-                        class: "flex justify-end gap-3",
-                        button {
-                            class: "px-4 py-2 text-gray-700 bg-gray-100 rounded-md hover:bg-gray-200",
-                            onclick: move |_| delete_confirm.set(None),
-                            "Cancel"
-                        }
+                    button {
+                        class: "px-4 py-2 text-white bg-blue-600 rounded-md hover:bg-blue-700 disabled:opacity-50",
+                        disabled: chosen.is_empty(),
+                        onclick: move |_| on_confirm.call(chosen.clone()),
+                        {i18n.t("employees.role_apply")}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A finished export with a link that saves it as `employees.csv`
+#[component]
+pub fn ExportReady(count: usize, csv: String, on_close: EventHandler<()>) -> Element {
+    let i18n = use_i18n();
+
+    rsx! {
+        div {
+            class: "flex justify-between items-center mb-4 bg-blue-50 border border-blue-200 rounded-md px-4 py-3 text-sm dark:bg-blue-900/40 dark:border-blue-700",
+            span { class: "text-gray-700 dark:text-gray-200", {i18n.plural("employees.exported", count, &[])} }
+            div {
+                class: "flex gap-4",
+                a {
+                    class: "font-medium text-blue-600 hover:text-blue-900 dark:text-blue-400 dark:hover:text-blue-300",
+                    href: csv_data_url(&csv),
+                    download: "employees.csv",
+                    {i18n.t("employees.download")}
+                }
+                button {
+                    class: "text-gray-600 hover:text-gray-900 dark:text-gray-300 dark:hover:text-white",
+                    onclick: move |_| on_close.call(()),
+                    {i18n.t("common.close")}
+                }
+            }
+        }
+    }
+}
+
+/// `csv` as a `data:` URL, so the browser can save it without another request
+pub fn csv_data_url(csv: &str) -> String {
+    let mut url = String::from("data:text/csv;charset=utf-8,");
+    for byte in csv.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            url.push(byte as char);
+        } else {
+            url.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    url
+}
+
+/// Asks before deleting `chosen`, naming the employee when there is one
+#[component]
+pub fn DeleteDialog(
+    chosen: Vec<Employee>,
+    on_cancel: EventHandler<()>,
+    on_confirm: EventHandler<()>,
+) -> Element {
+    let i18n = use_i18n();
+
+    rsx! {
+        div {
+            class: "fixed inset-0 flex items-center justify-center z-50",
+            style: "background-color: rgba(0, 0, 0, 0.4);",
+            div {
+                class: "bg-white rounded-lg p-6 max-w-sm mx-4 dark:bg-gray-800",
+                h3 { class: "text-lg font-bold mb-4",
+                    {i18n.plural("employees.delete_title", chosen.len(), &[])}
+                }
+                p { class: "text-gray-600 mb-4 dark:text-gray-300",
+                    if let [employee] = chosen.as_slice() {
+                        {i18n.tr("employees.delete_confirm", &[("name", full_name(employee))])}
+                    } else {
+                        {i18n.plural("employees.delete_confirm_selected", chosen.len(), &[])}
+                    }
+                }
+                p { class: "text-gray-500 text-sm mb-6 dark:text-gray-300", {i18n.t("employees.cannot_undo")} }
+                div {
+                    class: "flex justify-end gap-3",
+                    button {
+                        class: "px-4 py-2 text-gray-700 bg-gray-100 rounded-md hover:bg-gray-200 dark:text-gray-200 dark:bg-gray-700 dark:hover:bg-gray-600",
+                        onclick: move |_| on_cancel.call(()),
+                        {i18n.t("common.cancel")}
+                    }
+                    button {
+                        class: "px-4 py-2 text-white bg-red-600 rounded-md hover:bg-red-700",
+                        onclick: move |_| on_confirm.call(()),
+                        {i18n.t("common.confirm_delete")}
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn full_name(employee: &Employee) -> String {
+    format!(
+        "{} {}",
+        employee.first_name.as_deref().unwrap_or_default(),
+        employee.last_name.as_deref().unwrap_or_default()
+    )
+    .trim()
+    .to_string()
+}
+
+/// The employee list in its loading (`None`), error, empty or populated state.
+/// Rows offer "Create login" when `on_create_login` is given, and the
+/// selection "Change role" when `on_set_role_selected` is.
+#[component]
+pub fn EmployeeTable(
+    employees: Option<Result<Vec<Employee>, String>>,
+    highlights: Highlights,
+    /// Ticked rows, owned by the page so its dialogs can leave them be
+    selection: Signal<BTreeSet<i32>>,
+    on_view: EventHandler<i32>,
+    on_edit: EventHandler<Employee>,
+    on_delete: EventHandler<Employee>,
+    on_delete_selected: EventHandler<Vec<i32>>,
+    on_export_selected: EventHandler<Vec<i32>>,
+    on_set_role_selected: Option<EventHandler<Vec<i32>>>,
+    on_create_login: Option<EventHandler<Employee>>,
+) -> Element {
+    let i18n = use_i18n();
+    let columns = vec![
+        Column::new("id", "common.id", |e: &Employee| rsx! { "{e.id}" })
+            .sort_by(|e| e.id.into())
+            .width("w-20"),
+        Column::new("first_name", "employees.first_name", |e: &Employee| {
+            rsx! { "{e.first_name.as_deref().unwrap_or(\"-\")}" }
+        })
+        .sort_by(|e| SortKey::text(e.first_name.as_deref())),
+        Column::new("last_name", "employees.last_name", |e: &Employee| {
+            rsx! { "{e.last_name.as_deref().unwrap_or(\"-\")}" }
+        })
+        .sort_by(|e| SortKey::text(e.last_name.as_deref())),
+        Column::new("email", "common.email", |e: &Employee| {
+            rsx! {
+                span { class: "text-gray-600 dark:text-gray-300", "{e.email.as_deref().unwrap_or(\"-\")}" }
+            }
+        })
+        .sort_by(|e| SortKey::text(e.email.as_deref())),
+        Column::new("actions", "common.actions", move |e: &Employee| {
+            let id = e.id;
+            let edit = e.clone();
+            let delete = e.clone();
+            let login = e.clone();
+            rsx! {
+                div {
+                    class: "flex gap-2 font-medium",
+                    button {
+                        class: "text-gray-700 hover:text-gray-900 dark:text-gray-200 dark:hover:text-white",
+                        onclick: move |_| on_view.call(id),
+                        {i18n.t("common.view")}
+                    }
+                    button {
+                        class: "text-blue-600 hover:text-blue-900 dark:text-blue-400 dark:hover:text-blue-300",
+                        onclick: move |_| on_edit.call(edit.clone()),
+                        {i18n.t("common.edit")}
+                    }
+                    button {
+                        class: "text-red-600 hover:text-red-900 dark:text-red-400 dark:hover:text-red-300",
+                        onclick: move |_| on_delete.call(delete.clone()),
+                        {i18n.t("common.delete")}
+                    }
+                    if let Some(on_create_login) = on_create_login {
                         button {
-                            class: "px-4 py-2 text-white bg-red-600 rounded-md hover:bg-red-700",
-                            onclick: {
-                                let id = employee.id;
-                                move |_| handle_delete(id)
-                            },
-                            "Delete"
+                            class: "text-green-700 hover:text-green-900 dark:text-green-400 dark:hover:text-green-300",
+                            onclick: move |_| on_create_login.call(login.clone()),
+                            {i18n.t("employees.create_login")}
                         }
                     }
                 }
             }
+        })
+        .width("w-80"),
+    ];
+
+    let mut bulk_actions = vec![BulkAction {
+        label: "employees.delete_selected",
+        danger: true,
+        on_run: on_delete_selected,
+    }];
+    if let Some(on_run) = on_set_role_selected {
+        bulk_actions.push(BulkAction {
+            label: "employees.set_role_selected",
+            danger: false,
+            on_run,
+        });
+    }
+    bulk_actions.push(BulkAction {
+        label: "employees.export_selected",
+        danger: false,
+        on_run: on_export_selected,
+    });
+
+    rsx! {
+        DataTable {
+            rows: employees,
+            columns,
+            noun: "employees",
+            highlights,
+            bulk_actions,
+            selection,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::testing::{render, render_in};
+    use crate::i18n::Locale;
+
+    #[component]
+    fn Table(employees: Option<Result<Vec<Employee>, String>>) -> Element {
+        let highlights = use_highlights();
+        let selection = use_signal(BTreeSet::new);
+        rsx! {
+            EmployeeTable {
+                employees,
+                highlights,
+                selection,
+                on_view: |_| {},
+                on_edit: |_| {},
+                on_delete: |_| {},
+                on_delete_selected: |_| {},
+                on_export_selected: |_| {},
+            }
+        }
+    }
+
+    fn employee(id: i32, first: &str, last: Option<&str>, email: &str) -> Employee {
+        Employee {
+            id,
+            first_name: Some(first.into()),
+            last_name: last.map(Into::into),
+            email: Some(email.into()),
+        }
+    }
+
+    #[test]
+    fn shows_loading_until_the_server_answers() {
+        let html = render(Table, TableProps { employees: None });
+        assert!(html.contains("Loading employees..."));
+        assert!(!html.contains("<table"));
+    }
+
+    #[test]
+    fn shows_the_server_error() {
+        let html = render(
+            Table,
+            TableProps {
+                employees: Some(Err("Not signed in".into())),
+            },
+        );
+        assert!(html.contains("Error loading employees: Not signed in"));
+        assert!(!html.contains("<table"));
+    }
+
+    #[test]
+    fn says_so_when_there_are_no_employees() {
+        let html = render(
+            Table,
+            TableProps {
+                employees: Some(Ok(Vec::new())),
+            },
+        );
+        assert!(html.contains("No employees found."));
+        assert!(!html.contains("<td"));
+    }
+
+    #[test]
+    fn lists_every_employee() {
+        let html = render(
+            Table,
+            TableProps {
+                employees: Some(Ok(vec![
+                    employee(1, "Ada", Some("Lovelace"), "ada@example.com"),
+                    employee(2, "Grace", None, "grace@example.com"),
+                ])),
+            },
+        );
+        for text in [
+            "Ada",
+            "Lovelace",
+            "ada@example.com",
+            "Grace",
+            "grace@example.com",
+        ] {
+            assert!(html.contains(text), "missing {}", text);
+        }
+        assert_eq!(html.matches("Edit").count(), 2);
+        // Missing names show a dash
+        assert!(html.contains(">-</td>"));
+        assert!(!html.contains("No employees found."));
+    }
+
+    #[test]
+    fn create_login_only_comes_with_a_handler() {
+        let rows = || Some(Ok(vec![employee(1, "Ada", None, "ada@example.com")]));
+        assert!(!render(Table, TableProps { employees: rows() }).contains("Create login"));
+
+        #[component]
+        fn WithLogin(employees: Option<Result<Vec<Employee>, String>>) -> Element {
+            let highlights = use_highlights();
+            let selection = use_signal(BTreeSet::new);
+            rsx! {
+                EmployeeTable {
+                    employees,
+                    highlights,
+                    selection,
+                    on_view: |_| {},
+                    on_edit: |_| {},
+                    on_delete: |_| {},
+                    on_delete_selected: |_| {},
+                    on_export_selected: |_| {},
+                    on_create_login: |_| {},
+                }
+            }
+        }
+        let html = render(WithLogin, WithLoginProps { employees: rows() });
+        assert!(html.contains("Create login"));
+    }
+
+    #[component]
+    fn Export(count: usize, csv: String) -> Element {
+        rsx! {
+            ExportReady { count, csv, on_close: |_| {} }
+        }
+    }
+
+    #[test]
+    fn export_offers_the_csv_as_a_download() {
+        let html = render(
+            Export,
+            ExportProps {
+                count: 2,
+                csv: "id,email\n1,ada@example.com\n".into(),
+            },
+        );
+        assert!(html.contains("2 employees exported"));
+        assert!(html.contains(r#"download="employees.csv""#));
+        assert!(html.contains("data:text/csv;charset=utf-8,id%2Cemail%0A1%2Cada%40example.com%0A"));
+    }
+
+    #[test]
+    fn data_urls_escape_everything_but_plain_characters() {
+        assert_eq!(
+            csv_data_url("Zoë \"Z\"-1_2.3~"),
+            "data:text/csv;charset=utf-8,Zo%C3%AB%20%22Z%22-1_2.3~"
+        );
+    }
+
+    #[test]
+    fn speaks_german() {
+        let html = render_in(Locale::De, Table, TableProps { employees: None });
+        assert!(html.contains("Mitarbeiter werden geladen..."));
+
+        let html = render_in(
+            Locale::De,
+            Table,
+            TableProps {
+                employees: Some(Ok(vec![employee(1, "Ada", None, "ada@example.com")])),
+            },
+        );
+        for text in ["Vorname", "Nachname", "E-Mail", "Bearbeiten", "Spalten"] {
+            assert!(html.contains(text), "missing {}", text);
         }
+        assert!(!html.contains("First Name"));
     }
 }
@@ -4,7 +4,7 @@ use dioxus::prelude::*;
 pub fn Footer() -> Element {
     rsx! {
         footer {
-            class: "fixed bottom-0 left-0 right-0 bg-gray-500 text-gray-200 py-4 text-center border-t border-gray-700 z-50",
+            class: "fixed bottom-0 left-0 right-0 bg-gray-600 text-white dark:bg-gray-800 dark:text-gray-300 py-4 text-center border-t border-gray-700 z-50",
             "© 2025 Thorsten Fey"
         }
     }
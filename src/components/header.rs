@@ -1,36 +1,152 @@
+use crate::components::toast::use_toasts;
+use crate::i18n::{Locale, use_i18n};
+use crate::server::auth::{ROLE_MANAGE, USER_READ, WEBHOOK_MANAGE, logout, set_locale, set_theme};
+use crate::server::state::AuthState;
+use crate::theme::use_theme;
 use dioxus::prelude::*;
 use dioxus_router::components::Link;
-use crate::server::state::AuthState;
 
 #[component]
 pub fn Header() -> Element {
     let mut auth = use_context::<AuthState>();
+    let toasts = use_toasts();
+    let mut i18n = use_i18n();
+    let mut theme = use_theme();
 
     rsx! {
         header {
-            class: "fixed top-0 left-0 right-0 bg-blue-600 text-white p-4 shadow-md z-50",
+            class: "fixed top-0 left-0 right-0 bg-blue-700 dark:bg-gray-800 text-white p-4 shadow-md z-50",
             div {
                 class: "container mx-auto flex justify-between items-center",
                 h1 { class: "text-2xl font-bold", "Rust Fullstack Core" }
                 nav {
-                    class: "flex gap-4",
+                    class: "flex gap-4 items-center",
 
                     // always visible
-                    Link { to: "/", class: "hover:text-blue-200", "Home" }
-                    Link { to: "/login", class: "hover:text-blue-200", "Login" }
+                    Link { to: "/", class: "hover:text-blue-200", {i18n.t("nav.home")} }
+                    Link { to: "/login", class: "hover:text-blue-200", {i18n.t("nav.login")} }
 
-                    // only visible when logged in
-                    if (auth.logged_in)() {
-                        Link { to: "/employees", class: "hover:text-blue-200", "Employees" }
-                        Link { to: "/users", class: "hover:text-gray-200", "Users" }
+                    // only visible when logged in, and only the pages the role may open
+                    if auth.logged_in() {
+                        Link { to: "/employees", class: "hover:text-blue-200", {i18n.t("nav.employees")} }
+                        if auth.can(USER_READ) {
+                            Link { to: "/users", class: "hover:text-blue-200", {i18n.t("nav.users")} }
+                        }
+                        if auth.can(ROLE_MANAGE) {
+                            Link { to: "/roles", class: "hover:text-blue-200", {i18n.t("nav.roles")} }
+                        }
+                        if auth.can(WEBHOOK_MANAGE) {
+                            Link { to: "/webhooks", class: "hover:text-blue-200", {i18n.t("nav.webhooks")} }
+                        }
                         button {
                             class: "ml-4 bg-red-600 text-white px-3 py-1 rounded",
-                            onclick: move |_| auth.logged_in.set(false),
-                            "Logout"
+                            onclick: move |_| async move {
+                                // The cookie is HttpOnly; only the server can clear it
+                                match logout().await {
+                                    Ok(()) => toasts.info(i18n.t("auth.signed_out")),
+                                    Err(e) => toasts.error(i18n.tr(
+                                        "auth.sign_out_failed",
+                                        &[("error", e.to_string())],
+                                    )),
+                                }
+                                auth.user.set(None);
+                            },
+                            {i18n.t("nav.logout")}
+                        }
+                    }
+
+                    // Applies at once; the server keeps it in a cookie and,
+                    // when signed in, on the account
+                    select {
+                        class: "bg-blue-800 dark:bg-gray-700 text-white rounded px-2 py-1",
+                        title: i18n.t("nav.language"),
+                        onchange: move |e| async move {
+                            let Some(locale) = Locale::from_code(&e.value()) else {
+                                return;
+                            };
+                            i18n.locale.set(locale);
+                            if let Err(e) = set_locale(locale).await {
+                                toasts.error(i18n.tr("nav.language_failed", &[("error", e.to_string())]));
+                            }
+                        },
+                        for locale in Locale::ALL {
+                            option {
+                                value: locale.code(),
+                                selected: locale == i18n.locale(),
+                                "{locale.name()}"
+                            }
                         }
                     }
+
+                    // Light, dark, then whatever the operating system uses
+                    button {
+                        class: "bg-blue-800 dark:bg-gray-700 rounded px-2 py-1",
+                        title: i18n.tr(
+                            "theme.toggle",
+                            &[("name", i18n.t(theme.theme.read().label_key()))],
+                        ),
+                        onclick: move |_| async move {
+                            let next = theme.theme.read().next();
+                            theme.theme.set(next);
+                            if let Err(e) = set_theme(next).await {
+                                toasts.error(i18n.tr("theme.save_failed", &[("error", e.to_string())]));
+                            }
+                        },
+                        {theme.theme.read().icon()}
+                    }
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::components::testing::{render_route, user};
+    use crate::server::auth::{ROLE_ADMIN, ROLE_HR, ROLE_IT};
+
+    /// Which of the Employees, Users, Roles and Webhooks links the header shows
+    fn links_for(role: Option<&str>) -> [bool; 4] {
+        let html = render_route("/", role.map(user));
+        ["/employees", "/users", "/roles", "/webhooks"]
+            .map(|to| html.contains(&format!(r#"href="{}""#, to)))
+    }
+
+    #[test]
+    fn signed_out_sees_only_home_and_login() {
+        let html = render_route("/", None);
+        assert!(html.contains(r#"href="/login""#));
+        assert!(!html.contains("Logout"));
+        assert_eq!(links_for(None), [false, false, false, false]);
+    }
+
+    #[test]
+    fn links_follow_the_permissions() {
+        assert_eq!(links_for(Some(ROLE_ADMIN)), [true, true, true, true]);
+        assert_eq!(links_for(Some(ROLE_HR)), [true, true, false, false]);
+        assert_eq!(links_for(Some(ROLE_IT)), [true, true, false, false]);
+        assert_eq!(
+            links_for(Some("ROLE_EMPLOYEE")),
+            [true, false, false, false]
+        );
+    }
+
+    #[test]
+    fn signed_in_can_log_out() {
+        assert!(render_route("/", Some(user(ROLE_HR))).contains("Logout"));
+    }
+
+    #[test]
+    fn offers_every_language_with_the_current_one_selected() {
+        let html = render_route("/", None);
+        assert!(html.contains(r#"<option value="en" selected=true>English</option>"#));
+        assert!(html.contains(r#"<option value="de">Deutsch</option>"#));
+    }
+
+    #[test]
+    fn theme_toggle_names_the_current_theme() {
+        let html = render_route("/", None);
+        assert!(html.contains(r#"title="Theme: System""#));
+        assert!(html.contains("🖥️"));
+    }
+}
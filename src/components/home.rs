@@ -1,14 +1,273 @@
+use crate::i18n::use_i18n;
+use crate::server::dashboard::{AccountActivity, Dashboard, DatedEmployee, get_dashboard_server};
+use crate::server::state::AuthState;
 use dioxus::prelude::*;
 
+/// The dashboard when signed in, the tagline otherwise
 #[component]
 pub fn Home() -> Element {
+    let auth = use_context::<AuthState>();
+    let i18n = use_i18n();
+
+    // Reads the user, so it reloads after login and logout
+    let dashboard = use_resource(move || async move {
+        if auth.logged_in() {
+            Some(get_dashboard_server().await)
+        } else {
+            None
+        }
+    });
+
+    if !auth.logged_in() {
+        return rsx! {
+            div {
+                class: "text-center",
+                h1 {
+                    class: "text-lg text-gray-600 dark:text-gray-300",
+                    {i18n.t("home.tagline")}
+                }
+            }
+        };
+    }
+
+    rsx! {
+        DashboardView {
+            dashboard: dashboard().flatten().map(|result| result.map_err(|e| e.to_string())),
+        }
+    }
+}
+
+/// The dashboard in its loading (`None`), error or loaded state. HR and
+/// admin panels appear when the server filled them in for the role.
+#[component]
+pub fn DashboardView(dashboard: Option<Result<Dashboard, String>>) -> Element {
+    let i18n = use_i18n();
+
+    let dashboard = match dashboard {
+        None => {
+            return rsx! {
+                p { class: "text-gray-600 dark:text-gray-300", {i18n.t("dashboard.loading")} }
+            };
+        }
+        Some(Err(err)) => {
+            return rsx! {
+                div {
+                    class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded dark:bg-red-900/40 dark:border-red-700 dark:text-red-200",
+                    {i18n.tr("dashboard.load_error", &[("error", err)])}
+                }
+            };
+        }
+        Some(Ok(dashboard)) => dashboard,
+    };
+
     rsx! {
         div {
-            class: "text-center",
+            class: "container mx-auto px-4 py-8",
             h1 {
-                class: "text-lg text-gray-600",
-                "Clean Dioxus + TailwindCSS base ready."
+                class: "text-3xl font-bold text-blue-600 mb-6 dark:text-blue-400",
+                {i18n.t("dashboard.title")}
+            }
+
+            div {
+                class: "grid grid-cols-1 md:grid-cols-3 gap-6 mb-6",
+                Stat {
+                    label: i18n.t("dashboard.employees"),
+                    value: i18n.number(dashboard.total_employees as i64),
+                }
+                Stat {
+                    label: i18n.t("dashboard.without_account"),
+                    value: i18n.number(dashboard.employees_without_account as i64),
+                }
+                Panel { title: i18n.t("dashboard.users_per_role"),
+                    for count in dashboard.users_per_role.iter() {
+                        li {
+                            key: "{count.role}",
+                            class: "flex justify-between",
+                            span { "{count.role}" }
+                            span { class: "font-semibold", {i18n.number(count.users)} }
+                        }
+                    }
+                }
             }
+
+            if let Some(hr) = &dashboard.hr {
+                div {
+                    class: "grid grid-cols-1 md:grid-cols-2 gap-6 mb-6",
+                    EmployeeList { title: i18n.t("dashboard.recent_hires"), entries: hr.recent_hires.clone() }
+                    EmployeeList { title: i18n.t("dashboard.recent_changes"), entries: hr.recent_changes.clone() }
+                }
+            }
+
+            if let Some(admin) = &dashboard.admin {
+                div {
+                    class: "grid grid-cols-1 md:grid-cols-2 gap-6",
+                    AccountList { title: i18n.t("dashboard.recent_logins"), entries: admin.recent_logins.clone() }
+                    AccountList {
+                        title: i18n.tr(
+                            "dashboard.locked_accounts",
+                            &[("count", i18n.number(admin.locked_total as i64))],
+                        ),
+                        entries: admin.locked_accounts.clone(),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn Stat(label: String, value: String) -> Element {
+    rsx! {
+        div {
+            class: "bg-white shadow-md rounded-lg p-6 dark:bg-gray-800",
+            p { class: "text-sm font-medium text-gray-600 dark:text-gray-300", "{label}" }
+            p { class: "text-3xl font-bold text-gray-900 dark:text-gray-100", "{value}" }
+        }
+    }
+}
+
+#[component]
+fn Panel(title: String, children: Element) -> Element {
+    rsx! {
+        section {
+            class: "bg-white shadow-md rounded-lg p-6 dark:bg-gray-800",
+            h2 { class: "text-sm font-medium text-gray-600 mb-3 dark:text-gray-300", "{title}" }
+            ul { class: "space-y-1 text-gray-900 dark:text-gray-100", {children} }
         }
     }
 }
+
+#[component]
+fn EmployeeList(title: String, entries: Vec<DatedEmployee>) -> Element {
+    let i18n = use_i18n();
+
+    rsx! {
+        Panel { title,
+            if entries.is_empty() {
+                li { class: "text-gray-600 dark:text-gray-300", {i18n.t("dashboard.none")} }
+            }
+            for entry in entries {
+                li {
+                    key: "{entry.employee.id}",
+                    class: "flex justify-between gap-4",
+                    span {
+                        "{entry.employee.first_name.as_deref().unwrap_or_default()} {entry.employee.last_name.as_deref().unwrap_or_default()}"
+                    }
+                    span { class: "text-sm text-gray-600 dark:text-gray-300", {i18n.datetime(&entry.at)} }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn AccountList(title: String, entries: Vec<AccountActivity>) -> Element {
+    let i18n = use_i18n();
+
+    rsx! {
+        Panel { title,
+            if entries.is_empty() {
+                li { class: "text-gray-600 dark:text-gray-300", {i18n.t("dashboard.none")} }
+            }
+            for entry in entries {
+                li {
+                    key: "{entry.user_id}",
+                    class: "flex justify-between gap-4",
+                    span { "{entry.email}" }
+                    if let Some(at) = &entry.last_login_at {
+                        span { class: "text-sm text-gray-600 dark:text-gray-300", {i18n.datetime(at)} }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::testing::{render, render_in};
+    use crate::i18n::Locale;
+    use crate::server::dashboard::{AdminActivity, HrActivity, RoleCount};
+    use crate::server::db::Employee;
+
+    fn everyone() -> Dashboard {
+        Dashboard {
+            total_employees: 1234,
+            employees_without_account: 3,
+            users_per_role: vec![RoleCount {
+                role: "ROLE_HR".into(),
+                users: 2,
+            }],
+            hr: None,
+            admin: None,
+        }
+    }
+
+    fn props(dashboard: Dashboard) -> DashboardViewProps {
+        DashboardViewProps {
+            dashboard: Some(Ok(dashboard)),
+        }
+    }
+
+    #[test]
+    fn shows_the_counts_for_everyone() {
+        let html = render(DashboardView, props(everyone()));
+        assert!(html.contains("1,234"));
+        assert!(html.contains("Without user account"));
+        assert!(html.contains("ROLE_HR"));
+        assert!(!html.contains("Recent hires"));
+        assert!(!html.contains("Recent logins"));
+    }
+
+    #[test]
+    fn adds_the_hr_and_admin_panels() {
+        let html = render(
+            DashboardView,
+            props(Dashboard {
+                hr: Some(HrActivity {
+                    recent_hires: vec![DatedEmployee {
+                        employee: Employee {
+                            id: 1,
+                            first_name: Some("Ada".into()),
+                            last_name: Some("Lovelace".into()),
+                            email: None,
+                        },
+                        at: "2026-01-05T14:03:00+00:00".into(),
+                    }],
+                    recent_changes: Vec::new(),
+                }),
+                admin: Some(AdminActivity {
+                    recent_logins: Vec::new(),
+                    locked_accounts: Vec::new(),
+                    locked_total: 0,
+                }),
+                ..everyone()
+            }),
+        );
+        assert!(html.contains("Ada Lovelace"));
+        assert!(html.contains("Jan 5, 2026"));
+        assert!(html.contains("Nothing yet."));
+        assert!(html.contains("Locked accounts (0)"));
+    }
+
+    #[test]
+    fn shows_loading_and_errors() {
+        let loading = render(DashboardView, DashboardViewProps { dashboard: None });
+        assert!(loading.contains("Loading dashboard..."));
+        let failed = render(
+            DashboardView,
+            DashboardViewProps {
+                dashboard: Some(Err("Not signed in".into())),
+            },
+        );
+        assert!(failed.contains("Error loading dashboard: Not signed in"));
+    }
+
+    #[test]
+    fn formats_numbers_for_the_locale() {
+        let html = render_in(Locale::De, DashboardView, props(everyone()));
+        assert!(html.contains("1.234"));
+        assert!(html.contains("Ohne Benutzerkonto"));
+    }
+}
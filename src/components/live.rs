@@ -0,0 +1,111 @@
+//! Live table updates driven by `subscribe_changes()`
+//!
+//! Only the browser build subscribes; during SSR the hooks are inert.
+
+use std::collections::HashSet;
+
+use crate::server::events::ChangeEvent;
+use dioxus::prelude::*;
+
+/// How long a changed row stays highlighted
+const HIGHLIGHT_MS: u32 = 3_000;
+
+/// Delay before reconnecting after the stream drops
+const RECONNECT_MS: u32 = 3_000;
+
+/// Call `handler` for every employee/user row change, reconnecting as needed
+pub fn use_live_changes(handler: impl FnMut(ChangeEvent) + 'static) {
+    let handler = use_callback(handler);
+
+    use_future(move || async move {
+        #[cfg(feature = "web")]
+        loop {
+            if let Ok(mut stream) = crate::server::events::subscribe_changes().await {
+                while let Some(Ok(event)) = stream.recv().await {
+                    handler.call(event);
+                }
+            }
+            sleep(RECONNECT_MS).await;
+        }
+
+        #[cfg(not(feature = "web"))]
+        let _ = handler;
+    });
+}
+
+/// Row ids that changed recently and should be highlighted
+#[derive(Clone, Copy, PartialEq)]
+pub struct Highlights(Signal<HashSet<i32>>);
+
+pub fn use_highlights() -> Highlights {
+    Highlights(use_signal(HashSet::new))
+}
+
+impl Highlights {
+    pub fn contains(&self, id: i32) -> bool {
+        self.0.read().contains(&id)
+    }
+
+    /// Highlight `id` for a few seconds
+    pub fn flash(mut self, id: i32) {
+        self.0.write().insert(id);
+        spawn(async move {
+            sleep(HIGHLIGHT_MS).await;
+            self.0.write().remove(&id);
+        });
+    }
+
+    /// Row class for `id`: highlighted or the regular hover style
+    pub fn row_class(&self, id: i32) -> &'static str {
+        if self.contains(id) {
+            "bg-yellow-100 transition-colors duration-1000 dark:bg-yellow-900/40"
+        } else {
+            "hover:bg-gray-50 transition-colors duration-1000 dark:hover:bg-gray-700"
+        }
+    }
+}
+
+/// Patch `list` after row `id` changed: replace or append the fresh copy,
+/// or drop the row when it no longer exists
+pub fn apply_change<T>(list: &mut Vec<T>, id: i32, fresh: Option<T>, key: impl Fn(&T) -> i32) {
+    let position = list.iter().position(|item| key(item) == id);
+    match (position, fresh) {
+        (Some(i), Some(item)) => list[i] = item,
+        (None, Some(item)) => list.push(item),
+        (Some(i), None) => {
+            list.remove(i);
+        }
+        (None, None) => {}
+    }
+}
+
+async fn sleep(ms: u32) {
+    #[cfg(feature = "web")]
+    gloo_timers::future::TimeoutFuture::new(ms).await;
+
+    #[cfg(not(feature = "web"))]
+    let _ = ms;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply_change;
+
+    #[test]
+    fn apply_change_replaces_appends_and_removes() {
+        let key = |(id, _): &(i32, &str)| *id;
+        let mut list = vec![(1, "a"), (2, "b")];
+
+        apply_change(&mut list, 2, Some((2, "B")), key);
+        assert_eq!(list, vec![(1, "a"), (2, "B")]);
+
+        apply_change(&mut list, 3, Some((3, "c")), key);
+        assert_eq!(list, vec![(1, "a"), (2, "B"), (3, "c")]);
+
+        apply_change(&mut list, 1, None, key);
+        assert_eq!(list, vec![(2, "B"), (3, "c")]);
+
+        apply_change(&mut list, 9, None, key);
+        assert_eq!(list.len(), 2);
+    }
+}
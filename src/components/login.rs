@@ -1,38 +1,47 @@
 use dioxus::prelude::*;
 
+use crate::app::Route;
+use crate::components::toast::use_toasts;
+use crate::i18n::use_i18n;
+use crate::server::auth::{current_locale, login};
 use crate::server::state::AuthState;
 
 #[component]
 pub fn Login() -> Element {
     // Signals for form fields
-    let mut email = use_signal(|| String::new());
-    let mut password = use_signal(|| String::new());
-    
-    let _auth = use_context::<AuthState>();
+    let mut email = use_signal(String::new);
+    let mut password = use_signal(String::new);
+
+    let mut error = use_signal(|| None::<String>);
+
+    let mut auth = use_context::<AuthState>();
+    let toasts = use_toasts();
+    let mut i18n = use_i18n();
+    let nav = use_navigator();
 
     rsx! {
-        div { 
-            class: "bg-gray-100 flex justify-center pt-32 pb-20 min-h-[400px]",
+        div {
+            class: "bg-gray-100 flex justify-center pt-32 pb-20 min-h-[400px] dark:bg-gray-900",
 
             // Login card
             div {
-                class: "bg-white shadow-lg rounded-lg p-8 w-full max-w-md",
+                class: "bg-white shadow-lg rounded-lg p-8 w-full max-w-md dark:bg-gray-800",
 
                 h2 {
                     class: "text-2xl font-bold text-center mb-6",
-                    "Welcome Back"
+                    {i18n.t("auth.welcome")}
                 }
 
                 p {
-                    class: "text-gray-600 text-center mb-6",
-                    "Please sign in to continue."
+                    class: "text-gray-600 text-center mb-6 dark:text-gray-300",
+                    {i18n.t("auth.prompt")}
                 }
 
                 // Email field
                 div { class: "mb-4",
-                    label { class: "block mb-1 font-medium text-gray-700", "Email" }
+                    label { class: "block mb-1 font-medium text-gray-700 dark:text-gray-200", {i18n.t("common.email")} }
                     input {
-                        class: "w-full px-4 py-2 border rounded focus:ring-2 focus:ring-blue-400 focus:outline-none",
+                        class: "w-full px-4 py-2 border rounded focus:ring-2 focus:ring-blue-400 focus:outline-none dark:bg-gray-700 dark:text-gray-100 dark:border-gray-600",
                         r#type: "email",
                         value: "{email()}",
                         placeholder: "you@example.com",
@@ -42,9 +51,9 @@ pub fn Login() -> Element {
 
                 // Password field
                 div { class: "mb-6",
-                    label { class: "block mb-1 font-medium text-gray-700", "Password" }
+                    label { class: "block mb-1 font-medium text-gray-700 dark:text-gray-200", {i18n.t("auth.password")} }
                     input {
-                        class: "w-full px-4 py-2 border rounded focus:ring-2 focus:ring-blue-400 focus:outline-none",
+                        class: "w-full px-4 py-2 border rounded focus:ring-2 focus:ring-blue-400 focus:outline-none dark:bg-gray-700 dark:text-gray-100 dark:border-gray-600",
                         r#type: "password",
                         value: "{password()}",
                         placeholder: "••••••••",
@@ -52,24 +61,64 @@ pub fn Login() -> Element {
                     }
                 }
 
+                if let Some(message) = error() {
+                    p { class: "text-red-600 text-sm mb-4 dark:text-red-400", "{message}" }
+                }
+
                 // Login button
                 button {
                     class: "w-full bg-blue-600 text-white py-2 rounded font-semibold hover:bg-blue-700 transition-colors",
-                    onclick: move |_| {
-                        println!("Login attempt: {} / {}", email(), password());
+                    onclick: move |_| async move {
+                        match login(email(), password()).await {
+                            Ok(session) => {
+                                error.set(None);
+                                password.set(String::new());
+                                // Login switched the cookie to the user's saved language
+                                if let Ok(saved) = current_locale().await {
+                                    i18n.locale.set(saved);
+                                }
+                                toasts.success(i18n.tr("auth.signed_in", &[("email", session.email.clone())]));
+                                auth.user.set(Some(session));
+                                nav.push(Route::Employees {});
+                            }
+                            Err(_) => error.set(Some(i18n.t("error.invalid_login"))),
+                        }
                     },
-                    "Login"
+                    {i18n.t("auth.submit")}
                 }
 
                 // optional forgot password
                 div {
                     class: "text-center mt-4",
                     a {
-                        class: "text-blue-600 hover:underline cursor-pointer",
-                        "Forgot password?"
+                        class: "text-blue-600 hover:underline cursor-pointer dark:text-blue-400",
+                        {i18n.t("auth.forgot_password")}
                     }
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::components::testing::{render_route, render_route_in};
+    use crate::i18n::Locale;
+
+    #[test]
+    fn renders_an_empty_form_without_an_error() {
+        let html = render_route("/login", None);
+        assert!(html.contains("Welcome Back"));
+        assert!(html.contains(r#"type="email""#));
+        assert!(html.contains(r#"type="password""#));
+        assert!(!html.contains("Invalid email or password"));
+    }
+
+    #[test]
+    fn speaks_german() {
+        let html = render_route_in(Locale::De, "/login", None);
+        assert!(html.contains("Willkommen zurück"));
+        assert!(html.contains("Passwort vergessen?"));
+        assert!(!html.contains("Welcome Back"));
+    }
+}
@@ -1,7 +1,17 @@
-pub mod employees;
+pub mod data_table;
+pub mod employee_detail;
 pub mod employee_modal;
+pub mod employees;
 pub mod footer;
 pub mod header;
 pub mod home;
+pub mod live;
+pub mod login;
+pub mod roles;
+pub mod toast;
+pub mod user_modal;
 pub mod users;
-pub mod login;
\ No newline at end of file
+pub mod webhooks;
+
+#[cfg(test)]
+mod testing;
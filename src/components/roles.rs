@@ -0,0 +1,235 @@
+use crate::components::toast::use_toasts;
+use crate::i18n::use_i18n;
+use crate::server::auth::PERMISSIONS;
+use crate::server::roles::{Role, create_role, get_roles_server, set_role_permissions};
+use dioxus::prelude::*;
+
+const TH_CLASS: &str =
+    "px-4 py-3 text-xs font-medium text-gray-500 uppercase tracking-wider dark:text-gray-300";
+
+#[component]
+pub fn Roles() -> Element {
+    let mut roles = use_resource(|| async move { get_roles_server().await });
+    let mut name = use_signal(String::new);
+    let toasts = use_toasts();
+    let i18n = use_i18n();
+
+    let handle_create = move |_| {
+        spawn(async move {
+            match create_role(name()).await {
+                Ok(created) => {
+                    toasts.success(i18n.tr("roles.created", &[("role", created.name)]));
+                    name.set(String::new());
+                    roles.restart();
+                }
+                Err(e) => toasts.error(i18n.tr("common.error", &[("error", e.to_string())])),
+            }
+        });
+    };
+
+    // Saves at once; the row is patched with what the server stored
+    let toggle = move |(role, permission): (Role, &'static str)| {
+        let mut permissions = role.permissions.clone();
+        if let Some(at) = permissions.iter().position(|p| p == permission) {
+            permissions.remove(at);
+        } else {
+            permissions.push(permission.to_string());
+        }
+        spawn(async move {
+            match set_role_permissions(role.id, permissions).await {
+                Ok(saved) => {
+                    toasts.success(i18n.tr("roles.saved", &[("role", saved.name.clone())]));
+                    if let Some(Ok(list)) = roles.write().as_mut() {
+                        let row = list.iter_mut().find(|r| r.id == saved.id);
+                        if let Some(row) = row {
+                            *row = saved;
+                        }
+                    }
+                }
+                Err(e) => toasts.error(i18n.tr("common.error", &[("error", e.to_string())])),
+            }
+        });
+    };
+
+    rsx! {
+        div {
+            class: "container mx-auto px-4 py-8",
+
+            h1 {
+                class: "text-3xl font-bold text-blue-600 mb-6 dark:text-blue-400",
+                {i18n.t("roles.title")}
+            }
+
+            // New role
+            div {
+                class: "bg-white shadow-md rounded-lg p-6 mb-8 dark:bg-gray-800",
+                div {
+                    class: "flex gap-3 items-end",
+                    div {
+                        class: "flex-1",
+                        label { class: "block text-sm font-medium text-gray-700 mb-2 dark:text-gray-200", {i18n.t("roles.new_name")} }
+                        input {
+                            class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 dark:border-gray-600 dark:bg-gray-700 dark:text-gray-100",
+                            r#type: "text",
+                            value: "{name}",
+                            oninput: move |e| name.set(e.value()),
+                            placeholder: "ROLE_AUDITOR",
+                        }
+                    }
+                    button {
+                        class: "px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700 disabled:opacity-50",
+                        disabled: name.read().trim().is_empty(),
+                        onclick: handle_create,
+                        {i18n.t("roles.add")}
+                    }
+                }
+            }
+
+            RoleMatrix {
+                roles: roles().map(|result| result.map_err(|e| e.to_string())),
+                on_toggle: toggle,
+            }
+        }
+    }
+}
+
+/// One row per role and one checkbox per permission, in its loading
+/// (`None`), error or loaded state
+#[component]
+pub fn RoleMatrix(
+    roles: Option<Result<Vec<Role>, String>>,
+    on_toggle: EventHandler<(Role, &'static str)>,
+) -> Element {
+    let i18n = use_i18n();
+
+    let roles = match roles {
+        None => {
+            return rsx! {
+                p { class: "p-6 text-gray-600 dark:text-gray-300", {i18n.t("roles.loading")} }
+            };
+        }
+        Some(Err(err)) => {
+            return rsx! {
+                div {
+                    class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded dark:bg-red-900/40 dark:border-red-700 dark:text-red-200",
+                    {i18n.tr("roles.load_error", &[("error", err)])}
+                }
+            };
+        }
+        Some(Ok(roles)) => roles,
+    };
+
+    rsx! {
+        div {
+            class: "bg-white shadow-md rounded-lg overflow-x-auto dark:bg-gray-800",
+            table {
+                class: "min-w-full divide-y divide-gray-200 dark:divide-gray-700",
+                thead {
+                    class: "bg-gray-50 dark:bg-gray-700",
+                    tr {
+                        th { class: "{TH_CLASS} text-left", {i18n.t("roles.role")} }
+                        for permission in PERMISSIONS {
+                            th {
+                                key: "{permission}",
+                                class: "{TH_CLASS} text-center",
+                                title: *permission,
+                                {i18n.t(&format!("permission.{}", permission))}
+                            }
+                        }
+                    }
+                }
+                tbody {
+                    class: "bg-white divide-y divide-gray-200 dark:bg-gray-800 dark:divide-gray-700",
+                    for role in roles {
+                        tr {
+                            key: "{role.id}",
+                            class: "hover:bg-gray-50 dark:hover:bg-gray-700",
+                            td { class: "px-4 py-3 whitespace-nowrap text-sm font-medium text-gray-900 dark:text-gray-100", "{role.name}" }
+                            for permission in PERMISSIONS {
+                                td {
+                                    key: "{permission}",
+                                    class: "px-4 py-3 text-center",
+                                    input {
+                                        r#type: "checkbox",
+                                        "aria-label": "{role.name}: {permission}",
+                                        checked: role.permissions.iter().any(|p| p == permission),
+                                        onchange: {
+                                            let role = role.clone();
+                                            move |_| on_toggle.call((role.clone(), *permission))
+                                        },
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::testing::{render, render_in};
+    use crate::i18n::Locale;
+
+    #[component]
+    fn Matrix(roles: Option<Result<Vec<Role>, String>>) -> Element {
+        rsx! {
+            RoleMatrix { roles, on_toggle: |_| {} }
+        }
+    }
+
+    fn hr() -> Role {
+        Role {
+            id: 3,
+            name: "ROLE_HR".into(),
+            permissions: vec!["employee.read".into(), "employee.write".into()],
+        }
+    }
+
+    #[test]
+    fn ticks_the_granted_permissions() {
+        let html = render(
+            Matrix,
+            MatrixProps {
+                roles: Some(Ok(vec![hr()])),
+            },
+        );
+        assert!(html.contains("ROLE_HR"));
+        assert!(html.contains("Edit employees"));
+        assert!(html.contains("Manage webhooks"));
+        assert_eq!(html.matches("checked=true").count(), 2);
+        assert_eq!(
+            html.matches(r#"type="checkbox""#).count(),
+            PERMISSIONS.len()
+        );
+    }
+
+    #[test]
+    fn shows_loading_and_errors() {
+        assert!(render(Matrix, MatrixProps { roles: None }).contains("Loading roles..."));
+        let html = render(
+            Matrix,
+            MatrixProps {
+                roles: Some(Err("Permission denied".into())),
+            },
+        );
+        assert!(html.contains("Error loading roles: Permission denied"));
+        assert!(!html.contains("<table"));
+    }
+
+    #[test]
+    fn names_permissions_in_german() {
+        let html = render_in(
+            Locale::De,
+            Matrix,
+            MatrixProps {
+                roles: Some(Ok(vec![hr()])),
+            },
+        );
+        assert!(html.contains("Mitarbeiter bearbeiten"));
+        assert!(html.contains("Rollen verwalten"));
+    }
+}
@@ -0,0 +1,94 @@
+//! Server-side rendering helpers for component tests
+//!
+//! Components render once into a `VirtualDom` and come back as HTML, without
+//! a browser or a server. Pages are tested through their presentational
+//! parts (`EmployeeTable`, `UserTable`), which take the server function
+//! result as a prop: a test stands in for the server by passing `None`
+//! (still loading), `Some(Err(..))` or rows. Everything renders in English
+//! unless a test asks for another locale with `render_in` or
+//! `render_route_in`.
+
+use std::rc::Rc;
+
+use dioxus::core::ComponentFunction;
+use dioxus::history::{History, MemoryHistory};
+use dioxus::prelude::*;
+use dioxus_router::Router;
+
+use crate::app::Route;
+use crate::components::toast::Toasts;
+use crate::i18n::{I18n, Locale};
+use crate::server::auth::{DEFAULT_PERMISSIONS, SessionUser};
+use crate::server::state::AuthState;
+use crate::theme::{Theme, ThemeState};
+
+/// Render `component` with `props`, signed out
+pub fn render<P: Clone + 'static, M: 'static>(
+    component: impl ComponentFunction<P, M>,
+    props: P,
+) -> String {
+    render_in(Locale::En, component, props)
+}
+
+/// Render `component` with `props`, signed out, in `locale`
+pub fn render_in<P: Clone + 'static, M: 'static>(
+    locale: Locale,
+    component: impl ComponentFunction<P, M>,
+    props: P,
+) -> String {
+    let dom = VirtualDom::new_with_props(component, props);
+    finish(dom, None, locale)
+}
+
+/// Render the app's router at `path`, signed in as `user`
+pub fn render_route(path: &str, user: Option<SessionUser>) -> String {
+    render_route_in(Locale::En, path, user)
+}
+
+/// Render the app's router at `path`, signed in as `user`, in `locale`
+pub fn render_route_in(locale: Locale, path: &str, user: Option<SessionUser>) -> String {
+    let dom = VirtualDom::new(Routes);
+    dom.provide_root_context(Rc::new(MemoryHistory::with_initial_path(path)) as Rc<dyn History>);
+    finish(dom, user, locale)
+}
+
+/// A made-up signed-in account with `role` and its default permissions
+pub fn user(role: &str) -> SessionUser {
+    let permissions = DEFAULT_PERMISSIONS
+        .iter()
+        .find(|(name, _)| *name == role)
+        .map(|(_, granted)| granted.iter().map(|p| p.to_string()).collect())
+        .unwrap_or_default();
+    SessionUser {
+        id: 1,
+        email: "ada@example.com".into(),
+        role: role.into(),
+        permissions,
+    }
+}
+
+#[component]
+fn Routes() -> Element {
+    rsx! { Router::<Route> {} }
+}
+
+fn finish(mut dom: VirtualDom, user: Option<SessionUser>, locale: Locale) -> String {
+    let (auth, toasts, i18n, theme) = dom.in_scope(ScopeId::ROOT, || {
+        let auth = AuthState {
+            user: Signal::new(user),
+        };
+        let i18n = I18n {
+            locale: Signal::new(locale),
+        };
+        let theme = ThemeState {
+            theme: Signal::new(Theme::default()),
+        };
+        (auth, Toasts::new(), i18n, theme)
+    });
+    dom.provide_root_context(auth);
+    dom.provide_root_context(toasts);
+    dom.provide_root_context(i18n);
+    dom.provide_root_context(theme);
+    dom.rebuild_in_place();
+    dioxus_ssr::render(&dom)
+}
@@ -0,0 +1,237 @@
+//! Toast notifications for the outcome of server function calls
+//!
+//! `App` provides a `Toasts` context and renders the `ToastStack` outside
+//! the router, so toasts survive navigation. Components report with
+//! `success`, `error`, `warning` or `info` and an already translated
+//! message; `show` also takes an action button such as "Undo". Toasts stack
+//! in the bottom-right corner, the oldest drop off beyond `MAX_VISIBLE`, and
+//! in the browser they dismiss themselves after a few seconds.
+
+use crate::i18n::use_i18n;
+use dioxus::core::spawn_forever;
+use dioxus::prelude::*;
+
+const DISMISS_MS: u32 = 5_000;
+
+/// Errors and toasts with an action stay up longer
+const DISMISS_LONG_MS: u32 = 10_000;
+
+const MAX_VISIBLE: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ToastKind {
+    Success,
+    Error,
+    Warning,
+    Info,
+}
+
+impl ToastKind {
+    fn class(self) -> &'static str {
+        match self {
+            Self::Success => {
+                "bg-green-50 border-green-400 text-green-800 dark:bg-green-900 dark:text-green-100"
+            }
+            Self::Error => {
+                "bg-red-50 border-red-400 text-red-800 dark:bg-red-900 dark:text-red-100"
+            }
+            Self::Warning => {
+                "bg-yellow-50 border-yellow-400 text-yellow-800 dark:bg-yellow-900 dark:text-yellow-100"
+            }
+            Self::Info => {
+                "bg-blue-50 border-blue-400 text-blue-800 dark:bg-blue-900 dark:text-blue-100"
+            }
+        }
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            Self::Success => "✓",
+            Self::Error => "✕",
+            Self::Warning => "!",
+            Self::Info => "i",
+        }
+    }
+}
+
+/// A button on the toast; clicking it runs `on_click` and closes the toast
+#[derive(Clone, PartialEq)]
+pub struct ToastAction {
+    /// Catalog key of the button text
+    pub label: &'static str,
+    pub on_click: Callback<()>,
+}
+
+#[derive(Clone, PartialEq)]
+struct Toast {
+    id: u64,
+    kind: ToastKind,
+    message: String,
+    action: Option<ToastAction>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Toasts {
+    list: Signal<Vec<Toast>>,
+    next_id: Signal<u64>,
+}
+
+impl Default for Toasts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Self {
+            list: Signal::new(Vec::new()),
+            next_id: Signal::new(0),
+        }
+    }
+
+    pub fn success(self, message: impl Into<String>) {
+        self.show(ToastKind::Success, message, None);
+    }
+
+    pub fn error(self, message: impl Into<String>) {
+        self.show(ToastKind::Error, message, None);
+    }
+
+    pub fn warning(self, message: impl Into<String>) {
+        self.show(ToastKind::Warning, message, None);
+    }
+
+    pub fn info(self, message: impl Into<String>) {
+        self.show(ToastKind::Info, message, None);
+    }
+
+    /// Show a toast and return its id for `dismiss`
+    pub fn show(
+        mut self,
+        kind: ToastKind,
+        message: impl Into<String>,
+        action: Option<ToastAction>,
+    ) -> u64 {
+        let id = *self.next_id.peek();
+        self.next_id.set(id + 1);
+
+        let lingers = kind == ToastKind::Error || action.is_some();
+        let mut list = self.list.write();
+        list.push(Toast {
+            id,
+            kind,
+            message: message.into(),
+            action,
+        });
+        let excess = list.len().saturating_sub(MAX_VISIBLE);
+        list.drain(..excess);
+        drop(list);
+
+        self.dismiss_after(id, if lingers { DISMISS_LONG_MS } else { DISMISS_MS });
+        id
+    }
+
+    pub fn dismiss(mut self, id: u64) {
+        self.list.write().retain(|toast| toast.id != id);
+    }
+
+    fn dismiss_after(self, id: u64, ms: u32) {
+        // Not tied to the caller's scope: the page may be gone by then
+        #[cfg(feature = "web")]
+        spawn_forever(async move {
+            gloo_timers::future::TimeoutFuture::new(ms).await;
+            self.dismiss(id);
+        });
+
+        // Elsewhere toasts stay until closed
+        #[cfg(not(feature = "web"))]
+        let _ = (id, ms);
+    }
+}
+
+pub fn use_toasts() -> Toasts {
+    use_context::<Toasts>()
+}
+
+#[component]
+pub fn ToastStack() -> Element {
+    let toasts = use_toasts();
+    let i18n = use_i18n();
+
+    rsx! {
+        div {
+            class: "fixed bottom-20 right-4 z-50 flex flex-col gap-2 w-80",
+            for toast in toasts.list.read().iter().cloned() {
+                div {
+                    key: "{toast.id}",
+                    role: if toast.kind == ToastKind::Error { "alert" } else { "status" },
+                    class: "flex items-start gap-3 border-l-4 rounded-md shadow-lg px-4 py-3 text-sm {toast.kind.class()}",
+                    span { class: "font-bold", "{toast.kind.icon()}" }
+                    p { class: "flex-1", "{toast.message}" }
+                    if let Some(action) = toast.action.clone() {
+                        button {
+                            class: "font-semibold underline",
+                            onclick: move |_| {
+                                action.on_click.call(());
+                                toasts.dismiss(toast.id);
+                            },
+                            {i18n.t(action.label)}
+                        }
+                    }
+                    button {
+                        class: "opacity-60 hover:opacity-100",
+                        onclick: move |_| toasts.dismiss(toast.id),
+                        "✕"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::testing::render;
+
+    #[component]
+    fn Stack(count: usize) -> Element {
+        let toasts = use_toasts();
+        use_hook(|| {
+            toasts.success("Employee created");
+            toasts.error("Delete failed: Permission denied");
+            for n in 0..count {
+                toasts.info(format!("Note {}", n));
+            }
+            toasts.show(
+                ToastKind::Warning,
+                "Deleted Ada Lovelace",
+                Some(ToastAction {
+                    label: "common.undo",
+                    on_click: Callback::new(|_| {}),
+                }),
+            );
+        });
+        rsx! { ToastStack {} }
+    }
+
+    #[test]
+    fn renders_each_kind_with_its_action() {
+        let html = render(Stack, StackProps { count: 0 });
+        assert!(html.contains("Employee created"));
+        assert!(html.contains(r#"role="alert""#));
+        assert!(html.contains("Delete failed: Permission denied"));
+        assert!(html.contains("Undo"));
+        assert!(html.contains("bg-yellow-50"));
+    }
+
+    #[test]
+    fn oldest_toasts_drop_off() {
+        let html = render(Stack, StackProps { count: 4 });
+        assert!(!html.contains("Employee created"));
+        assert!(!html.contains("Delete failed"));
+        assert!(html.contains("Note 0"));
+        assert!(html.contains("Deleted Ada Lovelace"));
+    }
+}
@@ -1,106 +1,401 @@
-use crate::db::{User, create_user, update_user};
+use crate::components::employees::full_name;
+use crate::components::toast::use_toasts;
+use crate::i18n::use_i18n;
+use crate::server::auth::ROLE_EMPLOYEE;
+use crate::server::db::{
+    Employee, NewUser, UserExtended, create_user, get_unlinked_employees_server, update_user,
+};
+use crate::server::roles::get_roles_server;
+use crate::validation::{self, FieldErrors};
 use dioxus::prelude::*;
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum UserModalMode {
-    Create,
-    Edit(User),
+    /// A new account, for the employee it was started from if any
+    Create(Option<Employee>),
+    Edit(UserExtended),
 }
 
+/// The employee an account is linked to: id and display name
+#[derive(Clone, PartialEq)]
+struct Linked {
+    id: i32,
+    name: String,
+}
+
+impl From<&Employee> for Linked {
+    fn from(e: &Employee) -> Self {
+        Self {
+            id: e.id,
+            name: full_name(e),
+        }
+    }
+}
+
+/// Creates or edits a user, including the employee it is linked to. The
+/// picker only offers employees without an account; the server checks
+/// again, so a race ends in an error rather than a second link.
 #[component]
 pub fn UserModal(
     mode: UserModalMode,
     on_close: EventHandler<()>,
     on_save: EventHandler<()>,
 ) -> Element {
-    let email = use_signal(|| match &mode {
-        UserModalMode::Create => "".to_string(),
-        UserModalMode::Edit(u) => u.email.clone(),
+    let mut email = use_signal(|| match &mode {
+        UserModalMode::Create(employee) => employee
+            .as_ref()
+            .and_then(|e| e.email.clone())
+            .unwrap_or_default(),
+        UserModalMode::Edit(user) => user.email.clone(),
+    });
+    let mut role = use_signal(|| match &mode {
+        UserModalMode::Create(_) => ROLE_EMPLOYEE.to_string(),
+        UserModalMode::Edit(user) => user.role_name.clone(),
     });
+    let mut linked = use_signal(|| match &mode {
+        UserModalMode::Create(employee) => employee.as_ref().map(Linked::from),
+        UserModalMode::Edit(user) => user.employee_id.map(|id| Linked {
+            id,
+            name: user.employee_name.clone().unwrap_or_default(),
+        }),
+    });
+    // Until the roles load, the current one is the only choice
+    let roles = use_resource(|| async move { get_roles_server().await });
+    let role_names = move || -> Vec<String> {
+        match &*roles.read() {
+            Some(Ok(list)) => list.iter().map(|r| r.name.clone()).collect(),
+            _ => vec![role()],
+        }
+    };
+    let mut search = use_signal(String::new);
+    let candidates =
+        use_resource(move || async move { get_unlinked_employees_server(search()).await });
+
+    let mut is_saving = use_signal(|| false);
+    let mut error_message = use_signal(|| None::<String>);
+    let mut server_errors = use_signal(FieldErrors::default);
+    // Set after a create, to show the generated password once
+    let mut created = use_signal(|| None::<NewUser>);
+    let errors = use_memo(move || validation::user(&email()));
+
+    let i18n = use_i18n();
+    let toasts = use_toasts();
+    let email_error = move || -> Option<String> {
+        let error = match server_errors.read().get("email") {
+            Some(error) => error,
+            None if !email.read().is_empty() => errors.read().get("email")?,
+            None => return None,
+        };
+        Some(i18n.message(&error))
+    };
 
-    let role = use_signal(|| match &mode {
-        UserModalMode::Create => "ROLE_EMPLOYEE".to_string(),
-        UserModalMode::Edit(u) => u.role_name.clone(),
+    let mode_for_save = mode.clone();
+    let handle_save = move |_| {
+        let mode = mode_for_save.clone();
+        spawn(async move {
+            is_saving.set(true);
+            error_message.set(None);
+
+            let employee_id = linked.read().as_ref().map(|l| l.id);
+            // A create answers with the password to show; an edit is done
+            let result = match &mode {
+                UserModalMode::Create(_) => {
+                    create_user(email(), role(), employee_id).await.map(Some)
+                }
+                UserModalMode::Edit(user) => {
+                    update_user(user.user_id, email(), role(), employee_id)
+                        .await
+                        .map(|_| None)
+                }
+            };
+
+            match result {
+                Ok(new) => {
+                    on_save.call(());
+                    match new {
+                        Some(new) => created.set(Some(new)),
+                        None => {
+                            toasts.success(i18n.t("users.updated"));
+                            on_close.call(());
+                        }
+                    }
+                }
+                Err(e) => match FieldErrors::from_server(&e) {
+                    Some(fields) => server_errors.set(fields),
+                    None => error_message
+                        .set(Some(i18n.tr("common.error", &[("error", e.to_string())]))),
+                },
+            }
+
+            is_saving.set(false);
+        });
+    };
+
+    let title = i18n.t(match &mode {
+        UserModalMode::Create(_) => "users.create_title",
+        UserModalMode::Edit(_) => "users.edit_title",
+    });
+    let button_text = i18n.t(match &mode {
+        UserModalMode::Create(_) => "users.create",
+        UserModalMode::Edit(_) => "users.update",
     });
 
     rsx! {
         div {
-            class: "fixed inset-0 flex items-center justify-center bg-black/30 z-50",
+            class: "fixed inset-0 flex items-center justify-center z-50",
+            style: "background-color: rgba(0, 0, 0, 0.4);",
+            onclick: move |_| on_close.call(()),
 
             div {
-                class: "bg-white p-6 rounded-lg w-full max-w-md",
+                class: "bg-white rounded-lg shadow-xl max-w-md w-full mx-4 dark:bg-gray-800",
+                onclick: move |e| e.stop_propagation(),
 
-                h3 {
-                    class: "text-xl font-bold mb-4",
-                    match mode {
-                        UserModalMode::Create => "Create User",
-                        UserModalMode::Edit(_) => "Edit User",
+                // Header
+                div {
+                    class: "flex justify-between items-center p-6 border-b dark:border-gray-700",
+                    h2 {
+                        class: "text-xl font-bold text-gray-800 dark:text-gray-100",
+                        "{title}"
                     }
-                }
-
-                // EMAIL
-                div { class: "mb-4",
-                    label { class: "block text-sm font-medium text-gray-700 mb-1", "Email" }
-                    input {
-                        class: "w-full px-3 py-2 border rounded",
-                        value: "{email()}",
-                        oninput: move |e| email.set(e.value())
+                    button {
+                        class: "text-gray-500 hover:text-gray-600 dark:text-gray-300 dark:hover:text-gray-200",
+                        onclick: move |_| on_close.call(()),
+                        "✕"
                     }
                 }
 
-                // ROLE
-                div { class: "mb-4",
-                    label { class: "block text-sm font-medium text-gray-700 mb-1", "Role" }
-                    select {
-                        class: "w-full px-3 py-2 border rounded",
-                        value: "{role()}",
-                        oninput: move |e| role.set(e.value()),
-
-                        option { value: "ROLE_ADMIN", "Admin" }
-                        option { value: "ROLE_MANAGER", "Manager" }
-                        option { value: "ROLE_HR", "HR" }
-                        option { value: "ROLE_IT", "IT" }
-                        option { value: "ROLE_EMPLOYEE", "Employee" }
+                if let Some(new) = created() {
+                    // Created: the password is not stored in clear, so this
+                    // is the only chance to copy it
+                    div {
+                        class: "p-6",
+                        p {
+                            class: "mb-2 text-gray-800 dark:text-gray-100",
+                            {i18n.tr("users.created", &[("email", new.user.email.clone())])}
+                        }
+                        p { class: "mb-2 text-sm text-gray-600 dark:text-gray-300", {i18n.t("users.password_once")} }
+                        code {
+                            class: "block p-3 bg-gray-100 rounded font-mono break-all select-all dark:bg-gray-700 dark:text-gray-100",
+                            "{new.password}"
+                        }
                     }
-                }
+                    div {
+                        class: "flex justify-end p-6 border-t dark:border-gray-700",
+                        button {
+                            class: "px-4 py-2 text-white bg-blue-600 rounded-md hover:bg-blue-700",
+                            onclick: move |_| on_close.call(()),
+                            {i18n.t("users.done")}
+                        }
+                    }
+                } else {
+                    // Body
+                    div {
+                        class: "p-6",
 
-                // BUTTONS
-                div { class: "flex justify-end gap-3",
+                        if let Some(error) = error_message() {
+                            div {
+                                class: "mb-4 p-3 bg-red-100 border border-red-400 text-red-700 rounded dark:bg-red-900/40 dark:border-red-700 dark:text-red-200",
+                                "{error}"
+                            }
+                        }
 
-                    button {
-                        class: "px-4 py-2 bg-gray-200 text-gray-700 rounded",
-                        onclick: move |_| on_close(()),
-                        "Cancel"
-                    }
+                        // Email
+                        div {
+                            class: "mb-4",
+                            label {
+                                class: "block text-sm font-medium text-gray-700 mb-2 dark:text-gray-200",
+                                {i18n.t("common.email")}
+                            }
+                            input {
+                                class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 dark:border-gray-600 dark:bg-gray-700 dark:text-gray-100",
+                                r#type: "email",
+                                value: "{email}",
+                                oninput: move |e| {
+                                    email.set(e.value());
+                                    server_errors.write().remove("email");
+                                },
+                            }
+                            if let Some(message) = email_error() {
+                                p { class: "mt-1 text-sm text-red-600 dark:text-red-400", "{message}" }
+                            }
+                        }
 
-                    button {
-                        class: "px-4 py-2 bg-blue-600 text-white rounded hover:bg-blue-700",
-                        onclick: move |_| {
-                            spawn({
-                                let email = email();
-                                let role = role();
-                                let on_close = on_close.clone();
-                                let on_save = on_save.clone();
-
-                                async move {
-                                    match &mode {
-                                        UserModalMode::Create => {
-                                            let _ = create_user(email, role).await;
-                                        }
-                                        UserModalMode::Edit(u) => {
-                                            let _ = update_user(u.id, email, role).await;
+                        // Role
+                        div {
+                            class: "mb-4",
+                            label {
+                                class: "block text-sm font-medium text-gray-700 mb-2 dark:text-gray-200",
+                                {i18n.t("users.role")}
+                            }
+                            select {
+                                class: "w-full px-3 py-2 border border-gray-300 rounded-md dark:border-gray-600 dark:bg-gray-700 dark:text-gray-100",
+                                oninput: move |e| role.set(e.value()),
+                                for name in role_names() {
+                                    option { value: "{name}", selected: role() == name, "{name}" }
+                                }
+                            }
+                        }
+
+                        // Employee
+                        div {
+                            label {
+                                class: "block text-sm font-medium text-gray-700 mb-2 dark:text-gray-200",
+                                {i18n.t("users.employee")}
+                            }
+                            div {
+                                class: "flex justify-between items-center mb-2 px-3 py-2 bg-gray-100 rounded-md text-gray-900 dark:bg-gray-700 dark:text-gray-100",
+                                if let Some(current) = linked() {
+                                    span { "{current.name}" }
+                                    button {
+                                        class: "text-sm text-red-600 hover:text-red-900 dark:text-red-400 dark:hover:text-red-300",
+                                        onclick: move |_| linked.set(None),
+                                        {i18n.t("users.no_employee")}
+                                    }
+                                } else {
+                                    span { class: "text-gray-600 dark:text-gray-300", {i18n.t("users.no_employee")} }
+                                }
+                            }
+                            input {
+                                class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 dark:border-gray-600 dark:bg-gray-700 dark:text-gray-100",
+                                r#type: "search",
+                                value: "{search}",
+                                oninput: move |e| search.set(e.value()),
+                                placeholder: i18n.t("users.search_employee"),
+                            }
+                            if let Some(Ok(found)) = &*candidates.read() {
+                                ul {
+                                    class: "mt-2 max-h-40 overflow-y-auto divide-y divide-gray-200 dark:divide-gray-700",
+                                    if found.is_empty() {
+                                        li { class: "py-1 text-sm text-gray-600 dark:text-gray-300", {i18n.t("users.no_matches")} }
+                                    }
+                                    for employee in found.iter().cloned() {
+                                        li {
+                                            key: "{employee.id}",
+                                            button {
+                                                class: "w-full py-1 text-left text-gray-900 hover:bg-gray-100 dark:text-gray-100 dark:hover:bg-gray-700",
+                                                onclick: move |_| linked.set(Some(Linked::from(&employee))),
+                                                "{full_name(&employee)} "
+                                                span {
+                                                    class: "text-sm text-gray-600 dark:text-gray-300",
+                                                    "{employee.email.as_deref().unwrap_or_default()}"
+                                                }
+                                            }
                                         }
                                     }
-
-                                    on_close(());
-                                    on_save(());
                                 }
-                            });
-                        },
-                        "Save"
+                            }
+                        }
+                    }
+
+                    // Footer
+                    div {
+                        class: "flex justify-end gap-3 p-6 border-t dark:border-gray-700",
+                        button {
+                            class: "px-4 py-2 text-gray-700 bg-gray-100 rounded-md hover:bg-gray-200 dark:text-gray-200 dark:bg-gray-700 dark:hover:bg-gray-600",
+                            onclick: move |_| on_close.call(()),
+                            disabled: is_saving(),
+                            {i18n.t("common.cancel")}
+                        }
+                        button {
+                            class: "px-4 py-2 text-white bg-blue-600 rounded-md hover:bg-blue-700 disabled:opacity-50",
+                            onclick: handle_save,
+                            disabled: is_saving() || !errors.read().is_empty(),
+                            if is_saving() {
+                                {i18n.t("common.saving")}
+                            } else {
+                                "{button_text}"
+                            }
+                        }
                     }
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::testing::{render, render_in};
+    use crate::i18n::Locale;
+
+    #[component]
+    fn Modal(mode: UserModalMode) -> Element {
+        rsx! {
+            UserModal { mode, on_close: |_| {}, on_save: |_| {} }
+        }
+    }
+
+    fn ada() -> Employee {
+        Employee {
+            id: 3,
+            first_name: Some("Ada".into()),
+            last_name: Some("Lovelace".into()),
+            email: Some("ada@example.com".into()),
+        }
+    }
+
+    #[test]
+    fn create_for_an_employee_prefills_email_and_link() {
+        let html = render(
+            Modal,
+            ModalProps {
+                mode: UserModalMode::Create(Some(ada())),
+            },
+        );
+        assert!(html.contains("Add New User"));
+        assert!(html.contains(r#"value="ada@example.com""#));
+        assert!(html.contains("Ada Lovelace"));
+        assert!(html.contains(r#"value="ROLE_EMPLOYEE" selected=true"#));
+        assert!(!html.contains("disabled=true"));
+    }
+
+    #[test]
+    fn create_from_scratch_starts_unlinked() {
+        let html = render(
+            Modal,
+            ModalProps {
+                mode: UserModalMode::Create(None),
+            },
+        );
+        assert!(html.contains("No employee"));
+        // Nothing entered yet: Create is off, but the field is not flagged
+        assert_eq!(html.matches("disabled=true").count(), 1);
+        assert!(!html.contains("text-red-600"));
+    }
+
+    #[test]
+    fn edit_shows_role_and_linked_employee() {
+        let html = render(
+            Modal,
+            ModalProps {
+                mode: UserModalMode::Edit(UserExtended {
+                    user_id: 7,
+                    email: "ada@example.com".into(),
+                    role_name: "ROLE_HR".into(),
+                    employee_id: Some(3),
+                    employee_name: Some("Ada Lovelace".into()),
+                    is_active: true,
+                    deactivated_at: None,
+                }),
+            },
+        );
+        assert!(html.contains("Edit User"));
+        assert!(html.contains(r#"value="ROLE_HR" selected=true"#));
+        assert!(html.contains("<span>Ada Lovelace</span>"));
+        assert!(html.contains(r#"placeholder="Search employees without an account""#));
+    }
+
+    #[test]
+    fn speaks_german() {
+        let html = render_in(
+            Locale::De,
+            Modal,
+            ModalProps {
+                mode: UserModalMode::Create(None),
+            },
+        );
+        assert!(html.contains("Neuen Benutzer anlegen"));
+        assert!(html.contains("Kein Mitarbeiter"));
+    }
+}
@@ -1,10 +1,123 @@
+use crate::components::data_table::{Column, DataTable, SortKey, TableRow};
+use crate::components::live::{Highlights, apply_change, use_highlights, use_live_changes};
+use crate::components::toast::use_toasts;
+use crate::components::user_modal::{UserModal, UserModalMode};
+use crate::i18n::use_i18n;
+use crate::server::auth::USER_MANAGE;
+use crate::server::db::{
+    UserExtended, get_user_extended_server, get_users_extended_server, set_user_active,
+};
+use crate::server::events::{ChangeEvent, ChangeOp, ChangeTable};
+use crate::server::state::AuthState;
 use dioxus::prelude::*;
-use crate::server::db::get_users_extended_server;
+
+impl TableRow for UserExtended {
+    fn row_id(&self) -> i32 {
+        self.user_id
+    }
+}
+
+/// Which accounts the Users page lists
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    Active,
+    Deactivated,
+}
+
+impl StatusFilter {
+    const ALL: [Self; 3] = [Self::All, Self::Active, Self::Deactivated];
+
+    fn code(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Active => "active",
+            Self::Deactivated => "deactivated",
+        }
+    }
+
+    fn from_code(code: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|f| f.code() == code)
+            .unwrap_or_default()
+    }
+
+    pub fn matches(self, user: &UserExtended) -> bool {
+        match self {
+            Self::All => true,
+            Self::Active => user.is_active,
+            Self::Deactivated => !user.is_active,
+        }
+    }
+}
 
 #[component]
 pub fn Users() -> Element {
-    let users = use_resource(|| async move {
-        get_users_extended_server().await.ok()
+    let mut users = use_resource(|| async move { get_users_extended_server().await });
+    let mut modal_state = use_signal(|| None::<UserModalMode>);
+    let mut status = use_signal(StatusFilter::default);
+    let highlights = use_highlights();
+    let toasts = use_toasts();
+    let auth = use_context::<AuthState>();
+    let i18n = use_i18n();
+    let can_write = auth.can(USER_MANAGE);
+
+    // Deactivating also ends the account's sessions on the server
+    let set_active = move |(user, active): (UserExtended, bool)| {
+        spawn(async move {
+            match set_user_active(user.user_id, active).await {
+                Ok(saved) => {
+                    let key = if active {
+                        "users.activated"
+                    } else {
+                        "users.deactivated"
+                    };
+                    toasts.success(i18n.tr(key, &[("email", saved.email.clone())]));
+                    if let Some(Ok(list)) = users.write().as_mut() {
+                        apply_change(list, saved.user_id, Some(saved), |u| u.user_id);
+                    }
+                }
+                Err(e) => toasts.error(i18n.tr("common.error", &[("error", e.to_string())])),
+            }
+        });
+    };
+
+    // Patch rows changed elsewhere in place. Employee changes only matter
+    // for the users linked to that employee (name shown in the last column).
+    use_live_changes(move |event: ChangeEvent| {
+        let affected: Vec<i32> = match event.table {
+            ChangeTable::User => vec![event.id],
+            ChangeTable::Employee => match &*users.read() {
+                Some(Ok(list)) => list
+                    .iter()
+                    .filter(|u| u.employee_id == Some(event.id))
+                    .map(|u| u.user_id)
+                    .collect(),
+                _ => Vec::new(),
+            },
+        };
+
+        for user_id in affected {
+            spawn(async move {
+                let fresh = if event.table == ChangeTable::User && event.op == ChangeOp::Delete {
+                    None
+                } else {
+                    match get_user_extended_server(user_id).await {
+                        Ok(fresh) => fresh,
+                        Err(_) => return,
+                    }
+                };
+                let removed = fresh.is_none();
+                if let Some(Ok(list)) = users.write().as_mut() {
+                    apply_change(list, user_id, fresh, |u| u.user_id);
+                }
+                if !removed {
+                    highlights.flash(user_id);
+                }
+            });
+        }
     });
 
     rsx! {
@@ -12,70 +125,320 @@ pub fn Users() -> Element {
             class: "container mx-auto px-4 py-8",
 
             // Header
-            h1 {
-                class: "text-3xl font-bold text-blue-500 mb-6",
-                "Users"
-            }
-
-            // Table Wrapper
             div {
-                class: "bg-white shadow-md rounded-lg overflow-hidden",
-
-                match &*users.read() {
-                    None => rsx! {
-                        div { class: "flex justify-center items-center py-12",
-                            p { class: "text-gray-600", "Loading users..." }
-                        }
-                    },
-
-                    Some(None) => rsx! {
-                        div { class: "p-6 text-red-600", "Error loading users" }
-                    },
-
-                    Some(Some(list)) => rsx! {
-                        table {
-                            class: "min-w-full divide-y divide-gray-200",
-
-                            thead {
-                                class: "bg-gray-50",
-                                tr {
-                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider", "ID" }
-                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider", "Email" }
-                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider", "Role" }
-                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider", "Employee" }
-                                }
+                class: "flex justify-between items-center mb-6",
+                h1 {
+                    class: "text-3xl font-bold text-blue-600 dark:text-blue-400",
+                    {i18n.t("users.title")}
+                }
+                div {
+                    class: "flex items-center gap-3",
+                    select {
+                        class: "px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 dark:border-gray-600 dark:bg-gray-700 dark:text-gray-100",
+                        "aria-label": i18n.t("users.status"),
+                        value: status().code(),
+                        onchange: move |e| status.set(StatusFilter::from_code(&e.value())),
+                        for filter in StatusFilter::ALL {
+                            option {
+                                value: filter.code(),
+                                {i18n.t(&format!("users.filter_{}", filter.code()))}
                             }
+                        }
+                    }
+                    if can_write {
+                        button {
+                            class: "px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700 flex items-center gap-2",
+                            onclick: move |_| modal_state.set(Some(UserModalMode::Create(None))),
+                            span { "+" }
+                            span { {i18n.t("users.add")} }
+                        }
+                    }
+                }
+            }
 
-                            tbody {
-                                class: "bg-white divide-y divide-gray-200",
-
-                                for u in list.iter() {
-                                    tr {
-                                        key: "{u.user_id}",
-                                        class: "hover:bg-gray-50",
+            UserTable {
+                users: users().map(|result| {
+                    result
+                        .map(|list| list.into_iter().filter(|u| status().matches(u)).collect())
+                        .map_err(|e| e.to_string())
+                }),
+                highlights: highlights,
+                on_edit: can_write.then_some(EventHandler::new(move |user| {
+                    modal_state.set(Some(UserModalMode::Edit(user)))
+                })),
+                on_set_active: can_write.then_some(EventHandler::new(set_active)),
+            }
+        }
 
-                                        td { class: "px-6 py-4 whitespace-nowrap text-sm text-gray-900", "{u.user_id}" }
-                                        td { class: "px-6 py-4 whitespace-nowrap text-sm text-gray-900", "{u.email}" }
-                                        td { class: "px-6 py-4 whitespace-nowrap text-sm text-gray-900", "{u.role_name}" }
+        if let Some(mode) = modal_state() {
+            UserModal {
+                mode,
+                on_close: move |_| modal_state.set(None),
+                on_save: move |_| users.restart(),
+            }
+        }
+    }
+}
 
-                                        td {
-                                            class: "px-6 py-4 whitespace-nowrap text-sm text-gray-900",
-                                            "{u.employee_name.clone().unwrap_or(\"-\".into())}"
-                                        }
-                                    }
-                                }
+/// The user list in its loading (`None`), error, empty or populated state.
+/// Rows get an Edit button when `on_edit` is given and a Deactivate or
+/// Activate button when `on_set_active` is, called with the new state.
+#[component]
+pub fn UserTable(
+    users: Option<Result<Vec<UserExtended>, String>>,
+    highlights: Highlights,
+    on_edit: Option<EventHandler<UserExtended>>,
+    on_set_active: Option<EventHandler<(UserExtended, bool)>>,
+) -> Element {
+    let i18n = use_i18n();
+    let mut columns = vec![
+        Column::new("id", "common.id", |u: &UserExtended| rsx! { "{u.user_id}" })
+            .sort_by(|u| u.user_id.into())
+            .width("w-20"),
+        Column::new(
+            "email",
+            "common.email",
+            |u: &UserExtended| rsx! { "{u.email}" },
+        )
+        .sort_by(|u| SortKey::text(Some(&u.email))),
+        Column::new(
+            "role",
+            "users.role",
+            |u: &UserExtended| rsx! { "{u.role_name}" },
+        )
+        .sort_by(|u| SortKey::text(Some(&u.role_name))),
+        Column::new("employee", "users.employee", |u: &UserExtended| {
+            rsx! { "{u.employee_name.as_deref().unwrap_or(\"-\")}" }
+        })
+        .sort_by(|u| SortKey::text(u.employee_name.as_deref())),
+        Column::new("status", "users.status", |u: &UserExtended| {
+            rsx! { StatusBadge { user: u.clone() } }
+        })
+        .sort_by(|u| i32::from(u.is_active).into())
+        .width("w-36"),
+    ];
+    if on_edit.is_some() || on_set_active.is_some() {
+        columns.push(
+            Column::new("actions", "common.actions", move |u: &UserExtended| {
+                let (edit, toggle, active) = (u.clone(), u.clone(), u.is_active);
+                rsx! {
+                    div {
+                        class: "flex gap-3",
+                        if let Some(on_edit) = on_edit {
+                            button {
+                                class: "font-medium text-blue-600 hover:text-blue-900 dark:text-blue-400 dark:hover:text-blue-300",
+                                onclick: move |_| on_edit.call(edit.clone()),
+                                {i18n.t("common.edit")}
                             }
                         }
-
-                        if list.is_empty() {
-                            div {
-                                class: "text-center py-8 text-gray-500",
-                                "No users found."
+                        if let Some(on_set_active) = on_set_active {
+                            button {
+                                class: if active { "font-medium text-red-600 hover:text-red-900 dark:text-red-400 dark:hover:text-red-300" } else { "font-medium text-green-700 hover:text-green-900 dark:text-green-400 dark:hover:text-green-300" },
+                                onclick: move |_| on_set_active.call((toggle.clone(), !active)),
+                                {i18n.t(if active { "users.deactivate" } else { "users.activate" })}
                             }
                         }
                     }
                 }
+            })
+            .width("w-56"),
+        );
+    }
+
+    rsx! {
+        DataTable { rows: users, columns, noun: "users", highlights }
+    }
+}
+
+/// Active or deactivated; a deactivated badge shows since when
+#[component]
+fn StatusBadge(user: UserExtended) -> Element {
+    let i18n = use_i18n();
+    if user.is_active {
+        return rsx! {
+            span {
+                class: "px-2 py-1 rounded-full text-xs font-medium bg-green-100 text-green-800 dark:bg-green-900/40 dark:text-green-200",
+                {i18n.t("users.active")}
+            }
+        };
+    }
+    let since = user
+        .deactivated_at
+        .as_deref()
+        .map(|at| i18n.tr("users.deactivated_since", &[("at", i18n.datetime(at))]))
+        .unwrap_or_default();
+    rsx! {
+        span {
+            class: "px-2 py-1 rounded-full text-xs font-medium bg-gray-200 text-gray-800 dark:bg-gray-700 dark:text-gray-200",
+            title: "{since}",
+            {i18n.t("users.inactive")}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::testing::render;
+
+    #[component]
+    fn Table(users: Option<Result<Vec<UserExtended>, String>>) -> Element {
+        let highlights = use_highlights();
+        rsx! { UserTable { users, highlights } }
+    }
+
+    #[component]
+    fn EditableTable(users: Option<Result<Vec<UserExtended>, String>>) -> Element {
+        let highlights = use_highlights();
+        rsx! {
+            UserTable {
+                users,
+                highlights,
+                on_edit: |_| {},
+                on_set_active: |_| {},
             }
         }
     }
+
+    #[test]
+    fn shows_loading_until_the_server_answers() {
+        let html = render(Table, TableProps { users: None });
+        assert!(html.contains("Loading users..."));
+    }
+
+    #[test]
+    fn shows_the_server_error() {
+        let html = render(
+            Table,
+            TableProps {
+                users: Some(Err("Permission denied".into())),
+            },
+        );
+        assert!(html.contains("Error loading users: Permission denied"));
+        assert!(!html.contains("<table"));
+    }
+
+    #[test]
+    fn says_so_when_there_are_no_users() {
+        let html = render(
+            Table,
+            TableProps {
+                users: Some(Ok(Vec::new())),
+            },
+        );
+        assert!(html.contains("No users found."));
+    }
+
+    #[test]
+    fn lists_users_with_role_and_employee() {
+        let html = render(
+            Table,
+            TableProps {
+                users: Some(Ok(vec![
+                    UserExtended {
+                        user_id: 1,
+                        email: "ada@example.com".into(),
+                        role_name: "ROLE_HR".into(),
+                        employee_id: Some(3),
+                        employee_name: Some("Ada Lovelace".into()),
+                        is_active: true,
+                        deactivated_at: None,
+                    },
+                    UserExtended {
+                        user_id: 2,
+                        email: "admin@example.com".into(),
+                        role_name: "ROLE_ADMIN".into(),
+                        employee_id: None,
+                        employee_name: None,
+                        is_active: true,
+                        deactivated_at: None,
+                    },
+                ])),
+            },
+        );
+        for text in [
+            "ada@example.com",
+            "ROLE_HR",
+            "Ada Lovelace",
+            "admin@example.com",
+            "ROLE_ADMIN",
+        ] {
+            assert!(html.contains(text), "missing {}", text);
+        }
+        assert!(html.contains(">-</td>"));
+        assert!(!html.contains("No users found."));
+    }
+
+    #[test]
+    fn edit_buttons_only_come_with_a_handler() {
+        let users = || {
+            Some(Ok(vec![UserExtended {
+                user_id: 1,
+                email: "ada@example.com".into(),
+                role_name: "ROLE_HR".into(),
+                employee_id: None,
+                employee_name: None,
+                is_active: true,
+                deactivated_at: None,
+            }]))
+        };
+        let read_only = render(Table, TableProps { users: users() });
+        assert!(!read_only.contains("Edit"));
+        let editable = render(EditableTable, EditableTableProps { users: users() });
+        assert!(editable.contains("Edit"));
+        assert!(editable.contains("Actions"));
+    }
+
+    #[test]
+    fn shows_status_and_offers_the_opposite_action() {
+        let users = || {
+            Some(Ok(vec![
+                UserExtended {
+                    user_id: 1,
+                    email: "ada@example.com".into(),
+                    role_name: "ROLE_HR".into(),
+                    employee_id: None,
+                    employee_name: None,
+                    is_active: true,
+                    deactivated_at: None,
+                },
+                UserExtended {
+                    user_id: 2,
+                    email: "grace@example.com".into(),
+                    role_name: "ROLE_IT".into(),
+                    employee_id: None,
+                    employee_name: None,
+                    is_active: false,
+                    deactivated_at: Some("2026-01-05T14:30:00+00:00".into()),
+                },
+            ]))
+        };
+        let read_only = render(Table, TableProps { users: users() });
+        assert!(read_only.contains(">Active</span>"));
+        assert!(read_only.contains(r#"title="Deactivated since Jan 5, 2026"#));
+        assert!(!read_only.contains("Deactivate<"));
+
+        let editable = render(EditableTable, EditableTableProps { users: users() });
+        assert_eq!(editable.matches(">Deactivate</button>").count(), 1);
+        assert_eq!(editable.matches(">Activate</button>").count(), 1);
+    }
+
+    #[test]
+    fn status_filter_picks_accounts() {
+        let mut user = UserExtended {
+            user_id: 1,
+            email: "ada@example.com".into(),
+            role_name: "ROLE_HR".into(),
+            employee_id: None,
+            employee_name: None,
+            is_active: true,
+            deactivated_at: None,
+        };
+        assert!(StatusFilter::All.matches(&user));
+        assert!(StatusFilter::Active.matches(&user));
+        assert!(!StatusFilter::Deactivated.matches(&user));
+        user.is_active = false;
+        assert!(StatusFilter::Deactivated.matches(&user));
+        assert_eq!(StatusFilter::from_code("nonsense"), StatusFilter::All);
+    }
 }
@@ -0,0 +1,272 @@
+use crate::components::toast::use_toasts;
+use crate::i18n::{I18n, use_i18n};
+use crate::server::webhooks::{
+    EVENT_TYPES, create_webhook, delete_webhook, list_webhook_deliveries, list_webhooks,
+    redeliver_webhook, set_webhook_active,
+};
+use dioxus::prelude::*;
+
+#[component]
+pub fn Webhooks() -> Element {
+    let mut webhooks = use_resource(|| async move { list_webhooks().await });
+    let mut deliveries = use_resource(|| async move { list_webhook_deliveries(None).await });
+
+    let mut url = use_signal(String::new);
+    let mut event_type = use_signal(|| EVENT_TYPES[0].to_string());
+    let toasts = use_toasts();
+    let i18n = use_i18n();
+
+    let handle_create = move |_| {
+        spawn(async move {
+            match create_webhook(url(), event_type()).await {
+                Ok(_) => {
+                    toasts.success(i18n.tr("webhooks.added", &[("url", url())]));
+                    url.set(String::new());
+                    webhooks.restart();
+                }
+                Err(e) => toasts.error(i18n.tr("webhooks.add_failed", &[("error", e.to_string())])),
+            }
+        });
+    };
+
+    let toggle = move |id: i32, active: bool| {
+        spawn(async move {
+            match set_webhook_active(id, active).await {
+                Ok(_) => {
+                    toasts.success(i18n.t(if active {
+                        "webhooks.resumed"
+                    } else {
+                        "webhooks.paused"
+                    }));
+                    webhooks.restart();
+                }
+                Err(e) => {
+                    toasts.error(i18n.tr("webhooks.update_failed", &[("error", e.to_string())]))
+                }
+            }
+        });
+    };
+
+    let remove = move |id: i32| {
+        spawn(async move {
+            match delete_webhook(id).await {
+                Ok(_) => {
+                    toasts.success(i18n.t("webhooks.deleted"));
+                    webhooks.restart();
+                    deliveries.restart();
+                }
+                Err(e) => {
+                    toasts.error(i18n.tr("webhooks.delete_failed", &[("error", e.to_string())]))
+                }
+            }
+        });
+    };
+
+    let redeliver = move |id: i32| {
+        spawn(async move {
+            match redeliver_webhook(id).await {
+                Ok(_) => {
+                    toasts.info(i18n.t("deliveries.requeued"));
+                    deliveries.restart();
+                }
+                Err(e) => toasts
+                    .error(i18n.tr("deliveries.redeliver_failed", &[("error", e.to_string())])),
+            }
+        });
+    };
+
+    rsx! {
+        div {
+            class: "container mx-auto px-4 py-8",
+
+            h1 {
+                class: "text-3xl font-bold text-blue-600 mb-6 dark:text-blue-400",
+                {i18n.t("webhooks.title")}
+            }
+
+            // Registration form
+            div {
+                class: "bg-white shadow-md rounded-lg p-6 mb-8 dark:bg-gray-800",
+
+                div {
+                    class: "flex gap-3 items-end",
+                    div {
+                        class: "flex-1",
+                        label { class: "block text-sm font-medium text-gray-700 mb-2 dark:text-gray-200", {i18n.t("webhooks.url")} }
+                        input {
+                            class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 dark:border-gray-600 dark:bg-gray-700 dark:text-gray-100",
+                            r#type: "url",
+                            value: "{url}",
+                            oninput: move |e| url.set(e.value()),
+                            placeholder: "https://payroll.example.com/hooks/employees",
+                        }
+                    }
+                    div {
+                        label { class: "block text-sm font-medium text-gray-700 mb-2 dark:text-gray-200", {i18n.t("webhooks.event")} }
+                        select {
+                            class: "px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 dark:border-gray-600 dark:bg-gray-700 dark:text-gray-100",
+                            value: "{event_type}",
+                            onchange: move |e| event_type.set(e.value()),
+                            for event in EVENT_TYPES {
+                                option { value: event, "{event}" }
+                            }
+                        }
+                    }
+                    button {
+                        class: "px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700",
+                        onclick: handle_create,
+                        {i18n.t("webhooks.add")}
+                    }
+                }
+            }
+
+            // Registered webhooks
+            div {
+                class: "bg-white shadow-md rounded-lg overflow-hidden mb-8 dark:bg-gray-800",
+                match webhooks() {
+                    None => rsx! {
+                        p { class: "p-6 text-gray-600 dark:text-gray-300", {i18n.t("webhooks.loading")} }
+                    },
+                    Some(Err(err)) => rsx! {
+                        div { class: "p-6 text-red-600 dark:text-red-400", {i18n.tr("webhooks.load_error", &[("error", err.to_string())])} }
+                    },
+                    Some(Ok(list)) => rsx! {
+                        table {
+                            class: "min-w-full divide-y divide-gray-200 dark:divide-gray-700",
+                            thead {
+                                class: "bg-gray-50 dark:bg-gray-700",
+                                tr {
+                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider dark:text-gray-300", {i18n.t("webhooks.url")} }
+                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider dark:text-gray-300", {i18n.t("webhooks.event")} }
+                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider dark:text-gray-300", {i18n.t("webhooks.secret")} }
+                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider dark:text-gray-300", {i18n.t("common.actions")} }
+                                }
+                            }
+                            tbody {
+                                class: "bg-white divide-y divide-gray-200 dark:bg-gray-800 dark:divide-gray-700",
+                                for hook in list.iter() {
+                                    tr {
+                                        key: "{hook.id}",
+                                        class: if hook.is_active { "hover:bg-gray-50 dark:hover:bg-gray-700" } else { "text-gray-500 dark:text-gray-300" },
+                                        td { class: "px-6 py-4 text-sm break-all", "{hook.url}" }
+                                        td { class: "px-6 py-4 whitespace-nowrap text-sm", "{hook.event_type}" }
+                                        td { class: "px-6 py-4 text-xs font-mono break-all", "{hook.secret}" }
+                                        td {
+                                            class: "px-6 py-4 whitespace-nowrap text-sm font-medium flex gap-2",
+                                            button {
+                                                class: "text-blue-600 hover:text-blue-900 dark:text-blue-400 dark:hover:text-blue-300",
+                                                onclick: {
+                                                    let (id, active) = (hook.id, hook.is_active);
+                                                    move |_| toggle(id, !active)
+                                                },
+                                                {i18n.t(if hook.is_active { "webhooks.pause" } else { "webhooks.resume" })}
+                                            }
+                                            button {
+                                                class: "text-red-600 hover:text-red-900 dark:text-red-400 dark:hover:text-red-300",
+                                                onclick: {
+                                                    let id = hook.id;
+                                                    move |_| remove(id)
+                                                },
+                                                {i18n.t("common.delete")}
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        if list.is_empty() {
+                            div { class: "text-center py-8 text-gray-500 dark:text-gray-300", {i18n.t("webhooks.empty")} }
+                        }
+                    }
+                }
+            }
+
+            // Delivery log
+            div {
+                class: "flex justify-between items-center mb-4",
+                h2 { class: "text-xl font-bold text-gray-800 dark:text-gray-100", {i18n.t("deliveries.title")} }
+                button {
+                    class: "px-3 py-1 text-gray-700 bg-gray-100 rounded-md hover:bg-gray-200 dark:text-gray-200 dark:bg-gray-700 dark:hover:bg-gray-600",
+                    onclick: move |_| deliveries.restart(),
+                    {i18n.t("common.refresh")}
+                }
+            }
+            div {
+                class: "bg-white shadow-md rounded-lg overflow-hidden dark:bg-gray-800",
+                match deliveries() {
+                    None => rsx! {
+                        p { class: "p-6 text-gray-600 dark:text-gray-300", {i18n.t("deliveries.loading")} }
+                    },
+                    Some(Err(err)) => rsx! {
+                        div { class: "p-6 text-red-600 dark:text-red-400", {i18n.tr("deliveries.load_error", &[("error", err.to_string())])} }
+                    },
+                    Some(Ok(list)) => rsx! {
+                        table {
+                            class: "min-w-full divide-y divide-gray-200 dark:divide-gray-700",
+                            thead {
+                                class: "bg-gray-50 dark:bg-gray-700",
+                                tr {
+                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider dark:text-gray-300", {i18n.t("common.id")} }
+                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider dark:text-gray-300", {i18n.t("deliveries.created")} }
+                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider dark:text-gray-300", {i18n.t("webhooks.event")} }
+                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider dark:text-gray-300", {i18n.t("webhooks.url")} }
+                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider dark:text-gray-300", {i18n.t("deliveries.status")} }
+                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider dark:text-gray-300", {i18n.t("deliveries.attempts")} }
+                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider dark:text-gray-300", {i18n.t("deliveries.last_error")} }
+                                    th { class: "px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider dark:text-gray-300", {i18n.t("common.actions")} }
+                                }
+                            }
+                            tbody {
+                                class: "bg-white divide-y divide-gray-200 dark:bg-gray-800 dark:divide-gray-700",
+                                for delivery in list.iter() {
+                                    tr {
+                                        key: "{delivery.id}",
+                                        class: "hover:bg-gray-50 dark:hover:bg-gray-700",
+                                        td { class: "px-6 py-4 whitespace-nowrap text-sm text-gray-900 dark:text-gray-100", "{delivery.id}" }
+                                        td { class: "px-6 py-4 whitespace-nowrap text-sm text-gray-600 dark:text-gray-300", {i18n.datetime(&delivery.created_at)} }
+                                        td { class: "px-6 py-4 whitespace-nowrap text-sm text-gray-900 dark:text-gray-100", "{delivery.event_type}" }
+                                        td { class: "px-6 py-4 text-sm text-gray-600 break-all dark:text-gray-300", "{delivery.url}" }
+                                        td {
+                                            class: match delivery.status.as_str() {
+                                                "delivered" => "px-6 py-4 whitespace-nowrap text-sm text-green-700 dark:text-green-300",
+                                                "failed" => "px-6 py-4 whitespace-nowrap text-sm text-red-700 dark:text-red-300",
+                                                _ => "px-6 py-4 whitespace-nowrap text-sm text-yellow-800 dark:text-yellow-300",
+                                            },
+                                            {status_label(i18n, &delivery.status)}
+                                        }
+                                        td { class: "px-6 py-4 whitespace-nowrap text-sm text-gray-900 dark:text-gray-100", {i18n.number(delivery.attempts.into())} }
+                                        td { class: "px-6 py-4 text-sm text-gray-600 dark:text-gray-300", "{delivery.last_error.as_deref().unwrap_or(\"-\")}" }
+                                        td {
+                                            class: "px-6 py-4 whitespace-nowrap text-sm font-medium",
+                                            button {
+                                                class: "text-blue-600 hover:text-blue-900 dark:text-blue-400 dark:hover:text-blue-300",
+                                                onclick: {
+                                                    let id = delivery.id;
+                                                    move |_| redeliver(id)
+                                                },
+                                                {i18n.t("deliveries.redeliver")}
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        if list.is_empty() {
+                            div { class: "text-center py-8 text-gray-500 dark:text-gray-300", {i18n.t("deliveries.empty")} }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Delivery status in the UI language; unknown states as stored
+fn status_label(i18n: I18n, status: &str) -> String {
+    match status {
+        "pending" | "delivered" | "failed" => i18n.t(&format!("deliveries.status.{}", status)),
+        other => other.to_string(),
+    }
+}
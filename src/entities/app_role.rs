@@ -1,6 +1,5 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.17
 #[cfg(feature = "server")]
-
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -17,6 +16,8 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::users::Entity")]
     Users,
+    #[sea_orm(has_many = "super::role_permission::Entity")]
+    Permissions,
 }
 
 impl Related<super::users::Entity> for Entity {
@@ -25,4 +26,10 @@ impl Related<super::users::Entity> for Entity {
     }
 }
 
+impl Related<super::role_permission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Permissions.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
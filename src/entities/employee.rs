@@ -18,6 +18,14 @@ pub struct Model {
     // Optional unique email for employee
     #[sea_orm(unique)]
     pub email: Option<String>,
+
+    // Set on insert; NULL for rows older than the column
+    #[sea_orm(default_expr = "sea_orm::sea_query::Expr::current_timestamp()")]
+    pub created_at: Option<DateTimeWithTimeZone>,
+
+    // Kept current by a trigger on PostgreSQL (migration 010)
+    #[sea_orm(default_expr = "sea_orm::sea_query::Expr::current_timestamp()")]
+    pub updated_at: Option<DateTimeWithTimeZone>,
 }
 
 // Relations from employee to other entities
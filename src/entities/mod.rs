@@ -9,5 +9,14 @@ pub mod app_role;
 #[cfg(feature = "server")]
 pub mod employee;
 
+#[cfg(feature = "server")]
+pub mod role_permission;
+
 #[cfg(feature = "server")]
 pub mod users;
+
+#[cfg(feature = "server")]
+pub mod webhook;
+
+#[cfg(feature = "server")]
+pub mod webhook_delivery;
@@ -1,7 +1,9 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.17
 
 #[cfg(feature = "server")]
-
 pub use super::app_role::Entity as AppRole;
 pub use super::employee::Entity as Employee;
+pub use super::role_permission::Entity as RolePermission;
 pub use super::users::Entity as Users;
+pub use super::webhook::Entity as Webhook;
+pub use super::webhook_delivery::Entity as WebhookDelivery;
@@ -0,0 +1,34 @@
+//! `SeaORM` Entity for "role_permission": one row per permission a role has
+#[cfg(feature = "server")]
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "role_permission")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub role_id: i32,
+    /// A code from `server::auth::PERMISSIONS`
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub permission: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::app_role::Entity",
+        from = "Column::RoleId",
+        to = "super::app_role::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    AppRole,
+}
+
+impl Related<super::app_role::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AppRole.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -20,11 +20,32 @@ pub struct Model {
     pub role_id: i32,
 
     // Optional creation timestamp
+    #[sea_orm(default_expr = "sea_orm::sea_query::Expr::current_timestamp()")]
     pub created_at: Option<DateTimeWithTimeZone>,
 
     // Optional FK to employee.id (unique enforces one-to-one mapping)
     #[sea_orm(unique)]
     pub employee_id: Option<i32>,
+
+    // Inactive users are kept for history but may not log in
+    #[sea_orm(default_value = true)]
+    pub is_active: bool,
+
+    // Optional id assigned by the identity provider (SCIM externalId)
+    #[sea_orm(unique)]
+    pub external_id: Option<String>,
+
+    // Interface language code ('en', 'de'); NULL until the user picks one
+    pub locale: Option<String>,
+
+    // Color theme ('light', 'dark', 'system'); NULL until the user picks one
+    pub theme: Option<String>,
+
+    // Last successful login; NULL if the user never signed in
+    pub last_login_at: Option<DateTimeWithTimeZone>,
+
+    // Last deactivation; sessions issued before it are revoked
+    pub deactivated_at: Option<DateTimeWithTimeZone>,
 }
 
 // Relations from users to other entities
@@ -0,0 +1,43 @@
+// SeaORM entity for "webhook"
+use sea_orm::entity::prelude::*;
+
+// Model definition for the webhook table
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "webhook")]
+pub struct Model {
+    // Primary key with auto increment (sequence)
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+
+    // Receiver URL (http or https)
+    pub url: String,
+
+    // One of the event types in server::webhooks::EVENT_TYPES
+    pub event_type: String,
+
+    // HMAC-SHA256 key shared with the receiver
+    pub secret: String,
+
+    // Inactive webhooks receive no new deliveries
+    #[sea_orm(default_value = true)]
+    pub is_active: bool,
+
+    #[sea_orm(default_expr = "sea_orm::sea_query::Expr::current_timestamp()")]
+    pub created_at: DateTimeWithTimeZone,
+}
+
+// Relations from webhook to other entities
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::webhook_delivery::Entity")]
+    WebhookDelivery,
+}
+
+impl Related<super::webhook_delivery::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WebhookDelivery.def()
+    }
+}
+
+// Default ActiveModel behavior
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,62 @@
+// SeaORM entity for "webhook_delivery" (outbox and delivery log)
+use sea_orm::entity::prelude::*;
+
+// Model definition for the webhook_delivery table
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "webhook_delivery")]
+pub struct Model {
+    // Primary key with auto increment (sequence)
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+
+    // FK to webhook.id (cascade delete)
+    pub webhook_id: i32,
+
+    pub event_type: String,
+
+    // Envelope with event, occurred_at and data; sent verbatim
+    pub payload: Json,
+
+    // pending, delivered or failed
+    #[sea_orm(default_value = "pending")]
+    pub status: String,
+
+    // Number of attempts made so far
+    #[sea_orm(default_value = 0)]
+    pub attempts: i32,
+
+    // Earliest time the worker picks this row up again
+    #[sea_orm(default_expr = "sea_orm::sea_query::Expr::current_timestamp()")]
+    pub next_attempt_at: DateTimeWithTimeZone,
+
+    // HTTP status and error of the most recent attempt
+    pub last_status: Option<i32>,
+    pub last_error: Option<String>,
+
+    #[sea_orm(default_expr = "sea_orm::sea_query::Expr::current_timestamp()")]
+    pub created_at: DateTimeWithTimeZone,
+
+    pub delivered_at: Option<DateTimeWithTimeZone>,
+}
+
+// Relations from webhook_delivery to other entities
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::webhook::Entity",
+        from = "Column::WebhookId",
+        to = "super::webhook::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Webhook,
+}
+
+impl Related<super::webhook::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Webhook.def()
+    }
+}
+
+// Default ActiveModel behavior
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,337 @@
+//! German messages
+
+pub const MESSAGES: &[(&str, &str)] = &[
+    // Header and pages
+    ("nav.home", "Start"),
+    ("nav.login", "Anmelden"),
+    ("nav.employees", "Mitarbeiter"),
+    ("nav.users", "Benutzer"),
+    ("nav.roles", "Rollen"),
+    ("nav.webhooks", "Webhooks"),
+    ("nav.logout", "Abmelden"),
+    ("nav.language", "Sprache"),
+    (
+        "nav.language_failed",
+        "Die Sprache konnte nicht gespeichert werden: {error}",
+    ),
+    ("theme.light", "Hell"),
+    ("theme.dark", "Dunkel"),
+    ("theme.system", "System"),
+    ("theme.toggle", "Farbschema: {name}"),
+    (
+        "theme.save_failed",
+        "Das Farbschema konnte nicht gespeichert werden: {error}",
+    ),
+    (
+        "home.tagline",
+        "Saubere Dioxus- und TailwindCSS-Basis bereit.",
+    ),
+    // Dashboard
+    ("dashboard.title", "Übersicht"),
+    ("dashboard.loading", "Übersicht wird geladen..."),
+    (
+        "dashboard.load_error",
+        "Fehler beim Laden der Übersicht: {error}",
+    ),
+    ("dashboard.employees", "Mitarbeiter"),
+    ("dashboard.without_account", "Ohne Benutzerkonto"),
+    ("dashboard.users_per_role", "Benutzer je Rolle"),
+    ("dashboard.recent_hires", "Neueste Mitarbeiter"),
+    ("dashboard.recent_changes", "Letzte Änderungen"),
+    ("dashboard.recent_logins", "Letzte Anmeldungen"),
+    ("dashboard.locked_accounts", "Gesperrte Konten ({count})"),
+    ("dashboard.none", "Noch nichts."),
+    // Shared labels
+    ("common.id", "ID"),
+    ("common.email", "E-Mail"),
+    ("common.actions", "Aktionen"),
+    ("common.view", "👁️ Ansehen"),
+    ("common.edit", "✏️ Bearbeiten"),
+    ("common.delete", "🗑️ Löschen"),
+    ("common.cancel", "Abbrechen"),
+    ("common.confirm_delete", "Löschen"),
+    ("common.undo", "Rückgängig"),
+    ("common.refresh", "Aktualisieren"),
+    ("common.saving", "Wird gespeichert..."),
+    ("common.error", "Fehler: {error}"),
+    ("common.close", "Schließen"),
+    // Tables
+    ("table.columns", "Spalten"),
+    ("table.selected.one", "{count} ausgewählt"),
+    ("table.selected.other", "{count} ausgewählt"),
+    // Login
+    ("auth.welcome", "Willkommen zurück"),
+    ("auth.prompt", "Bitte melden Sie sich an, um fortzufahren."),
+    ("auth.password", "Passwort"),
+    ("auth.submit", "Anmelden"),
+    ("auth.forgot_password", "Passwort vergessen?"),
+    ("auth.signed_in", "Angemeldet als {email}"),
+    ("auth.signed_out", "Abgemeldet"),
+    ("auth.sign_out_failed", "Abmelden fehlgeschlagen: {error}"),
+    // Employees
+    ("employees.title", "Mitarbeiterverzeichnis"),
+    ("employees.add", "Mitarbeiter hinzufügen"),
+    ("employees.loading", "Mitarbeiter werden geladen..."),
+    (
+        "employees.load_error",
+        "Fehler beim Laden der Mitarbeiter: {error}",
+    ),
+    ("employees.empty", "Keine Mitarbeiter gefunden."),
+    ("employees.first_name", "Vorname"),
+    ("employees.create_login", "🔑 Login anlegen"),
+    ("employees.last_name", "Nachname"),
+    ("employees.delete_selected", "Auswahl löschen"),
+    ("employees.delete_title.one", "Mitarbeiter löschen?"),
+    (
+        "employees.delete_title.other",
+        "{count} Mitarbeiter löschen?",
+    ),
+    (
+        "employees.delete_confirm",
+        "Möchten Sie {name} wirklich löschen?",
+    ),
+    (
+        "employees.delete_confirm_selected.one",
+        "Möchten Sie den ausgewählten Mitarbeiter wirklich löschen?",
+    ),
+    (
+        "employees.delete_confirm_selected.other",
+        "Möchten Sie die {count} ausgewählten Mitarbeiter wirklich löschen?",
+    ),
+    (
+        "employees.cannot_undo",
+        "Diese Aktion kann nicht rückgängig gemacht werden.",
+    ),
+    ("employees.deleted_named", "{name} gelöscht"),
+    ("employees.deleted.one", "{count} Mitarbeiter gelöscht"),
+    ("employees.deleted.other", "{count} Mitarbeiter gelöscht"),
+    (
+        "employees.delete_failed",
+        "{name} konnte nicht gelöscht werden: {error}",
+    ),
+    (
+        "employees.restored",
+        "{restored} von {total} wiederhergestellt",
+    ),
+    (
+        "employees.restore_failed",
+        "{name} konnte nicht wiederhergestellt werden: {error}",
+    ),
+    ("employees.set_role_selected", "Rolle ändern"),
+    ("employees.export_selected", "Als CSV exportieren"),
+    (
+        "employees.role_title.one",
+        "Rolle von {count} Mitarbeiter ändern",
+    ),
+    (
+        "employees.role_title.other",
+        "Rolle von {count} Mitarbeitern ändern",
+    ),
+    (
+        "employees.role_confirm.one",
+        "Das Benutzerkonto des ausgewählten Mitarbeiters erhält diese Rolle.",
+    ),
+    (
+        "employees.role_confirm.other",
+        "Die Benutzerkonten aller {count} ausgewählten Mitarbeiter erhalten diese Rolle.",
+    ),
+    ("employees.role_apply", "Übernehmen"),
+    (
+        "employees.role_set.one",
+        "{count} Konto hat die Rolle {role} erhalten",
+    ),
+    (
+        "employees.role_set.other",
+        "{count} Konten haben die Rolle {role} erhalten",
+    ),
+    (
+        "employees.role_failed",
+        "Die Rolle von {name} konnte nicht geändert werden: {error}",
+    ),
+    ("employees.exported.one", "{count} Mitarbeiter exportiert"),
+    ("employees.exported.other", "{count} Mitarbeiter exportiert"),
+    ("employees.download", "employees.csv herunterladen"),
+    // Employee detail page
+    ("employee_detail.back", "← Zurück zu den Mitarbeitern"),
+    ("employee_detail.loading", "Mitarbeiter wird geladen..."),
+    (
+        "employee_detail.load_error",
+        "Fehler beim Laden des Mitarbeiters: {error}",
+    ),
+    ("employee_detail.record", "Stammdaten"),
+    ("employee_detail.account", "Benutzerkonto"),
+    (
+        "employee_detail.no_account",
+        "Diesem Mitarbeiter ist kein Benutzerkonto zugeordnet.",
+    ),
+    ("employee_detail.deleted", "Mitarbeiter gelöscht"),
+    // Employee form
+    ("employee_form.create_title", "Neuen Mitarbeiter anlegen"),
+    ("employee_form.edit_title", "Mitarbeiter bearbeiten"),
+    ("employee_form.create", "Anlegen"),
+    ("employee_form.update", "Speichern"),
+    ("employee_form.first_name_placeholder", "Vorname eingeben"),
+    ("employee_form.last_name_placeholder", "Nachname eingeben"),
+    ("employee_form.email_placeholder", "E-Mail-Adresse eingeben"),
+    ("employee_form.created", "Mitarbeiter angelegt"),
+    ("employee_form.updated", "Mitarbeiter aktualisiert"),
+    // Users
+    ("users.title", "Benutzer"),
+    ("users.loading", "Benutzer werden geladen..."),
+    (
+        "users.load_error",
+        "Fehler beim Laden der Benutzer: {error}",
+    ),
+    ("users.empty", "Keine Benutzer gefunden."),
+    ("users.role", "Rolle"),
+    ("users.employee", "Mitarbeiter"),
+    ("users.add", "Benutzer hinzufügen"),
+    ("users.create_title", "Neuen Benutzer anlegen"),
+    ("users.edit_title", "Benutzer bearbeiten"),
+    ("users.create", "Anlegen"),
+    ("users.update", "Speichern"),
+    ("users.search_employee", "Mitarbeiter ohne Konto suchen"),
+    ("users.no_employee", "Kein Mitarbeiter"),
+    (
+        "users.no_matches",
+        "Kein Mitarbeiter ohne Konto passt zur Suche.",
+    ),
+    ("users.created", "Benutzer {email} angelegt"),
+    (
+        "users.password_once",
+        "Startpasswort, wird nur einmal angezeigt:",
+    ),
+    ("users.done", "Fertig"),
+    ("users.updated", "Benutzer aktualisiert"),
+    ("users.status", "Status"),
+    ("users.active", "Aktiv"),
+    ("users.inactive", "Deaktiviert"),
+    ("users.deactivated_since", "Deaktiviert seit {at}"),
+    ("users.filter_all", "Alle Konten"),
+    ("users.filter_active", "Aktive Konten"),
+    ("users.filter_deactivated", "Deaktivierte Konten"),
+    ("users.activate", "Aktivieren"),
+    ("users.deactivate", "Deaktivieren"),
+    ("users.activated", "Benutzer {email} aktiviert"),
+    (
+        "users.deactivated",
+        "Benutzer {email} deaktiviert und abgemeldet",
+    ),
+    // Roles
+    ("roles.title", "Rollen und Berechtigungen"),
+    ("roles.role", "Rolle"),
+    ("roles.new_name", "Name der neuen Rolle"),
+    ("roles.add", "Rolle hinzufügen"),
+    ("roles.loading", "Rollen werden geladen..."),
+    ("roles.load_error", "Fehler beim Laden der Rollen: {error}"),
+    ("roles.created", "Rolle {role} hinzugefügt"),
+    ("roles.saved", "Berechtigungen von {role} gespeichert"),
+    ("permission.employee.read", "Mitarbeiter ansehen"),
+    ("permission.employee.write", "Mitarbeiter bearbeiten"),
+    ("permission.user.read", "Benutzer ansehen"),
+    ("permission.user.manage", "Benutzer verwalten"),
+    ("permission.role.manage", "Rollen verwalten"),
+    ("permission.webhook.manage", "Webhooks verwalten"),
+    ("permission.audit.read", "Kontoaktivität ansehen"),
+    // Webhooks
+    ("webhooks.title", "Webhooks"),
+    ("webhooks.url", "URL"),
+    ("webhooks.event", "Ereignis"),
+    ("webhooks.secret", "Signaturschlüssel"),
+    ("webhooks.add", "Webhook hinzufügen"),
+    ("webhooks.pause", "Pausieren"),
+    ("webhooks.resume", "Fortsetzen"),
+    ("webhooks.loading", "Webhooks werden geladen..."),
+    (
+        "webhooks.load_error",
+        "Fehler beim Laden der Webhooks: {error}",
+    ),
+    ("webhooks.empty", "Keine Webhooks registriert."),
+    ("webhooks.added", "Webhook für {url} hinzugefügt"),
+    (
+        "webhooks.add_failed",
+        "Webhook konnte nicht hinzugefügt werden: {error}",
+    ),
+    ("webhooks.resumed", "Webhook fortgesetzt"),
+    ("webhooks.paused", "Webhook pausiert"),
+    (
+        "webhooks.update_failed",
+        "Webhook konnte nicht geändert werden: {error}",
+    ),
+    ("webhooks.deleted", "Webhook gelöscht"),
+    (
+        "webhooks.delete_failed",
+        "Webhook konnte nicht gelöscht werden: {error}",
+    ),
+    ("deliveries.title", "Letzte Zustellungen"),
+    ("deliveries.loading", "Zustellungen werden geladen..."),
+    (
+        "deliveries.load_error",
+        "Fehler beim Laden der Zustellungen: {error}",
+    ),
+    ("deliveries.empty", "Noch keine Zustellungen."),
+    ("deliveries.created", "Erstellt"),
+    ("deliveries.status", "Status"),
+    ("deliveries.status.pending", "ausstehend"),
+    ("deliveries.status.delivered", "zugestellt"),
+    ("deliveries.status.failed", "fehlgeschlagen"),
+    ("deliveries.attempts", "Versuche"),
+    ("deliveries.last_error", "Letzter Fehler"),
+    ("deliveries.redeliver", "Erneut zustellen"),
+    ("deliveries.requeued", "Zustellung erneut eingeplant"),
+    (
+        "deliveries.redeliver_failed",
+        "Erneute Zustellung fehlgeschlagen: {error}",
+    ),
+    // Field validation (validation::FieldError)
+    ("validation.invalid_input", "Ungültige Eingabe"),
+    ("validation.required", "Pflichtfeld"),
+    ("validation.too_long", "Höchstens {max} Zeichen"),
+    ("validation.email", "Keine gültige E-Mail-Adresse"),
+    ("validation.phone", "Keine gültige Telefonnummer"),
+    // Server errors (session::AccessError, directory::DirectoryError,
+    // roles::RoleError)
+    ("error.invalid_login", "E-Mail oder Passwort ist falsch"),
+    ("error.not_signed_in", "Nicht angemeldet"),
+    ("error.permission_denied", "Keine Berechtigung"),
+    (
+        "error.employee_not_found",
+        "Mitarbeiter {id} nicht gefunden",
+    ),
+    (
+        "error.employee_has_no_account",
+        "Mitarbeiter {id} hat kein Benutzerkonto",
+    ),
+    ("error.user_not_found", "Benutzer {id} nicht gefunden"),
+    (
+        "error.email_conflict",
+        "Die E-Mail-Adresse {email} ist bereits vergeben",
+    ),
+    ("error.role_not_found", "Rolle {role} nicht gefunden"),
+    (
+        "error.role_not_grantable",
+        "Die Rolle {role} hat Berechtigungen, die Sie nicht haben, und kann nicht vergeben werden",
+    ),
+    ("error.role_name_taken", "Die Rolle {role} gibt es bereits"),
+    (
+        "error.unknown_permission",
+        "Unbekannte Berechtigung {permission}",
+    ),
+    (
+        "error.permission_not_grantable",
+        "Die Berechtigung {permission} haben Sie nicht und können sie nicht vergeben",
+    ),
+    (
+        "error.own_role_lockout",
+        "Der eigenen Rolle kann die Rollenverwaltung nicht entzogen werden",
+    ),
+    (
+        "error.employee_already_linked",
+        "Mitarbeiter {id} hat bereits ein Benutzerkonto ({email})",
+    ),
+    (
+        "error.deactivate_self",
+        "Das eigene Konto kann nicht deaktiviert werden",
+    ),
+    ("error.query_failed", "Abfrage fehlgeschlagen: {error}"),
+];
@@ -0,0 +1,298 @@
+//! English messages; also the fallback for keys missing from other catalogs
+
+pub const MESSAGES: &[(&str, &str)] = &[
+    // Header and pages
+    ("nav.home", "Home"),
+    ("nav.login", "Login"),
+    ("nav.employees", "Employees"),
+    ("nav.users", "Users"),
+    ("nav.roles", "Roles"),
+    ("nav.webhooks", "Webhooks"),
+    ("nav.logout", "Logout"),
+    ("nav.language", "Language"),
+    (
+        "nav.language_failed",
+        "Could not save the language: {error}",
+    ),
+    ("theme.light", "Light"),
+    ("theme.dark", "Dark"),
+    ("theme.system", "System"),
+    ("theme.toggle", "Theme: {name}"),
+    ("theme.save_failed", "Could not save the theme: {error}"),
+    ("home.tagline", "Clean Dioxus + TailwindCSS base ready."),
+    // Dashboard
+    ("dashboard.title", "Dashboard"),
+    ("dashboard.loading", "Loading dashboard..."),
+    ("dashboard.load_error", "Error loading dashboard: {error}"),
+    ("dashboard.employees", "Employees"),
+    ("dashboard.without_account", "Without user account"),
+    ("dashboard.users_per_role", "Users per role"),
+    ("dashboard.recent_hires", "Recent hires"),
+    ("dashboard.recent_changes", "Recent changes"),
+    ("dashboard.recent_logins", "Recent logins"),
+    ("dashboard.locked_accounts", "Locked accounts ({count})"),
+    ("dashboard.none", "Nothing yet."),
+    // Shared labels
+    ("common.id", "ID"),
+    ("common.email", "Email"),
+    ("common.actions", "Actions"),
+    ("common.view", "👁️ View"),
+    ("common.edit", "✏️ Edit"),
+    ("common.delete", "🗑️ Delete"),
+    ("common.cancel", "Cancel"),
+    ("common.confirm_delete", "Delete"),
+    ("common.undo", "Undo"),
+    ("common.refresh", "Refresh"),
+    ("common.saving", "Saving..."),
+    ("common.error", "Error: {error}"),
+    ("common.close", "Close"),
+    // Tables
+    ("table.columns", "Columns"),
+    ("table.selected.one", "{count} selected"),
+    ("table.selected.other", "{count} selected"),
+    // Login
+    ("auth.welcome", "Welcome Back"),
+    ("auth.prompt", "Please sign in to continue."),
+    ("auth.password", "Password"),
+    ("auth.submit", "Login"),
+    ("auth.forgot_password", "Forgot password?"),
+    ("auth.signed_in", "Signed in as {email}"),
+    ("auth.signed_out", "Signed out"),
+    ("auth.sign_out_failed", "Sign-out failed: {error}"),
+    // Employees
+    ("employees.title", "Employee Directory"),
+    ("employees.add", "Add Employee"),
+    ("employees.loading", "Loading employees..."),
+    ("employees.load_error", "Error loading employees: {error}"),
+    ("employees.empty", "No employees found."),
+    ("employees.first_name", "First Name"),
+    ("employees.create_login", "🔑 Create login"),
+    ("employees.last_name", "Last Name"),
+    ("employees.delete_selected", "Delete selected"),
+    ("employees.delete_title.one", "Delete Employee?"),
+    ("employees.delete_title.other", "Delete {count} Employees?"),
+    (
+        "employees.delete_confirm",
+        "Are you sure you want to delete {name}?",
+    ),
+    (
+        "employees.delete_confirm_selected.one",
+        "Are you sure you want to delete the selected employee?",
+    ),
+    (
+        "employees.delete_confirm_selected.other",
+        "Are you sure you want to delete the {count} selected employees?",
+    ),
+    ("employees.cannot_undo", "This action cannot be undone."),
+    ("employees.deleted_named", "Deleted {name}"),
+    ("employees.deleted.one", "Deleted {count} employee"),
+    ("employees.deleted.other", "Deleted {count} employees"),
+    (
+        "employees.delete_failed",
+        "Could not delete {name}: {error}",
+    ),
+    ("employees.restored", "Restored {restored} of {total}"),
+    (
+        "employees.restore_failed",
+        "Could not restore {name}: {error}",
+    ),
+    ("employees.set_role_selected", "Change role"),
+    ("employees.export_selected", "Export CSV"),
+    (
+        "employees.role_title.one",
+        "Change Role of {count} Employee",
+    ),
+    (
+        "employees.role_title.other",
+        "Change Role of {count} Employees",
+    ),
+    (
+        "employees.role_confirm.one",
+        "The user account of the selected employee gets this role.",
+    ),
+    (
+        "employees.role_confirm.other",
+        "The user accounts of all {count} selected employees get this role.",
+    ),
+    ("employees.role_apply", "Apply"),
+    (
+        "employees.role_set.one",
+        "Gave {count} account the role {role}",
+    ),
+    (
+        "employees.role_set.other",
+        "Gave {count} accounts the role {role}",
+    ),
+    (
+        "employees.role_failed",
+        "Could not change the role of {name}: {error}",
+    ),
+    ("employees.exported.one", "{count} employee exported"),
+    ("employees.exported.other", "{count} employees exported"),
+    ("employees.download", "Download employees.csv"),
+    // Employee detail page
+    ("employee_detail.back", "← Back to employees"),
+    ("employee_detail.loading", "Loading employee..."),
+    (
+        "employee_detail.load_error",
+        "Error loading employee: {error}",
+    ),
+    ("employee_detail.record", "Details"),
+    ("employee_detail.account", "User Account"),
+    (
+        "employee_detail.no_account",
+        "No user account is linked to this employee.",
+    ),
+    ("employee_detail.deleted", "Employee deleted"),
+    // Employee form
+    ("employee_form.create_title", "Add New Employee"),
+    ("employee_form.edit_title", "Edit Employee"),
+    ("employee_form.create", "Create"),
+    ("employee_form.update", "Update"),
+    ("employee_form.first_name_placeholder", "Enter first name"),
+    ("employee_form.last_name_placeholder", "Enter last name"),
+    ("employee_form.email_placeholder", "Enter email address"),
+    ("employee_form.created", "Employee created"),
+    ("employee_form.updated", "Employee updated"),
+    // Users
+    ("users.title", "Users"),
+    ("users.loading", "Loading users..."),
+    ("users.load_error", "Error loading users: {error}"),
+    ("users.empty", "No users found."),
+    ("users.role", "Role"),
+    ("users.employee", "Employee"),
+    ("users.add", "Add User"),
+    ("users.create_title", "Add New User"),
+    ("users.edit_title", "Edit User"),
+    ("users.create", "Create"),
+    ("users.update", "Update"),
+    (
+        "users.search_employee",
+        "Search employees without an account",
+    ),
+    ("users.no_employee", "No employee"),
+    (
+        "users.no_matches",
+        "No employee without an account matches.",
+    ),
+    ("users.created", "User {email} created"),
+    ("users.password_once", "Initial password, shown only once:"),
+    ("users.done", "Done"),
+    ("users.updated", "User updated"),
+    ("users.status", "Status"),
+    ("users.active", "Active"),
+    ("users.inactive", "Deactivated"),
+    ("users.deactivated_since", "Deactivated since {at}"),
+    ("users.filter_all", "All accounts"),
+    ("users.filter_active", "Active accounts"),
+    ("users.filter_deactivated", "Deactivated accounts"),
+    ("users.activate", "Activate"),
+    ("users.deactivate", "Deactivate"),
+    ("users.activated", "User {email} activated"),
+    (
+        "users.deactivated",
+        "User {email} deactivated and signed out",
+    ),
+    // Roles
+    ("roles.title", "Roles and Permissions"),
+    ("roles.role", "Role"),
+    ("roles.new_name", "New role name"),
+    ("roles.add", "Add Role"),
+    ("roles.loading", "Loading roles..."),
+    ("roles.load_error", "Error loading roles: {error}"),
+    ("roles.created", "Role {role} added"),
+    ("roles.saved", "Permissions of {role} saved"),
+    ("permission.employee.read", "View employees"),
+    ("permission.employee.write", "Edit employees"),
+    ("permission.user.read", "View users"),
+    ("permission.user.manage", "Manage users"),
+    ("permission.role.manage", "Manage roles"),
+    ("permission.webhook.manage", "Manage webhooks"),
+    ("permission.audit.read", "View account activity"),
+    // Webhooks
+    ("webhooks.title", "Webhooks"),
+    ("webhooks.url", "URL"),
+    ("webhooks.event", "Event"),
+    ("webhooks.secret", "Signing Secret"),
+    ("webhooks.add", "Add Webhook"),
+    ("webhooks.pause", "Pause"),
+    ("webhooks.resume", "Resume"),
+    ("webhooks.loading", "Loading webhooks..."),
+    ("webhooks.load_error", "Error loading webhooks: {error}"),
+    ("webhooks.empty", "No webhooks registered."),
+    ("webhooks.added", "Webhook added for {url}"),
+    ("webhooks.add_failed", "Could not add webhook: {error}"),
+    ("webhooks.resumed", "Webhook resumed"),
+    ("webhooks.paused", "Webhook paused"),
+    (
+        "webhooks.update_failed",
+        "Could not update webhook: {error}",
+    ),
+    ("webhooks.deleted", "Webhook deleted"),
+    (
+        "webhooks.delete_failed",
+        "Could not delete webhook: {error}",
+    ),
+    ("deliveries.title", "Recent Deliveries"),
+    ("deliveries.loading", "Loading deliveries..."),
+    ("deliveries.load_error", "Error loading deliveries: {error}"),
+    ("deliveries.empty", "No deliveries yet."),
+    ("deliveries.created", "Created"),
+    ("deliveries.status", "Status"),
+    ("deliveries.status.pending", "pending"),
+    ("deliveries.status.delivered", "delivered"),
+    ("deliveries.status.failed", "failed"),
+    ("deliveries.attempts", "Attempts"),
+    ("deliveries.last_error", "Last Error"),
+    ("deliveries.redeliver", "Redeliver"),
+    ("deliveries.requeued", "Delivery queued again"),
+    (
+        "deliveries.redeliver_failed",
+        "Could not redeliver: {error}",
+    ),
+    // Field validation (validation::FieldError)
+    ("validation.invalid_input", "Invalid input"),
+    ("validation.required", "Required"),
+    ("validation.too_long", "At most {max} characters"),
+    ("validation.email", "Not a valid email address"),
+    ("validation.phone", "Not a valid phone number"),
+    // Server errors (session::AccessError, directory::DirectoryError,
+    // roles::RoleError)
+    ("error.invalid_login", "Invalid email or password"),
+    ("error.not_signed_in", "Not signed in"),
+    ("error.permission_denied", "Permission denied"),
+    ("error.employee_not_found", "Employee {id} not found"),
+    (
+        "error.employee_has_no_account",
+        "Employee {id} has no user account",
+    ),
+    ("error.user_not_found", "User {id} not found"),
+    ("error.email_conflict", "Email {email} already exists"),
+    ("error.role_not_found", "Role {role} not found"),
+    (
+        "error.role_not_grantable",
+        "You cannot grant {role}, which has permissions you do not have",
+    ),
+    ("error.role_name_taken", "Role {role} already exists"),
+    (
+        "error.unknown_permission",
+        "Unknown permission {permission}",
+    ),
+    (
+        "error.permission_not_grantable",
+        "You cannot grant {permission}, which you do not have",
+    ),
+    (
+        "error.own_role_lockout",
+        "You cannot take role management away from your own role",
+    ),
+    (
+        "error.employee_already_linked",
+        "Employee {id} already has a user account ({email})",
+    ),
+    (
+        "error.deactivate_self",
+        "You cannot deactivate your own account",
+    ),
+    ("error.query_failed", "Query failed: {error}"),
+];
@@ -0,0 +1,433 @@
+//! Translations and locale-aware formatting
+//!
+//! Messages are looked up by key in the catalog of a `Locale` (`en.rs`,
+//! `de.rs`); a key missing from German falls back to English, one missing
+//! everywhere to the key itself. `{name}` placeholders are filled from
+//! `(name, value)` pairs. A plural message is two keys, `<key>.one` and
+//! `<key>.other`, which is all English and German need; `{count}` is filled
+//! with the formatted number.
+//!
+//! The UI reads the current locale from the `I18n` context that `App`
+//! provides. The server translates its own messages (field errors, access
+//! errors, `DirectoryError`) for the request with `request_locale()`: the
+//! `locale` cookie written by the language switcher and at login, else the
+//! browser's `Accept-Language`. Error enums implement `Translate`, which
+//! ties each variant to its catalog key.
+
+mod de;
+mod en;
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Cookie holding the chosen locale code
+pub const COOKIE: &str = "locale";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::De];
+
+    /// Language code stored in the cookie and in `users.locale`
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::De => "de",
+        }
+    }
+
+    /// Parse a language tag such as `de`, `de-AT` or `en_US`
+    pub fn from_code(tag: &str) -> Option<Self> {
+        let language = tag.trim().split(['-', '_']).next()?;
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.code().eq_ignore_ascii_case(language))
+    }
+
+    /// The language's own name, for the switcher
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::En => "English",
+            Self::De => "Deutsch",
+        }
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::En => en::MESSAGES,
+            Self::De => de::MESSAGES,
+        }
+    }
+
+    /// Thousands and decimal separators
+    fn separators(self) -> (char, char) {
+        match self {
+            Self::En => (',', '.'),
+            Self::De => ('.', ','),
+        }
+    }
+}
+
+/// Placeholder values for a message, e.g. `[("name", name)]`
+pub type Args<'a> = &'a [(&'a str, String)];
+
+fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    locale
+        .catalog()
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, message)| *message)
+}
+
+/// The message for `key`
+pub fn t(locale: Locale, key: &str) -> String {
+    tr(locale, key, &[])
+}
+
+/// The message for `key` with its placeholders filled from `args`
+pub fn tr(locale: Locale, key: &str, args: Args) -> String {
+    let template = lookup(locale, key)
+        .or_else(|| lookup(Locale::En, key))
+        .unwrap_or(key);
+    args.iter()
+        .fold(template.to_string(), |message, (name, value)| {
+            message.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+/// `<key>.one` or `<key>.other` for `count`, with `{count}` filled in
+pub fn plural(locale: Locale, key: &str, count: i64, args: Args) -> String {
+    let form = if count == 1 { "one" } else { "other" };
+    let mut all = vec![("count", format_number(locale, count))];
+    all.extend(args.iter().cloned());
+    tr(locale, &format!("{}.{}", key, form), &all)
+}
+
+/// An error enum whose variants map to catalog keys
+pub trait Translate {
+    fn key(&self) -> &'static str;
+
+    fn args(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    fn message(&self, locale: Locale) -> String {
+        tr(locale, self.key(), &self.args())
+    }
+}
+
+//
+// -------------------- FORMATTING --------------------
+//
+
+/// Integer with thousands separators: `1,234,567` / `1.234.567`
+pub fn format_number(locale: Locale, value: i64) -> String {
+    let (thousands, _) = locale.separators();
+    let digits = value.unsigned_abs().to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(thousands);
+        }
+        grouped.push(digit);
+    }
+    if value < 0 {
+        grouped.insert(0, '-');
+    }
+    grouped
+}
+
+/// Number rounded to `places` decimals: `1,234.5` / `1.234,5`
+pub fn format_decimal(locale: Locale, value: f64, places: usize) -> String {
+    let (_, decimal) = locale.separators();
+    let fixed = format!("{:.*}", places, value.abs());
+    let (whole, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+    let mut out = format_number(locale, whole.parse().unwrap_or(0));
+    if value < 0.0 && fixed.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        out.insert(0, '-');
+    }
+    if !fraction.is_empty() {
+        out.push(decimal);
+        out.push_str(fraction);
+    }
+    out
+}
+
+const MONTHS_EN: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Year, month (1-based), day and, for timestamps, hour and minute
+type DateParts = (u32, usize, u32, Option<(u32, u32)>);
+
+/// The parts of an RFC 3339 / ISO 8601 timestamp or date, as the server
+/// functions send them
+fn parse_timestamp(iso: &str) -> Option<DateParts> {
+    let year = iso.get(0..4)?.parse().ok()?;
+    let month: usize = iso.get(5..7)?.parse().ok()?;
+    let day = iso.get(8..10)?.parse().ok()?;
+    if !(1..=12).contains(&month) || iso.get(4..5)? != "-" || iso.get(7..8)? != "-" {
+        return None;
+    }
+    let time = match iso.get(10..11) {
+        Some("T" | " ") => Some((
+            iso.get(11..13)?.parse().ok()?,
+            iso.get(14..16)?.parse().ok()?,
+        )),
+        _ => None,
+    };
+    Some((year, month, day, time))
+}
+
+/// Date of a timestamp: `Jan 5, 2026` / `05.01.2026`. Anything that does
+/// not parse comes back unchanged.
+pub fn format_date(locale: Locale, iso: &str) -> String {
+    let Some((year, month, day, _)) = parse_timestamp(iso) else {
+        return iso.to_string();
+    };
+    match locale {
+        Locale::En => format!("{} {}, {}", MONTHS_EN[month - 1], day, year),
+        Locale::De => format!("{:02}.{:02}.{}", day, month, year),
+    }
+}
+
+/// Date and time of a timestamp, in the timestamp's own offset:
+/// `Jan 5, 2026, 2:03 PM` / `05.01.2026, 14:03`
+pub fn format_datetime(locale: Locale, iso: &str) -> String {
+    let Some((_, _, _, Some((hour, minute)))) = parse_timestamp(iso) else {
+        return format_date(locale, iso);
+    };
+    let date = format_date(locale, iso);
+    match locale {
+        Locale::En => {
+            let suffix = if hour < 12 { "AM" } else { "PM" };
+            let hour12 = match hour % 12 {
+                0 => 12,
+                h => h,
+            };
+            format!("{}, {}:{:02} {}", date, hour12, minute, suffix)
+        }
+        Locale::De => format!("{}, {:02}:{:02}", date, hour, minute),
+    }
+}
+
+//
+// -------------------- UI CONTEXT --------------------
+//
+
+/// The UI's current locale. Reading it subscribes the component, so
+/// switching the language re-renders every translated string.
+#[derive(Clone, Copy, PartialEq)]
+pub struct I18n {
+    pub locale: Signal<Locale>,
+}
+
+impl I18n {
+    pub fn locale(&self) -> Locale {
+        (self.locale)()
+    }
+
+    pub fn t(&self, key: &str) -> String {
+        t(self.locale(), key)
+    }
+
+    pub fn tr(&self, key: &str, args: Args) -> String {
+        tr(self.locale(), key, args)
+    }
+
+    pub fn plural(&self, key: &str, count: usize, args: Args) -> String {
+        plural(self.locale(), key, count as i64, args)
+    }
+
+    pub fn number(&self, value: i64) -> String {
+        format_number(self.locale(), value)
+    }
+
+    pub fn date(&self, iso: &str) -> String {
+        format_date(self.locale(), iso)
+    }
+
+    pub fn datetime(&self, iso: &str) -> String {
+        format_datetime(self.locale(), iso)
+    }
+
+    pub fn message(&self, error: &impl Translate) -> String {
+        error.message(self.locale())
+    }
+}
+
+pub fn use_i18n() -> I18n {
+    use_context::<I18n>()
+}
+
+//
+// -------------------- SERVER --------------------
+//
+
+/// Locale of the current request: the `locale` cookie, else the first
+/// supported language in `Accept-Language`, else English
+#[cfg(feature = "server")]
+pub fn request_locale() -> Locale {
+    use dioxus::fullstack::FullstackContext;
+    use dioxus::fullstack::http::header;
+
+    if let Some(locale) =
+        crate::server::session::request_cookie(COOKIE).and_then(|code| Locale::from_code(&code))
+    {
+        return locale;
+    }
+    FullstackContext::current()
+        .and_then(|ctx| {
+            let parts = ctx.parts_mut();
+            let value = parts.headers.get(header::ACCEPT_LANGUAGE)?.to_str().ok()?;
+            from_accept_language(value)
+        })
+        .unwrap_or_default()
+}
+
+/// First supported language of an `Accept-Language` header, by quality
+#[cfg_attr(not(feature = "server"), allow(dead_code))]
+fn from_accept_language(header: &str) -> Option<Locale> {
+    let mut ranked: Vec<(f32, Locale)> = header
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let locale = Locale::from_code(parts.next()?)?;
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.parse().ok())?;
+            Some((quality, locale))
+        })
+        .collect();
+    // Stable: equal qualities keep the header's order
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    ranked.into_iter().find(|(q, _)| *q > 0.0).map(|(_, l)| l)
+}
+
+/// A server function error with `error`'s message in the request's locale
+#[cfg(feature = "server")]
+pub fn server_error(error: &impl Translate) -> ServerFnError {
+    ServerFnError::new(error.message(request_locale()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    fn keys(locale: Locale) -> BTreeSet<&'static str> {
+        locale.catalog().iter().map(|(k, _)| *k).collect()
+    }
+
+    fn placeholders(message: &str) -> BTreeSet<&str> {
+        message
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect()
+    }
+
+    #[test]
+    fn catalogs_have_the_same_keys_and_placeholders() {
+        for locale in Locale::ALL {
+            let unique = keys(locale);
+            assert_eq!(
+                unique.len(),
+                locale.catalog().len(),
+                "{:?} repeats a key",
+                locale
+            );
+            assert_eq!(unique, keys(Locale::En), "{:?} keys differ", locale);
+        }
+        for (key, english) in en::MESSAGES {
+            let german = lookup(Locale::De, key).unwrap();
+            assert_eq!(placeholders(english), placeholders(german), "{}", key);
+        }
+    }
+
+    #[test]
+    fn plurals_come_in_pairs() {
+        let all = keys(Locale::En);
+        for key in &all {
+            if let Some(base) = key.strip_suffix(".one") {
+                assert!(all.contains(format!("{}.other", base).as_str()), "{}", key);
+            }
+        }
+    }
+
+    #[test]
+    fn falls_back_to_english_then_the_key() {
+        assert_eq!(t(Locale::De, "nav.employees"), "Mitarbeiter");
+        assert_eq!(t(Locale::En, "nav.employees"), "Employees");
+        assert_eq!(t(Locale::De, "no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn fills_placeholders_and_plurals() {
+        let args = [("email", "ada@example.com".to_string())];
+        assert_eq!(
+            tr(Locale::De, "auth.signed_in", &args),
+            "Angemeldet als ada@example.com"
+        );
+        assert_eq!(
+            plural(Locale::En, "employees.deleted", 1, &[]),
+            "Deleted 1 employee"
+        );
+        assert_eq!(
+            plural(Locale::De, "employees.deleted", 1200, &[]),
+            "1.200 Mitarbeiter gelöscht"
+        );
+    }
+
+    #[test]
+    fn formats_numbers() {
+        assert_eq!(format_number(Locale::En, 0), "0");
+        assert_eq!(format_number(Locale::En, 999), "999");
+        assert_eq!(format_number(Locale::En, 1_234_567), "1,234,567");
+        assert_eq!(format_number(Locale::De, -1_234), "-1.234");
+        assert_eq!(format_decimal(Locale::En, 1234.5, 1), "1,234.5");
+        assert_eq!(format_decimal(Locale::De, 1234.56, 1), "1.234,6");
+        assert_eq!(format_decimal(Locale::De, -0.04, 1), "0,0");
+        assert_eq!(format_decimal(Locale::En, 3.0, 0), "3");
+    }
+
+    #[test]
+    fn formats_dates() {
+        let ts = "2026-01-05T14:03:09+00:00";
+        assert_eq!(format_date(Locale::En, ts), "Jan 5, 2026");
+        assert_eq!(format_date(Locale::De, ts), "05.01.2026");
+        assert_eq!(format_datetime(Locale::En, ts), "Jan 5, 2026, 2:03 PM");
+        assert_eq!(format_datetime(Locale::De, ts), "05.01.2026, 14:03");
+        assert_eq!(
+            format_datetime(Locale::En, "2026-01-05T00:15:00Z"),
+            "Jan 5, 2026, 12:15 AM"
+        );
+        assert_eq!(format_datetime(Locale::De, "2026-12-31"), "31.12.2026");
+        assert_eq!(format_date(Locale::De, "soon"), "soon");
+    }
+
+    #[test]
+    fn parses_language_tags() {
+        assert_eq!(Locale::from_code("de"), Some(Locale::De));
+        assert_eq!(Locale::from_code("de-AT"), Some(Locale::De));
+        assert_eq!(Locale::from_code("EN_us"), Some(Locale::En));
+        assert_eq!(Locale::from_code("fr"), None);
+
+        assert_eq!(
+            from_accept_language("de-DE,de;q=0.9,en;q=0.8"),
+            Some(Locale::De)
+        );
+        assert_eq!(
+            from_accept_language("fr-CH, en;q=0.5, de;q=0.7"),
+            Some(Locale::De)
+        );
+        assert_eq!(from_accept_language("fr, de;q=0"), None);
+        assert_eq!(from_accept_language(""), None);
+    }
+}
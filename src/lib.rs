@@ -0,0 +1,9 @@
+//! Employee directory: Dioxus app, entities and server-side logic, shared by
+//! the web server (`src/main.rs`) and the admin CLI (`src/bin/rfc-admin`)
+pub mod app;
+pub mod components;
+pub mod entities;
+pub mod i18n;
+pub mod server;
+pub mod theme;
+pub mod validation;
@@ -1,9 +1,36 @@
-mod app;
-mod components;
-mod server;
-mod entities;
+use rust_fullstack_core::app;
+#[cfg(feature = "server")]
+use rust_fullstack_core::server;
 
 fn main() {
     dotenvy::dotenv().ok();
+
+    #[cfg(feature = "server")]
+    {
+        let config = server::config::init();
+        server::telemetry::init(&config.log);
+        server::metrics::init();
+        tracing::info!("Effective configuration:\n{}", config);
+    }
+
+    // Server build: mount the extra axum routes next to the Dioxus app
+    #[cfg(feature = "server")]
+    dioxus::serve(|| async move {
+        server::webhooks::worker::spawn();
+        server::metrics::spawn_upkeep();
+        server::health::spawn_shutdown_handler();
+
+        Ok(dioxus::server::router(app::App)
+            .merge(server::health::router())
+            .merge(server::rest::router())
+            .merge(server::scim::router())
+            .merge(server::graphql::router())
+            .merge(server::metrics::router())
+            .layer(axum::middleware::from_fn(server::health::track_in_flight))
+            .layer(server::config::get().http.cors_layer())
+            .layer(axum::middleware::from_fn(server::telemetry::request_id)))
+    });
+
+    #[cfg(not(feature = "server"))]
     dioxus::launch(app::App);
 }
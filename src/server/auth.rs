@@ -0,0 +1,286 @@
+//! Password hashing helpers shared by the server-side features, login and logout
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Locale;
+use crate::theme::Theme;
+
+#[cfg(feature = "server")]
+use bcrypt::BcryptError;
+
+#[cfg(feature = "server")]
+use sea_orm::*;
+
+#[cfg(feature = "server")]
+use crate::entities::{app_role, users};
+
+#[cfg(feature = "server")]
+use crate::server::db_connection::get_db;
+
+/// Hash a password with bcrypt on the blocking thread pool
+#[cfg(feature = "server")]
+pub async fn hash_password(password: String) -> Result<String, BcryptError> {
+    let cost = crate::server::config::get().auth.bcrypt_cost;
+    tokio::task::spawn_blocking(move || bcrypt::hash(password, cost))
+        .await
+        .expect("bcrypt hashing task panicked")
+}
+
+/// Check a password against a bcrypt hash on the blocking thread pool
+#[cfg(feature = "server")]
+pub async fn verify_password(password: String, hash: String) -> Result<bool, BcryptError> {
+    tokio::task::spawn_blocking(move || bcrypt::verify(password, &hash))
+        .await
+        .expect("bcrypt verification task panicked")
+}
+
+/// Generate a random password for accounts that only log in through SSO
+#[cfg(feature = "server")]
+pub fn random_password() -> String {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).expect("OS random number generator unavailable");
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Compare secrets without leaking the position of the first difference
+#[cfg(feature = "server")]
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+//
+// -------------------- ROLES --------------------
+//
+
+// Roles are rows in `app_role`; these are the ones every database starts
+// with. Checks test permissions, never role names.
+pub const ROLE_ADMIN: &str = "ROLE_ADMIN";
+pub const ROLE_MANAGER: &str = "ROLE_MANAGER";
+pub const ROLE_HR: &str = "ROLE_HR";
+pub const ROLE_IT: &str = "ROLE_IT";
+pub const ROLE_EMPLOYEE: &str = "ROLE_EMPLOYEE";
+
+//
+// -------------------- PERMISSIONS --------------------
+//
+
+// Shared with the UI, which hides what the server would refuse anyway
+/// List and open employees
+pub const EMPLOYEE_READ: &str = "employee.read";
+/// Create, edit and delete employees
+pub const EMPLOYEE_WRITE: &str = "employee.write";
+/// List user accounts and see which employee they belong to
+pub const USER_READ: &str = "user.read";
+/// Create accounts and change their role and employee
+pub const USER_MANAGE: &str = "user.manage";
+/// Create roles and edit their permissions
+pub const ROLE_MANAGE: &str = "role.manage";
+/// Register webhooks and read their delivery log
+pub const WEBHOOK_MANAGE: &str = "webhook.manage";
+/// See account activity: recent logins and deactivated accounts
+pub const AUDIT_READ: &str = "audit.read";
+
+/// Every permission a role can be granted, in display order
+pub const PERMISSIONS: &[&str] = &[
+    EMPLOYEE_READ,
+    EMPLOYEE_WRITE,
+    USER_READ,
+    USER_MANAGE,
+    ROLE_MANAGE,
+    WEBHOOK_MANAGE,
+    AUDIT_READ,
+];
+
+/// What the reference roles start with; `migrations/011_role_permissions.sql`
+/// grants the same
+pub const DEFAULT_PERMISSIONS: &[(&str, &[&str])] = &[
+    (ROLE_ADMIN, PERMISSIONS),
+    (ROLE_MANAGER, &[EMPLOYEE_READ]),
+    (ROLE_HR, &[EMPLOYEE_READ, EMPLOYEE_WRITE, USER_READ]),
+    (ROLE_IT, &[EMPLOYEE_READ, USER_READ, USER_MANAGE]),
+    (ROLE_EMPLOYEE, &[EMPLOYEE_READ]),
+];
+
+//
+// -------------------- LOGIN --------------------
+//
+
+/// The account a successful login resolved to
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionUser {
+    pub id: i32,
+    pub email: String,
+    pub role: String,
+    /// The role's permissions when this was loaded. The session cookie does
+    /// not carry them; server functions read them fresh for every check.
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+impl SessionUser {
+    pub fn can(&self, permission: &str) -> bool {
+        self.permissions.iter().any(|p| p == permission)
+    }
+}
+
+/// Verify email and password. Unknown accounts, wrong passwords and
+/// deactivated users all get the same error.
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("login"))]
+pub async fn login(email: String, password: String) -> Result<SessionUser, ServerFnError> {
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let found = users::Entity::find()
+        .filter(users::Column::Email.eq(email.trim()))
+        .find_also_related(app_role::Entity)
+        .one(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?;
+
+    let (mut saved_locale, mut saved_theme) = (None, None);
+    let mut session = match found {
+        Some((user, role)) if user.is_active => {
+            let valid = verify_password(password, user.password_hash)
+                .await
+                .unwrap_or(false);
+            saved_locale = user.locale.as_deref().and_then(Locale::from_code);
+            saved_theme = user.theme.as_deref().and_then(Theme::from_code);
+            valid.then(|| SessionUser {
+                id: user.id,
+                email: user.email,
+                role: role.map(|r| r.name).unwrap_or_default(),
+                permissions: Vec::new(),
+            })
+        }
+        _ => None,
+    };
+
+    if let Some(session) = session.as_mut() {
+        session.permissions = crate::server::roles::permissions_of(&db, &session.role)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?;
+    }
+
+    crate::server::metrics::record_login(session.is_some());
+    match session {
+        Some(session) => {
+            tracing::info!(user_id = session.id, "Login succeeded");
+            // Only the dashboard reads it, so a failed write does not block login
+            if let Err(e) = users::Entity::update_many()
+                .col_expr(
+                    users::Column::LastLoginAt,
+                    sea_orm::sea_query::Expr::current_timestamp().into(),
+                )
+                .filter(users::Column::Id.eq(session.id))
+                .exec(&db)
+                .await
+            {
+                tracing::warn!(user_id = session.id, error = %e, "Could not record the login time");
+            }
+            crate::server::session::start(&session);
+            // The language and theme the user picked last time, on any device
+            if let Some(locale) = saved_locale {
+                set_preference_cookie(crate::i18n::COOKIE, locale.code());
+            }
+            if let Some(theme) = saved_theme {
+                set_preference_cookie(crate::theme::COOKIE, theme.code());
+            }
+            Ok(session)
+        }
+        None => {
+            tracing::info!("Login failed");
+            let locale = crate::i18n::request_locale();
+            Err(ServerFnError::new(crate::i18n::t(
+                locale,
+                "error.invalid_login",
+            )))
+        }
+    }
+}
+
+/// The account of the current session cookie, if any and not revoked, with
+/// its role's permissions as they are now
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("current_session"))]
+pub async fn current_session() -> Result<Option<SessionUser>, ServerFnError> {
+    crate::server::session::current_with_permissions().await
+}
+
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("logout"))]
+pub async fn logout() -> Result<(), ServerFnError> {
+    crate::server::session::end();
+    Ok(())
+}
+
+//
+// -------------------- PREFERENCES --------------------
+//
+// Language and theme live in a cookie per browser and, for a signed-in
+// user, on the account; login copies the account's choice into the cookies.
+//
+
+/// The language to show: the cookie from the switcher or the last login,
+/// else the browser's `Accept-Language`
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("current_locale"))]
+pub async fn current_locale() -> Result<Locale, ServerFnError> {
+    Ok(crate::i18n::request_locale())
+}
+
+/// Switch the language for this browser and, when signed in, save it for
+/// the user so the next login anywhere starts in it
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("set_locale"))]
+pub async fn set_locale(locale: Locale) -> Result<(), ServerFnError> {
+    set_preference_cookie(crate::i18n::COOKIE, locale.code());
+    save_for_user(users::Column::Locale, locale.code()).await
+}
+
+/// The theme from the cookie, `System` without one
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("current_theme"))]
+pub async fn current_theme() -> Result<Theme, ServerFnError> {
+    Ok(crate::theme::request_theme())
+}
+
+/// Switch the theme for this browser and, when signed in, save it for the
+/// user
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("set_theme"))]
+pub async fn set_theme(theme: Theme) -> Result<(), ServerFnError> {
+    set_preference_cookie(crate::theme::COOKIE, theme.code());
+    save_for_user(users::Column::Theme, theme.code()).await
+}
+
+/// Remember a preference in this browser for a year
+#[cfg(feature = "server")]
+fn set_preference_cookie(name: &str, code: &str) {
+    crate::server::session::set_cookie(&format!(
+        "{}={}; Path=/; SameSite=Lax; Max-Age={}",
+        name,
+        code,
+        365 * 24 * 60 * 60
+    ));
+}
+
+/// Store `code` in `column` of the signed-in user's row; signed out there
+/// is only the cookie
+#[cfg(feature = "server")]
+async fn save_for_user(column: users::Column, code: &str) -> Result<(), ServerFnError> {
+    let Some(user) = crate::server::session::current() else {
+        return Ok(());
+    };
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+    users::Entity::update_many()
+        .col_expr(column, sea_orm::sea_query::Expr::value(code))
+        .filter(users::Column::Id.eq(user.id))
+        .exec(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?;
+    Ok(())
+}
@@ -0,0 +1,210 @@
+// src/server/bulk.rs
+// Changes to many employees at once, for the selection in the Employees
+// table. Each call runs in one transaction. Ids that cannot be changed
+// (unknown, or without an account for a role change) are skipped and
+// reported with the reason; a database error rolls back the whole batch.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use sea_orm::*;
+
+#[cfg(feature = "server")]
+use crate::entities::employee;
+
+#[cfg(feature = "server")]
+use crate::i18n::{Locale, Translate};
+
+#[cfg(feature = "server")]
+use crate::server::auth::{EMPLOYEE_READ, EMPLOYEE_WRITE, USER_MANAGE};
+
+#[cfg(feature = "server")]
+use crate::server::db_connection::get_db;
+
+#[cfg(feature = "server")]
+use crate::server::directory::{self, DirectoryError};
+
+#[cfg(feature = "server")]
+use crate::server::session::require_permission;
+
+//
+// -------------------- DTOs --------------------
+//
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BulkResult {
+    /// Ids that were changed
+    pub done: Vec<i32>,
+    /// Ids that were skipped
+    pub failed: Vec<BulkFailure>,
+    /// Deleted employees that had a user account, which was unlinked or
+    /// deactivated
+    #[serde(default)]
+    pub with_account: Vec<i32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BulkFailure {
+    pub id: i32,
+    /// Why, in the request's language
+    pub error: String,
+}
+
+#[cfg(feature = "server")]
+impl BulkResult {
+    /// Record the outcome for `id`; database errors abort the batch
+    fn record(
+        &mut self,
+        id: i32,
+        outcome: Result<(), DirectoryError>,
+        locale: Locale,
+    ) -> Result<(), DirectoryError> {
+        match outcome {
+            Ok(()) => self.done.push(id),
+            Err(DirectoryError::Db(e)) => return Err(DirectoryError::Db(e)),
+            Err(e) => self.failed.push(BulkFailure {
+                id,
+                error: e.message(locale),
+            }),
+        }
+        Ok(())
+    }
+}
+
+//
+// -------------------- SERVER FUNCTIONS --------------------
+//
+
+/// Delete the employees with `ids`. With `auth.deactivate_with_employee`
+/// their accounts are deactivated too.
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("delete_employees"))]
+pub async fn delete_employees(ids: Vec<i32>) -> Result<BulkResult, ServerFnError> {
+    require_permission(EMPLOYEE_WRITE).await?;
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let deactivate = crate::server::config::get().auth.deactivate_with_employee;
+    remove_employees(&db, ids, deactivate, crate::i18n::request_locale())
+        .await
+        .map_err(|e| crate::i18n::server_error(&e))
+}
+
+/// Give the accounts linked to the employees with `ids` the role `role`,
+/// which must not have permissions the caller lacks. Employees without an
+/// account are reported as failed.
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("set_employees_role"))]
+pub async fn set_employees_role(ids: Vec<i32>, role: String) -> Result<BulkResult, ServerFnError> {
+    let viewer = require_permission(USER_MANAGE).await?;
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    assign_role(
+        &db,
+        &viewer.permissions,
+        ids,
+        &role,
+        crate::i18n::request_locale(),
+    )
+    .await
+    .map_err(|e| crate::i18n::server_error(&e))
+}
+
+/// The employees with `ids` as CSV, in the format of `rfc-admin employee
+/// export`; unknown ids are left out
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("export_employees"))]
+pub async fn export_employees(ids: Vec<i32>) -> Result<String, ServerFnError> {
+    require_permission(EMPLOYEE_READ).await?;
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let employees = employee::Entity::find()
+        .filter(employee::Column::Id.is_in(ids))
+        .order_by_asc(employee::Column::Id)
+        .all(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?;
+
+    let mut out = Vec::new();
+    write_csv(&mut out, &employees)
+        .map_err(|e| ServerFnError::new(format!("Export failed: {}", e)))?;
+    String::from_utf8(out).map_err(|e| ServerFnError::new(format!("Export failed: {}", e)))
+}
+
+//
+// -------------------- HELPERS --------------------
+//
+
+/// `id,first_name,last_name,email` with a header row; `import` ignores `id`
+#[cfg(feature = "server")]
+pub fn write_csv<W: std::io::Write>(out: W, employees: &[employee::Model]) -> csv::Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(["id", "first_name", "last_name", "email"])?;
+    for e in employees {
+        writer.write_record([
+            e.id.to_string(),
+            e.first_name.clone().unwrap_or_default(),
+            e.last_name.clone().unwrap_or_default(),
+            e.email.clone().unwrap_or_default(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Each id once, in ascending order
+#[cfg(feature = "server")]
+fn distinct(mut ids: Vec<i32>) -> Vec<i32> {
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+#[cfg(feature = "server")]
+pub async fn remove_employees<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    ids: Vec<i32>,
+    deactivate_accounts: bool,
+    locale: Locale,
+) -> Result<BulkResult, DirectoryError> {
+    let txn = db.begin().await?;
+    let mut result = BulkResult::default();
+    for id in distinct(ids) {
+        let outcome = directory::delete_employee(&txn, id, deactivate_accounts).await;
+        if let Ok(true) = outcome {
+            result.with_account.push(id);
+        }
+        result.record(id, outcome.map(|_| ()), locale)?;
+    }
+    txn.commit().await?;
+    Ok(result)
+}
+
+#[cfg(feature = "server")]
+pub async fn assign_role<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    granter: &[String],
+    ids: Vec<i32>,
+    role: &str,
+    locale: Locale,
+) -> Result<BulkResult, DirectoryError> {
+    let txn = db.begin().await?;
+    let role_id = directory::grantable_role_id(&txn, granter, role).await?;
+    let mut result = BulkResult::default();
+    for id in distinct(ids) {
+        let outcome = directory::set_account_role(&txn, id, role_id)
+            .await
+            .map(|_| ());
+        result.record(id, outcome, locale)?;
+    }
+    txn.commit().await?;
+    Ok(result)
+}
@@ -0,0 +1,213 @@
+// src/server/dashboard.rs
+// Directory statistics for the home page. One server function answers with
+// counts and short lists; every figure is a COUNT or a LIMITed query, so the
+// cost does not grow with the directory.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::server::db::Employee;
+
+#[cfg(feature = "server")]
+use sea_orm::prelude::DateTimeWithTimeZone;
+
+#[cfg(feature = "server")]
+use sea_orm::sea_query::Expr;
+
+#[cfg(feature = "server")]
+use sea_orm::*;
+
+#[cfg(feature = "server")]
+use crate::entities::{app_role, employee, users};
+
+#[cfg(feature = "server")]
+use crate::server::auth::{AUDIT_READ, EMPLOYEE_WRITE};
+
+#[cfg(feature = "server")]
+use crate::server::db_connection::get_db;
+
+/// Entries in each of the dashboard's lists
+#[cfg(feature = "server")]
+const LIST_LIMIT: u64 = 5;
+
+//
+// -------------------- DTOs --------------------
+//
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Dashboard {
+    pub total_employees: u64,
+    pub employees_without_account: u64,
+    /// Every role in `app_role`, including those without users
+    pub users_per_role: Vec<RoleCount>,
+    /// Filled in for roles with `employee.write`
+    pub hr: Option<HrActivity>,
+    /// Filled in for roles with `audit.read`
+    pub admin: Option<AdminActivity>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(FromQueryResult))]
+pub struct RoleCount {
+    pub role: String,
+    pub users: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HrActivity {
+    /// Newest employees first
+    pub recent_hires: Vec<DatedEmployee>,
+    /// Employees edited after they were created, latest edit first
+    pub recent_changes: Vec<DatedEmployee>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AdminActivity {
+    pub recent_logins: Vec<AccountActivity>,
+    /// Deactivated users, who cannot log in
+    pub locked_accounts: Vec<AccountActivity>,
+    pub locked_total: u64,
+}
+
+/// An employee with the time of the event it is listed for (RFC 3339)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DatedEmployee {
+    pub employee: Employee,
+    pub at: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountActivity {
+    pub user_id: i32,
+    pub email: String,
+    /// Last login (RFC 3339), if the user ever signed in
+    pub last_login_at: Option<String>,
+}
+
+#[cfg(feature = "server")]
+impl From<users::Model> for AccountActivity {
+    fn from(m: users::Model) -> Self {
+        Self {
+            user_id: m.id,
+            email: m.email,
+            last_login_at: m.last_login_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+//
+// -------------------- SERVER FUNCTION --------------------
+//
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("get_dashboard_server"))]
+pub async fn get_dashboard_server() -> Result<Dashboard, ServerFnError> {
+    let viewer = crate::server::session::require_permissions().await?;
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let failed = |e: DbErr| ServerFnError::new(format!("Query failed: {}", e));
+
+    let total_employees = employee::Entity::find().count(&db).await.map_err(failed)?;
+    let employees_without_account = employee::Entity::find()
+        .join(JoinType::LeftJoin, employee::Relation::User.def())
+        .filter(users::Column::Id.is_null())
+        .count(&db)
+        .await
+        .map_err(failed)?;
+    let users_per_role = app_role::Entity::find()
+        .select_only()
+        .column_as(app_role::Column::Name, "role")
+        .column_as(
+            Expr::col((users::Entity, users::Column::Id)).count(),
+            "users",
+        )
+        .join(JoinType::LeftJoin, app_role::Relation::Users.def())
+        .group_by(app_role::Column::Id)
+        .group_by(app_role::Column::Name)
+        .order_by_asc(app_role::Column::Id)
+        .into_model::<RoleCount>()
+        .all(&db)
+        .await
+        .map_err(failed)?;
+
+    let hr = if viewer.can(EMPLOYEE_WRITE) {
+        Some(hr_activity(&db).await.map_err(failed)?)
+    } else {
+        None
+    };
+    let admin = if viewer.can(AUDIT_READ) {
+        Some(admin_activity(&db).await.map_err(failed)?)
+    } else {
+        None
+    };
+
+    Ok(Dashboard {
+        total_employees,
+        employees_without_account,
+        users_per_role,
+        hr,
+        admin,
+    })
+}
+
+#[cfg(feature = "server")]
+async fn hr_activity<C: ConnectionTrait>(db: &C) -> Result<HrActivity, DbErr> {
+    let recent_hires = employee::Entity::find()
+        .filter(employee::Column::CreatedAt.is_not_null())
+        .order_by_desc(employee::Column::CreatedAt)
+        .limit(LIST_LIMIT)
+        .all(db)
+        .await?;
+    let recent_changes = employee::Entity::find()
+        .filter(Expr::col(employee::Column::UpdatedAt).gt(Expr::col(employee::Column::CreatedAt)))
+        .order_by_desc(employee::Column::UpdatedAt)
+        .limit(LIST_LIMIT)
+        .all(db)
+        .await?;
+
+    Ok(HrActivity {
+        recent_hires: dated(recent_hires, |m| m.created_at),
+        recent_changes: dated(recent_changes, |m| m.updated_at),
+    })
+}
+
+#[cfg(feature = "server")]
+fn dated(
+    models: Vec<employee::Model>,
+    at: fn(&employee::Model) -> Option<DateTimeWithTimeZone>,
+) -> Vec<DatedEmployee> {
+    models
+        .into_iter()
+        .filter_map(|m| {
+            let at = at(&m)?.to_rfc3339();
+            Some(DatedEmployee {
+                employee: m.into(),
+                at,
+            })
+        })
+        .collect()
+}
+
+#[cfg(feature = "server")]
+async fn admin_activity<C: ConnectionTrait>(db: &C) -> Result<AdminActivity, DbErr> {
+    let recent_logins = users::Entity::find()
+        .filter(users::Column::LastLoginAt.is_not_null())
+        .order_by_desc(users::Column::LastLoginAt)
+        .limit(LIST_LIMIT)
+        .all(db)
+        .await?;
+    let locked = users::Entity::find().filter(users::Column::IsActive.eq(false));
+    let locked_total = locked.clone().count(db).await?;
+    let locked_accounts = locked
+        .order_by_asc(users::Column::Email)
+        .limit(LIST_LIMIT)
+        .all(db)
+        .await?;
+
+    Ok(AdminActivity {
+        recent_logins: recent_logins.into_iter().map(Into::into).collect(),
+        locked_accounts: locked_accounts.into_iter().map(Into::into).collect(),
+        locked_total,
+    })
+}
@@ -1,5 +1,5 @@
 // src/db.rs
-// Unified DB API: Employees + UsersExtended (view)
+// Unified DB API: Employees + UsersExtended
 // Code & comments in English.
 
 use dioxus::prelude::*;
@@ -11,19 +11,35 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "server")]
 use sea_orm::*;
 
+#[cfg(feature = "server")]
+use sea_orm::prelude::DateTimeWithTimeZone;
+
 #[cfg(feature = "server")]
 use crate::server::db_connection::get_db;
 
+#[cfg(feature = "server")]
+use crate::server::auth::{EMPLOYEE_READ, EMPLOYEE_WRITE, USER_MANAGE, USER_READ};
+
+#[cfg(feature = "server")]
+use crate::server::session::require_permission;
+
 #[cfg(feature = "server")]
 pub use crate::entities::prelude::Employee as EmployeeEntity;
 
 #[cfg(feature = "server")]
 pub use crate::entities::employee;
 
+#[cfg(feature = "server")]
+pub use crate::entities::app_role;
+
+#[cfg(feature = "server")]
+use crate::entities::users;
+
 //
 // -------------------- EMPLOYEE DTO --------------------
 //
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct Employee {
     pub id: i32,
     pub first_name: Option<String>,
@@ -43,11 +59,34 @@ impl From<employee::Model> for Employee {
     }
 }
 
+//
+// -------------------- ROLE DTO --------------------
+//
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct Role {
+    pub id: i32,
+    pub name: String,
+}
+
+#[cfg(feature = "server")]
+impl From<app_role::Model> for Role {
+    fn from(m: app_role::Model) -> Self {
+        Self {
+            id: m.id,
+            name: m.name,
+        }
+    }
+}
+
 //
 // -------------------- EMPLOYEE CRUD --------------------
 //
 #[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("get_employees_server"))]
 pub async fn get_employees_server() -> Result<Vec<Employee>, ServerFnError> {
+    require_permission(EMPLOYEE_READ).await?;
+
     let db = get_db()
         .await
         .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
@@ -61,146 +100,444 @@ pub async fn get_employees_server() -> Result<Vec<Employee>, ServerFnError> {
 }
 
 #[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("get_employee_server"))]
+pub async fn get_employee_server(id: i32) -> Result<Option<Employee>, ServerFnError> {
+    require_permission(EMPLOYEE_READ).await?;
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let model = EmployeeEntity::find_by_id(id)
+        .one(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?;
+
+    Ok(model.map(Into::into))
+}
+
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("create_employee"))]
 pub async fn create_employee(
     first_name: String,
     last_name: String,
     email: String,
 ) -> Result<Employee, ServerFnError> {
+    require_permission(EMPLOYEE_WRITE).await?;
+
     let db = get_db()
         .await
         .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
 
-    let new = employee::ActiveModel {
-        id: NotSet,
-        first_name: Set(Some(first_name)),
-        last_name: Set(Some(last_name)),
-        email: Set(Some(email)),
-    };
-
-    let res = new
-        .insert(&db)
-        .await
-        .map_err(|e| ServerFnError::new(format!("Insert failed: {}", e)))?;
-
-    Ok(res.into())
+    insert_employee(&db, first_name, last_name, email).await
 }
 
 #[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("update_employee"))]
 pub async fn update_employee(
     id: i32,
     first_name: String,
     last_name: String,
     email: String,
 ) -> Result<Employee, ServerFnError> {
+    require_permission(EMPLOYEE_WRITE).await?;
+
     let db = get_db()
         .await
         .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
 
-    let existing = EmployeeEntity::find_by_id(id)
-        .one(&db)
-        .await
-        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?
-        .ok_or_else(|| ServerFnError::new("Employee not found"))?;
-
-    let mut model: employee::ActiveModel = existing.into();
-    model.first_name = Set(Some(first_name));
-    model.last_name = Set(Some(last_name));
-    model.email = Set(Some(email));
-
-    let updated = model
-        .update(&db)
-        .await
-        .map_err(|e| ServerFnError::new(format!("Update failed: {}", e)))?;
-
-    Ok(updated.into())
+    modify_employee(&db, id, first_name, last_name, email).await
 }
 
 #[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("delete_employee"))]
 pub async fn delete_employee(id: i32) -> Result<(), ServerFnError> {
+    require_permission(EMPLOYEE_WRITE).await?;
+
     let db = get_db()
         .await
         .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
 
-    EmployeeEntity::delete_by_id(id)
-        .exec(&db)
+    remove_employee(&db, id).await
+}
+
+//
+// -------------------- EMPLOYEE WRITES (SHARED) --------------------
+//
+// The server functions above, the GraphQL mutations and `rfc-admin` go
+// through these helpers. Input is checked against `validation::employee`,
+// the rules the form uses; the email rules live in `server::directory`,
+// which the REST and SCIM APIs call directly. Permission checks are up to
+// each entry point.
+//
+#[cfg(feature = "server")]
+pub async fn insert_employee<C: ConnectionTrait>(
+    db: &C,
+    first_name: String,
+    last_name: String,
+    email: String,
+) -> Result<Employee, ServerFnError> {
+    crate::validation::employee(&first_name, &last_name, &email).into_result()?;
+
+    crate::server::directory::insert_employee(db, first_name, last_name, email)
+        .await
+        .map(Into::into)
+        .map_err(|e| crate::i18n::server_error(&e))
+}
+
+/// Update an employee and its linked user's email; see `server::directory`
+#[cfg(feature = "server")]
+pub async fn modify_employee<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    id: i32,
+    first_name: String,
+    last_name: String,
+    email: String,
+) -> Result<Employee, ServerFnError> {
+    crate::validation::employee(&first_name, &last_name, &email).into_result()?;
+
+    crate::server::directory::update_employee(db, id, first_name, last_name, email)
         .await
-        .map_err(|e| ServerFnError::new(format!("Delete failed: {}", e)))?;
+        .map(Into::into)
+        .map_err(|e| crate::i18n::server_error(&e))
+}
 
-    Ok(())
+/// Delete an employee; with `auth.deactivate_with_employee` its account is
+/// deactivated too
+#[cfg(feature = "server")]
+pub async fn remove_employee<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    id: i32,
+) -> Result<(), ServerFnError> {
+    let deactivate = crate::server::config::get().auth.deactivate_with_employee;
+    crate::server::directory::delete_employee(db, id, deactivate)
+        .await
+        .map(|_| ())
+        .map_err(|e| crate::i18n::server_error(&e))
 }
 
 //
-// -------------------- USERS_EXTENDED VIEW (FROM DATABASE VIEW) --------------------
+// -------------------- USERS_EXTENDED --------------------
 //
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct UserExtended {
     pub user_id: i32,
     pub email: String,
     pub role_name: String,
     pub employee_id: Option<i32>,
     pub employee_name: Option<String>,
+    /// Inactive accounts are kept for history but cannot sign in
+    pub is_active: bool,
+    /// RFC 3339; the last deactivation, also kept after a reactivation
+    pub deactivated_at: Option<String>,
 }
 
 #[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("get_users_extended_server"))]
 pub async fn get_users_extended_server() -> Result<Vec<UserExtended>, ServerFnError> {
-    // We use sea-orm's FromQueryResult + Statement::from_sql_and_values to read the view.
-    // This avoids using QueryResult/try_get and is fully compatible with Dioxus server macro.
-
-    #[cfg(feature = "server")]
-    {
-        use sea_orm::FromQueryResult;
-
-        #[derive(Debug, FromQueryResult)]
-        struct Row {
-            user_id: i32,
-            email: String,
-            role_name: String,
-            employee_id: Option<i32>,
-            employee_name: Option<String>,
-        }
+    require_permission(USER_READ).await?;
 
-        let db = get_db()
-            .await
-            .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
 
-        let stmt = Statement::from_sql_and_values(
-            sea_orm::DatabaseBackend::Postgres,
-            r#"
-            SELECT
-                user_id,
-                email,
-                role_name,
-                employee_id,
-                employee_name
-            FROM users_extended
-            ORDER BY user_id
-            "#,
-            [], // no params
-        );
+    let rows = users_extended_query()
+        .order_by_asc(users::Column::Id)
+        .into_model::<UserExtendedRow>()
+        .all(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?;
 
-        let rows: Vec<Row> = Row::find_by_statement(stmt)
-            .all(&db)
-            .await
-            .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?;
-
-        let mapped = rows
-            .into_iter()
-            .map(|r| UserExtended {
-                user_id: r.user_id,
-                email: r.email,
-                role_name: r.role_name,
-                employee_id: r.employee_id,
-                employee_name: r.employee_name,
-            })
-            .collect();
-
-        Ok(mapped)
+    Ok(rows.into_iter().map(Into::into).collect())
+}
+
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("get_user_extended_server"))]
+pub async fn get_user_extended_server(id: i32) -> Result<Option<UserExtended>, ServerFnError> {
+    require_permission(USER_READ).await?;
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let row = users_extended_query()
+        .filter(users::Column::Id.eq(id))
+        .into_model::<UserExtendedRow>()
+        .one(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?;
+
+    Ok(row.map(Into::into))
+}
+
+//
+// -------------------- USER WRITES --------------------
+//
+// `users.employee_id` links an account to at most one employee and back.
+// The rules (one account per employee, shared email) live in
+// `server::directory`; these functions only check the role and input.
+//
+
+/// A new account with its generated password, shown once to whoever
+/// created it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NewUser {
+    pub user: UserExtended,
+    pub password: String,
+}
+
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("create_user"))]
+pub async fn create_user(
+    email: String,
+    role: String,
+    employee_id: Option<i32>,
+) -> Result<NewUser, ServerFnError> {
+    let viewer = require_permission(USER_MANAGE).await?;
+    crate::validation::user(&email).into_result()?;
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let password = crate::server::auth::random_password();
+    let hash = crate::server::auth::hash_password(password.clone())
+        .await
+        .map_err(|e| ServerFnError::new(format!("Hashing failed: {}", e)))?;
+    let created = crate::server::directory::create_user(
+        &db,
+        &viewer.permissions,
+        email,
+        &role,
+        employee_id,
+        hash,
+    )
+    .await
+    .map_err(|e| crate::i18n::server_error(&e))?;
+
+    Ok(NewUser {
+        user: load_user_extended(&db, created.id).await?,
+        password,
+    })
+}
+
+/// Change a user's email, role and linked employee; `employee_id: None`
+/// unlinks the account
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("update_user"))]
+pub async fn update_user(
+    user_id: i32,
+    email: String,
+    role: String,
+    employee_id: Option<i32>,
+) -> Result<UserExtended, ServerFnError> {
+    let viewer = require_permission(USER_MANAGE).await?;
+    crate::validation::user(&email).into_result()?;
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    crate::server::directory::update_user(
+        &db,
+        &viewer.permissions,
+        user_id,
+        email,
+        &role,
+        employee_id,
+    )
+    .await
+    .map_err(|e| crate::i18n::server_error(&e))?;
+
+    load_user_extended(&db, user_id).await
+}
+
+/// Block or restore a user's login. Deactivating also ends the user's
+/// sessions; nobody can deactivate their own account.
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("set_user_active"))]
+pub async fn set_user_active(user_id: i32, active: bool) -> Result<UserExtended, ServerFnError> {
+    let viewer = require_permission(USER_MANAGE).await?;
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    crate::server::directory::set_user_active(&db, viewer.id, user_id, active)
+        .await
+        .map_err(|e| crate::i18n::server_error(&e))?;
+
+    load_user_extended(&db, user_id).await
+}
+
+/// Employees without an account whose name or email contains `search`, for
+/// the user editor's picker
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("get_unlinked_employees_server"))]
+pub async fn get_unlinked_employees_server(search: String) -> Result<Vec<Employee>, ServerFnError> {
+    require_permission(USER_MANAGE).await?;
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let mut query = EmployeeEntity::find()
+        .join(JoinType::LeftJoin, employee::Relation::User.def())
+        .filter(users::Column::Id.is_null());
+    let search = search.trim().to_lowercase();
+    if !search.is_empty() {
+        // Case-insensitive on every backend
+        let matches = |column: employee::Column| {
+            sea_query::Expr::expr(sea_query::Func::lower(sea_query::Expr::col((
+                employee::Entity,
+                column,
+            ))))
+            .like(crate::server::search::contains(&search))
+        };
+        query = query.filter(
+            Condition::any()
+                .add(matches(employee::Column::FirstName))
+                .add(matches(employee::Column::LastName))
+                .add(matches(employee::Column::Email)),
+        );
     }
 
-    // If compiled without server feature (shouldn't happen in server context),
-    // return an empty vector.
-    #[cfg(not(feature = "server"))]
-    {
-        Ok(Vec::new())
+    let models = query
+        .order_by_asc(employee::Column::LastName)
+        .order_by_asc(employee::Column::FirstName)
+        .limit(PICKER_LIMIT)
+        .all(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?;
+
+    Ok(models.into_iter().map(Into::into).collect())
+}
+
+/// Most employees the picker offers at once; typing narrows the list
+#[cfg(feature = "server")]
+const PICKER_LIMIT: u64 = 20;
+
+#[cfg(feature = "server")]
+async fn load_user_extended<C: ConnectionTrait>(
+    db: &C,
+    user_id: i32,
+) -> Result<UserExtended, ServerFnError> {
+    users_extended_query()
+        .filter(users::Column::Id.eq(user_id))
+        .into_model::<UserExtendedRow>()
+        .one(db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?
+        .map(Into::into)
+        .ok_or_else(|| {
+            crate::i18n::server_error(&crate::server::directory::DirectoryError::UserNotFound(
+                user_id,
+            ))
+        })
+}
+
+// The same columns as the Postgres `users_extended` view, built with the
+// query builder so it runs on every backend.
+#[cfg(feature = "server")]
+#[derive(Debug, FromQueryResult)]
+struct UserExtendedRow {
+    user_id: i32,
+    email: String,
+    role_name: String,
+    employee_id: Option<i32>,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    is_active: bool,
+    deactivated_at: Option<DateTimeWithTimeZone>,
+}
+
+#[cfg(feature = "server")]
+impl From<UserExtendedRow> for UserExtended {
+    fn from(r: UserExtendedRow) -> Self {
+        // Like `first_name || ' ' || last_name` in the view: NULL if either is
+        let employee_name = match (r.first_name, r.last_name) {
+            (Some(first), Some(last)) => Some(format!("{} {}", first, last)),
+            _ => None,
+        };
+        Self {
+            user_id: r.user_id,
+            email: r.email,
+            role_name: r.role_name,
+            employee_id: r.employee_id,
+            employee_name,
+            is_active: r.is_active,
+            deactivated_at: r.deactivated_at.map(|t| t.to_rfc3339()),
+        }
     }
 }
+
+#[cfg(feature = "server")]
+fn users_extended_query() -> Select<users::Entity> {
+    users::Entity::find()
+        .select_only()
+        .column_as(users::Column::Id, "user_id")
+        .column(users::Column::Email)
+        .column_as(app_role::Column::Name, "role_name")
+        .column(users::Column::EmployeeId)
+        .column(employee::Column::FirstName)
+        .column(employee::Column::LastName)
+        .column(users::Column::IsActive)
+        .column(users::Column::DeactivatedAt)
+        .join(JoinType::InnerJoin, users::Relation::AppRole.def())
+        .join(JoinType::LeftJoin, users::Relation::Employee.def())
+}
+
+//
+// -------------------- EMPLOYEE PROFILE --------------------
+//
+// The detail page at `/employees/:id`. The schema has no manager, reports
+// or audit tables yet, so the profile is the record plus its account.
+//
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EmployeeProfile {
+    pub employee: Employee,
+    /// The linked user account; always `None` for roles without
+    /// `user.read`, which may not see accounts
+    pub account: Option<UserExtended>,
+}
+
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("get_employee_profile_server"))]
+pub async fn get_employee_profile_server(
+    id: i32,
+) -> Result<Option<EmployeeProfile>, ServerFnError> {
+    let viewer = require_permission(EMPLOYEE_READ).await?;
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let Some(model) = EmployeeEntity::find_by_id(id)
+        .one(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?
+    else {
+        return Ok(None);
+    };
+
+    let account = if viewer.can(USER_READ) {
+        users_extended_query()
+            .filter(users::Column::EmployeeId.eq(id))
+            .into_model::<UserExtendedRow>()
+            .one(&db)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?
+            .map(Into::into)
+    } else {
+        None
+    };
+
+    Ok(Some(EmployeeProfile {
+        employee: model.into(),
+        account,
+    }))
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests;
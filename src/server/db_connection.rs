@@ -1,15 +1,65 @@
 //! Database connection setup for SeaORM
 #[cfg(feature = "server")]
-use sea_orm::{Database, DatabaseConnection, DbErr};
+use sea_orm::{ConnectOptions, Database, DatabaseConnection, DbErr};
 
-/// Get database connection from environment variable
+#[cfg(feature = "sqlite")]
+use sea_orm::{ConnectionTrait, DatabaseBackend};
+
+/// Process-wide connection pool, created on first use
+#[cfg(all(feature = "server", not(test)))]
+static POOL: tokio::sync::OnceCell<DatabaseConnection> = tokio::sync::OnceCell::const_new();
+
+#[cfg(all(feature = "server", test))]
+tokio::task_local! {
+    /// Per-test database installed by `server::testing`
+    pub static TEST_DB: DatabaseConnection;
+}
+
+/// Get database connection configured by `database.*`
+///
+/// Returns a handle to the shared pool (cheap to clone). Test builds
+/// connect per call instead, because every `#[tokio::test]` has its own
+/// runtime and pooled connections cannot outlive the runtime that made them.
+/// Inside `server::testing` they get that test's own database.
 #[allow(dead_code)]
 #[cfg(feature = "server")]
 pub async fn get_db() -> Result<DatabaseConnection, DbErr> {
-    let database_url = std::env::var("DATABASE_URL")
-        .expect("DATABASE_URL must be set in .env file");
-    
-    Database::connect(&database_url).await
+    #[cfg(not(test))]
+    return POOL.get_or_try_init(connect).await.cloned();
+
+    #[cfg(test)]
+    return match TEST_DB.try_with(DatabaseConnection::clone) {
+        Ok(db) => Ok(db),
+        Err(_) => connect().await,
+    };
+}
+
+#[cfg(feature = "server")]
+async fn connect() -> Result<DatabaseConnection, DbErr> {
+    let config = &crate::server::config::get().database;
+
+    let mut options = ConnectOptions::new(config.url.clone());
+    options
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .connect_timeout(std::time::Duration::from_secs(config.connect_timeout_secs));
+
+    // Every connection to `sqlite::memory:` opens its own empty database
+    #[cfg(feature = "sqlite")]
+    if config.url.starts_with("sqlite:") && config.url.contains(":memory:") {
+        options.max_connections(1).min_connections(1);
+    }
+
+    let mut db = Database::connect(options).await?;
+    db.set_metric_callback(crate::server::telemetry::record_query);
+
+    // SQLite has no SQL migrations; the schema comes from the entities
+    #[cfg(feature = "sqlite")]
+    if db.get_database_backend() == DatabaseBackend::Sqlite {
+        crate::server::migrate::create_sqlite_schema(&db).await?;
+    }
+
+    Ok(db)
 }
 
 /// Get database connection or panic (for use in main/startup)
@@ -0,0 +1,809 @@
+//! Employee and user writes that keep their shared email consistent
+//!
+//! An employee and the user account linked to it (`users.employee_id`) have
+//! the same email, and no two users may share one. These rules used to live
+//! in plpgsql triggers (`migrations/triggers.sql`, dropped by 007). Every
+//! write of either email or of the link goes through here, from the server
+//! functions, REST, SCIM and `rfc-admin` alike, so they hold on every backend
+//! and a collision comes back as `DirectoryError::EmailConflict`.
+
+use std::fmt;
+
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+
+use crate::entities::{app_role, employee, role_permission, users};
+use crate::i18n::{Locale, Translate};
+
+#[derive(Debug)]
+pub enum DirectoryError {
+    EmployeeNotFound(i32),
+    UserNotFound(i32),
+    RoleNotFound(String),
+    /// Another user or employee already has this email
+    EmailConflict(String),
+    /// The employee already has a user account (`users.employee_id` is
+    /// unique); carries the employee id and that account's email
+    EmployeeAlreadyLinked(i32, String),
+    /// The employee has no user account to change
+    NoAccount(i32),
+    /// Deactivating one's own account would end the session doing it
+    DeactivateSelf,
+    /// The role has permissions the granting user lacks
+    RoleNotGrantable(String),
+    Db(DbErr),
+}
+
+impl Translate for DirectoryError {
+    fn key(&self) -> &'static str {
+        match self {
+            Self::EmployeeNotFound(_) => "error.employee_not_found",
+            Self::UserNotFound(_) => "error.user_not_found",
+            Self::RoleNotFound(_) => "error.role_not_found",
+            Self::EmailConflict(_) => "error.email_conflict",
+            Self::EmployeeAlreadyLinked(..) => "error.employee_already_linked",
+            Self::NoAccount(_) => "error.employee_has_no_account",
+            Self::DeactivateSelf => "error.deactivate_self",
+            Self::RoleNotGrantable(_) => "error.role_not_grantable",
+            Self::Db(_) => "error.query_failed",
+        }
+    }
+
+    fn args(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::EmployeeNotFound(id) | Self::UserNotFound(id) | Self::NoAccount(id) => {
+                vec![("id", id.to_string())]
+            }
+            Self::RoleNotFound(role) | Self::RoleNotGrantable(role) => {
+                vec![("role", role.clone())]
+            }
+            Self::EmailConflict(email) => vec![("email", email.clone())],
+            Self::EmployeeAlreadyLinked(id, email) => {
+                vec![("id", id.to_string()), ("email", email.clone())]
+            }
+            Self::DeactivateSelf => Vec::new(),
+            Self::Db(e) => vec![("error", e.to_string())],
+        }
+    }
+}
+
+/// English, for the REST and SCIM APIs and the logs
+impl fmt::Display for DirectoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message(Locale::En))
+    }
+}
+
+impl std::error::Error for DirectoryError {}
+
+impl From<DbErr> for DirectoryError {
+    fn from(e: DbErr) -> Self {
+        Self::Db(e)
+    }
+}
+
+//
+// -------------------- RULES --------------------
+//
+
+/// Fail unless `email` is free for the given user / employee pair: no other
+/// user and no other employee may have it. Either side may be absent.
+pub async fn ensure_email_available<C: ConnectionTrait>(
+    db: &C,
+    email: &str,
+    user_id: Option<i32>,
+    employee_id: Option<i32>,
+) -> Result<(), DirectoryError> {
+    let mut users_with_email = users::Entity::find().filter(users::Column::Email.eq(email));
+    if let Some(id) = user_id {
+        users_with_email = users_with_email.filter(users::Column::Id.ne(id));
+    }
+    // The linked user is about to receive this email anyway
+    if let Some(id) = employee_id {
+        users_with_email = users_with_email.filter(
+            Condition::any()
+                .add(users::Column::EmployeeId.is_null())
+                .add(users::Column::EmployeeId.ne(id)),
+        );
+    }
+
+    let mut employees_with_email =
+        employee::Entity::find().filter(employee::Column::Email.eq(email));
+    if let Some(id) = employee_id {
+        employees_with_email = employees_with_email.filter(employee::Column::Id.ne(id));
+    }
+
+    if users_with_email.one(db).await?.is_some() || employees_with_email.one(db).await?.is_some() {
+        return Err(DirectoryError::EmailConflict(email.to_string()));
+    }
+    Ok(())
+}
+
+/// Fail unless `employee_id` exists and no user other than `user_id` is
+/// linked to it
+pub async fn ensure_employee_linkable<C: ConnectionTrait>(
+    db: &C,
+    employee_id: i32,
+    user_id: Option<i32>,
+) -> Result<(), DirectoryError> {
+    employee::Entity::find_by_id(employee_id)
+        .one(db)
+        .await?
+        .ok_or(DirectoryError::EmployeeNotFound(employee_id))?;
+
+    let mut linked = users::Entity::find().filter(users::Column::EmployeeId.eq(employee_id));
+    if let Some(id) = user_id {
+        linked = linked.filter(users::Column::Id.ne(id));
+    }
+    match linked.one(db).await? {
+        Some(other) => Err(DirectoryError::EmployeeAlreadyLinked(
+            employee_id,
+            other.email,
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Turn a write that broke `users.employee_id`'s uniqueness into
+/// `EmployeeAlreadyLinked`. `ensure_employee_linkable` reads before the
+/// write, so two requests linking the same employee at once can both pass
+/// it; the constraint (migration 013) then fails the later one. `txn` is
+/// rolled back first so the account that won can be looked up on `db`.
+async fn link_conflict<C: ConnectionTrait>(
+    db: &C,
+    txn: DatabaseTransaction,
+    employee_id: Option<i32>,
+    err: DbErr,
+) -> DirectoryError {
+    let employee_id = match employee_id {
+        Some(id) if is_link_violation(&err) => id,
+        _ => return err.into(),
+    };
+    if let Err(e) = txn.rollback().await {
+        return e.into();
+    }
+    let linked = users::Entity::find()
+        .filter(users::Column::EmployeeId.eq(employee_id))
+        .one(db)
+        .await;
+    match linked {
+        Ok(Some(other)) => DirectoryError::EmployeeAlreadyLinked(employee_id, other.email),
+        Ok(None) => err.into(),
+        Err(e) => e.into(),
+    }
+}
+
+/// Whether `err` is a violation of the unique `users.employee_id`, named by
+/// its constraint on PostgreSQL and by its column on SQLite
+fn is_link_violation(err: &DbErr) -> bool {
+    match err.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(message)) => {
+            message.contains("unique_users_employee_id") || message.contains("users.employee_id")
+        }
+        _ => false,
+    }
+}
+
+/// Give the user linked to `employee_id`, if any, this email
+async fn sync_to_user<C: ConnectionTrait>(
+    db: &C,
+    employee_id: i32,
+    email: &str,
+) -> Result<(), DbErr> {
+    users::Entity::update_many()
+        .col_expr(users::Column::Email, Expr::value(email))
+        .filter(users::Column::EmployeeId.eq(employee_id))
+        .filter(users::Column::Email.ne(email))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Give the employee with `employee_id` this email
+async fn sync_to_employee<C: ConnectionTrait>(
+    db: &C,
+    employee_id: i32,
+    email: &str,
+) -> Result<(), DbErr> {
+    employee::Entity::update_many()
+        .col_expr(employee::Column::Email, Expr::value(email))
+        .col_expr(employee::Column::UpdatedAt, Expr::value(now()))
+        .filter(employee::Column::Id.eq(employee_id))
+        .filter(
+            Condition::any()
+                .add(employee::Column::Email.is_null())
+                .add(employee::Column::Email.ne(email)),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+//
+// -------------------- WRITES --------------------
+//
+
+/// Add an employee whose email no user or employee has yet
+pub async fn insert_employee<C: ConnectionTrait>(
+    db: &C,
+    first_name: String,
+    last_name: String,
+    email: String,
+) -> Result<employee::Model, DirectoryError> {
+    ensure_email_available(db, &email, None, None).await?;
+
+    let model = employee::ActiveModel {
+        id: NotSet,
+        first_name: Set(Some(first_name)),
+        last_name: Set(Some(last_name)),
+        email: Set(Some(email)),
+        ..Default::default()
+    };
+    Ok(model.insert(db).await?)
+}
+
+/// Update an employee and, in the same transaction, its linked user's email
+pub async fn update_employee<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    id: i32,
+    first_name: String,
+    last_name: String,
+    email: String,
+) -> Result<employee::Model, DirectoryError> {
+    let txn = db.begin().await?;
+
+    let existing = employee::Entity::find_by_id(id)
+        .one(&txn)
+        .await?
+        .ok_or(DirectoryError::EmployeeNotFound(id))?;
+    let linked_user = users::Entity::find()
+        .filter(users::Column::EmployeeId.eq(id))
+        .one(&txn)
+        .await?;
+    ensure_email_available(&txn, &email, linked_user.map(|u| u.id), Some(id)).await?;
+
+    let mut model: employee::ActiveModel = existing.into();
+    model.first_name = Set(Some(first_name));
+    model.last_name = Set(Some(last_name));
+    model.email = Set(Some(email.clone()));
+    touch(&mut model);
+    let updated = model.update(&txn).await?;
+    sync_to_user(&txn, id, &email).await?;
+
+    txn.commit().await?;
+    Ok(updated)
+}
+
+/// Change a user's email and, in the same transaction, its linked employee's
+pub async fn update_user_email<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    user_id: i32,
+    email: String,
+) -> Result<users::Model, DirectoryError> {
+    let txn = db.begin().await?;
+
+    let user = users::Entity::find_by_id(user_id)
+        .one(&txn)
+        .await?
+        .ok_or(DirectoryError::UserNotFound(user_id))?;
+    ensure_email_available(&txn, &email, Some(user_id), user.employee_id).await?;
+
+    let mut model: users::ActiveModel = user.into();
+    model.email = Set(email);
+    let updated = model.update(&txn).await?;
+    sync_user(&txn, &updated).await?;
+
+    txn.commit().await?;
+    Ok(updated)
+}
+
+/// Create a user, optionally linked to an employee. The employee takes the
+/// user's email, as on every later change.
+pub async fn create_user<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    granter: &[String],
+    email: String,
+    role: &str,
+    employee_id: Option<i32>,
+    password_hash: String,
+) -> Result<users::Model, DirectoryError> {
+    let txn = db.begin().await?;
+
+    let role_id = grantable_role_id(&txn, granter, role).await?;
+    if let Some(employee_id) = employee_id {
+        ensure_employee_linkable(&txn, employee_id, None).await?;
+    }
+    ensure_email_available(&txn, &email, None, employee_id).await?;
+
+    let model = users::ActiveModel {
+        email: Set(email),
+        password_hash: Set(password_hash),
+        role_id: Set(role_id),
+        employee_id: Set(employee_id),
+        is_active: Set(true),
+        ..Default::default()
+    };
+    let created = match model.insert(&txn).await {
+        Ok(created) => created,
+        Err(e) => return Err(link_conflict(db, txn, employee_id, e).await),
+    };
+    sync_user(&txn, &created).await?;
+
+    txn.commit().await?;
+    Ok(created)
+}
+
+/// Link a user to `employee_id`, which takes the user's email
+pub async fn link_employee<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    user_id: i32,
+    employee_id: i32,
+) -> Result<users::Model, DirectoryError> {
+    let txn = db.begin().await?;
+
+    let user = users::Entity::find_by_id(user_id)
+        .one(&txn)
+        .await?
+        .ok_or(DirectoryError::UserNotFound(user_id))?;
+    ensure_employee_linkable(&txn, employee_id, Some(user_id)).await?;
+    ensure_email_available(&txn, &user.email, Some(user_id), Some(employee_id)).await?;
+
+    let mut model: users::ActiveModel = user.into();
+    model.employee_id = Set(Some(employee_id));
+    let linked = match model.update(&txn).await {
+        Ok(linked) => linked,
+        Err(e) => return Err(link_conflict(db, txn, Some(employee_id), e).await),
+    };
+    sync_user(&txn, &linked).await?;
+
+    txn.commit().await?;
+    Ok(linked)
+}
+
+/// Set a user's password hash
+pub async fn set_password<C: ConnectionTrait>(
+    db: &C,
+    user_id: i32,
+    password_hash: String,
+) -> Result<users::Model, DirectoryError> {
+    let user = users::Entity::find_by_id(user_id)
+        .one(db)
+        .await?
+        .ok_or(DirectoryError::UserNotFound(user_id))?;
+
+    let mut model: users::ActiveModel = user.into();
+    model.password_hash = Set(password_hash);
+    Ok(model.update(db).await?)
+}
+
+/// Change a user's email, role and linked employee together. `None` unlinks;
+/// a newly linked employee takes the user's email. A new role must be
+/// grantable by `granter`; keeping the current one needs nothing.
+pub async fn update_user<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    granter: &[String],
+    user_id: i32,
+    email: String,
+    role: &str,
+    employee_id: Option<i32>,
+) -> Result<users::Model, DirectoryError> {
+    let txn = db.begin().await?;
+
+    let user = users::Entity::find_by_id(user_id)
+        .one(&txn)
+        .await?
+        .ok_or(DirectoryError::UserNotFound(user_id))?;
+    let mut role_id = role_id(&txn, role).await?;
+    if role_id != user.role_id {
+        role_id = grantable_role_id(&txn, granter, role).await?;
+    }
+    if let Some(employee_id) = employee_id {
+        ensure_employee_linkable(&txn, employee_id, Some(user_id)).await?;
+    }
+    // An unlinked account may keep the email it shared with its employee
+    let shares_email_with = employee_id.or(user.employee_id);
+    ensure_email_available(&txn, &email, Some(user_id), shares_email_with).await?;
+
+    let mut model: users::ActiveModel = user.into();
+    model.email = Set(email);
+    model.role_id = Set(role_id);
+    model.employee_id = Set(employee_id);
+    let updated = match model.update(&txn).await {
+        Ok(updated) => updated,
+        Err(e) => return Err(link_conflict(db, txn, employee_id, e).await),
+    };
+    sync_user(&txn, &updated).await?;
+
+    txn.commit().await?;
+    Ok(updated)
+}
+
+/// Activate or deactivate a user. `viewer_id` is the account asking, which
+/// may not deactivate itself.
+pub async fn set_user_active<C: ConnectionTrait>(
+    db: &C,
+    viewer_id: i32,
+    user_id: i32,
+    active: bool,
+) -> Result<users::Model, DirectoryError> {
+    if !active && user_id == viewer_id {
+        return Err(DirectoryError::DeactivateSelf);
+    }
+    let user = users::Entity::find_by_id(user_id)
+        .one(db)
+        .await?
+        .ok_or(DirectoryError::UserNotFound(user_id))?;
+
+    let mut model: users::ActiveModel = user.into();
+    set_active(&mut model, active);
+    Ok(model.update(db).await?)
+}
+
+/// Delete an employee. With `deactivate_account`, the account linked to it
+/// is deactivated in the same transaction; otherwise it is only unlinked.
+/// Nothing changes if the employee does not exist. Returns whether an
+/// account was linked.
+pub async fn delete_employee<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    id: i32,
+    deactivate_account: bool,
+) -> Result<bool, DirectoryError> {
+    let txn = db.begin().await?;
+
+    let linked = users::Entity::find()
+        .filter(users::Column::EmployeeId.eq(id))
+        .one(&txn)
+        .await?;
+    let had_account = linked.is_some();
+    if let (Some(user), true) = (linked, deactivate_account) {
+        let mut model: users::ActiveModel = user.into();
+        set_active(&mut model, false);
+        model.update(&txn).await?;
+    }
+    let deleted = employee::Entity::delete_by_id(id).exec(&txn).await?;
+    if deleted.rows_affected == 0 {
+        return Err(DirectoryError::EmployeeNotFound(id));
+    }
+
+    txn.commit().await?;
+    Ok(had_account)
+}
+
+/// Give the account linked to `employee_id` the role `role_id`
+pub async fn set_account_role<C: ConnectionTrait>(
+    db: &C,
+    employee_id: i32,
+    role_id: i32,
+) -> Result<users::Model, DirectoryError> {
+    employee::Entity::find_by_id(employee_id)
+        .one(db)
+        .await?
+        .ok_or(DirectoryError::EmployeeNotFound(employee_id))?;
+    let user = users::Entity::find()
+        .filter(users::Column::EmployeeId.eq(employee_id))
+        .one(db)
+        .await?
+        .ok_or(DirectoryError::NoAccount(employee_id))?;
+
+    let mut model: users::ActiveModel = user.into();
+    model.role_id = Set(role_id);
+    Ok(model.update(db).await?)
+}
+
+/// Stamp `updated_at` on an employee about to be saved. PostgreSQL's
+/// trigger from migration 010 does the same, but SQLite has none.
+pub fn touch(model: &mut employee::ActiveModel) {
+    model.updated_at = Set(Some(now()));
+}
+
+fn now() -> DateTimeWithTimeZone {
+    chrono::Utc::now().fixed_offset()
+}
+
+/// Set `is_active`. Deactivating an active account also stamps
+/// `deactivated_at`, which revokes the sessions issued before it.
+pub fn set_active(model: &mut users::ActiveModel, active: bool) {
+    let was_active = !matches!(
+        model.is_active,
+        ActiveValue::Set(false) | ActiveValue::Unchanged(false)
+    );
+    if !active && was_active {
+        model.deactivated_at = Set(Some(now()));
+    }
+    model.is_active = Set(active);
+}
+
+/// The id of the role called `name`
+pub async fn role_id<C: ConnectionTrait>(db: &C, name: &str) -> Result<i32, DirectoryError> {
+    app_role::Entity::find()
+        .filter(app_role::Column::Name.eq(name))
+        .one(db)
+        .await?
+        .map(|role| role.id)
+        .ok_or_else(|| DirectoryError::RoleNotFound(name.to_string()))
+}
+
+/// The id of the role called `name`, if `granter` (the permissions of the
+/// user handing it out) holds every permission the role has. Without this,
+/// `user.manage` alone could hand out admin rights, even to oneself.
+pub async fn grantable_role_id<C: ConnectionTrait>(
+    db: &C,
+    granter: &[String],
+    name: &str,
+) -> Result<i32, DirectoryError> {
+    let id = role_id(db, name).await?;
+    let grants = role_permission::Entity::find()
+        .filter(role_permission::Column::RoleId.eq(id))
+        .all(db)
+        .await?;
+    if grants.iter().all(|g| granter.contains(&g.permission)) {
+        Ok(id)
+    } else {
+        Err(DirectoryError::RoleNotGrantable(name.to_string()))
+    }
+}
+
+/// Every permission, as the granter for callers with full rights such as
+/// `rfc-admin`
+pub fn full_rights() -> Vec<String> {
+    crate::server::auth::PERMISSIONS
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Copy a user's current email to its linked employee. For callers that
+/// change other user fields in their own transaction and already ran
+/// `ensure_email_available`.
+pub async fn sync_user<C: ConnectionTrait>(db: &C, user: &users::Model) -> Result<(), DbErr> {
+    match user.employee_id {
+        Some(employee_id) => sync_to_employee(db, employee_id, &user.email).await,
+        None => Ok(()),
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    async fn sqlite() -> DatabaseConnection {
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1);
+        let db = Database::connect(options).await.unwrap();
+        crate::server::migrate::create_sqlite_schema(&db)
+            .await
+            .unwrap();
+        db
+    }
+
+    async fn add_employee(db: &DatabaseConnection, email: &str) -> employee::Model {
+        employee::ActiveModel {
+            first_name: Set(Some("Ada".into())),
+            last_name: Set(Some("Lovelace".into())),
+            email: Set(Some(email.into())),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap()
+    }
+
+    async fn add_user(
+        db: &DatabaseConnection,
+        email: &str,
+        employee_id: Option<i32>,
+    ) -> users::Model {
+        users::ActiveModel {
+            email: Set(email.into()),
+            password_hash: Set("x".into()),
+            role_id: Set(5),
+            employee_id: Set(employee_id),
+            is_active: Set(true),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap()
+    }
+
+    async fn email_of_user(db: &DatabaseConnection, id: i32) -> String {
+        users::Entity::find_by_id(id)
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap()
+            .email
+    }
+
+    async fn email_of_employee(db: &DatabaseConnection, id: i32) -> Option<String> {
+        employee::Entity::find_by_id(id)
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap()
+            .email
+    }
+
+    #[tokio::test]
+    async fn employee_email_change_reaches_the_linked_user() {
+        let db = sqlite().await;
+        let emp = add_employee(&db, "ada@example.com").await;
+        let user = add_user(&db, "ada@example.com", Some(emp.id)).await;
+
+        let updated = update_employee(
+            &db,
+            emp.id,
+            "Ada".into(),
+            "King".into(),
+            "ada@new.example".into(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(updated.last_name.as_deref(), Some("King"));
+        assert_eq!(email_of_user(&db, user.id).await, "ada@new.example");
+    }
+
+    #[tokio::test]
+    async fn user_email_change_reaches_the_linked_employee() {
+        let db = sqlite().await;
+        let emp = add_employee(&db, "ada@example.com").await;
+        let user = add_user(&db, "ada@example.com", Some(emp.id)).await;
+
+        update_user_email(&db, user.id, "ada@new.example".into())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            email_of_employee(&db, emp.id).await.as_deref(),
+            Some("ada@new.example")
+        );
+    }
+
+    #[tokio::test]
+    async fn unlinked_records_change_alone() {
+        let db = sqlite().await;
+        let emp = add_employee(&db, "ada@example.com").await;
+        let other = add_employee(&db, "grace@example.com").await;
+        let user = add_user(&db, "admin@example.com", None).await;
+
+        update_user_email(&db, user.id, "root@example.com".into())
+            .await
+            .unwrap();
+        update_employee(
+            &db,
+            emp.id,
+            "Ada".into(),
+            "Lovelace".into(),
+            "ada@new.example".into(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(email_of_user(&db, user.id).await, "root@example.com");
+        assert_eq!(
+            email_of_employee(&db, other.id).await.as_deref(),
+            Some("grace@example.com")
+        );
+    }
+
+    #[tokio::test]
+    async fn collisions_are_rejected_and_nothing_changes() {
+        let db = sqlite().await;
+        let ada = add_employee(&db, "ada@example.com").await;
+        let ada_user = add_user(&db, "ada@example.com", Some(ada.id)).await;
+        let admin = add_user(&db, "admin@example.com", None).await;
+        let grace = add_employee(&db, "grace@example.com").await;
+
+        // Employee takes another user's email
+        let err = update_employee(
+            &db,
+            ada.id,
+            "Ada".into(),
+            "Lovelace".into(),
+            "admin@example.com".into(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, DirectoryError::EmailConflict(ref e) if e == "admin@example.com"));
+
+        // User takes another user's email
+        let err = update_user_email(&db, admin.id, "ada@example.com".into())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DirectoryError::EmailConflict(_)));
+
+        // Linked user takes another employee's email
+        let err = update_user_email(&db, ada_user.id, "grace@example.com".into())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DirectoryError::EmailConflict(_)));
+
+        assert_eq!(email_of_user(&db, ada_user.id).await, "ada@example.com");
+        assert_eq!(
+            email_of_employee(&db, ada.id).await.as_deref(),
+            Some("ada@example.com")
+        );
+        assert_eq!(
+            email_of_employee(&db, grace.id).await.as_deref(),
+            Some("grace@example.com")
+        );
+    }
+
+    #[tokio::test]
+    async fn keeping_the_same_email_is_not_a_conflict() {
+        let db = sqlite().await;
+        let emp = add_employee(&db, "ada@example.com").await;
+        let user = add_user(&db, "ada@example.com", Some(emp.id)).await;
+
+        update_employee(
+            &db,
+            emp.id,
+            "Augusta".into(),
+            "Lovelace".into(),
+            "ada@example.com".into(),
+        )
+        .await
+        .unwrap();
+        update_user_email(&db, user.id, "ada@example.com".into())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn unknown_ids_are_reported() {
+        let db = sqlite().await;
+        assert!(matches!(
+            update_employee(&db, 99, "A".into(), "B".into(), "a@example.com".into()).await,
+            Err(DirectoryError::EmployeeNotFound(99))
+        ));
+        assert!(matches!(
+            update_user_email(&db, 99, "a@example.com".into()).await,
+            Err(DirectoryError::UserNotFound(99))
+        ));
+    }
+
+    #[tokio::test]
+    async fn a_second_link_to_one_employee_is_reported_as_linked() {
+        let db = sqlite().await;
+        let emp = add_employee(&db, "ada@example.com").await;
+        add_user(&db, "ada@example.com", Some(emp.id)).await;
+
+        // As if a concurrent request had passed `ensure_employee_linkable`
+        let txn = db.begin().await.unwrap();
+        let err = users::ActiveModel {
+            email: Set("lovelace@example.com".into()),
+            password_hash: Set("x".into()),
+            role_id: Set(5),
+            employee_id: Set(Some(emp.id)),
+            is_active: Set(true),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .unwrap_err();
+
+        let err = link_conflict(&db, txn, Some(emp.id), err).await;
+        assert!(matches!(
+            err,
+            DirectoryError::EmployeeAlreadyLinked(id, ref email)
+                if id == emp.id && email == "ada@example.com"
+        ));
+        assert_eq!(users::Entity::find().count(&db).await.unwrap(), 1);
+    }
+
+    #[test]
+    fn messages_are_english_unless_asked() {
+        let err = DirectoryError::EmailConflict("ada@example.com".into());
+        assert_eq!(err.to_string(), "Email ada@example.com already exists");
+        assert_eq!(
+            err.message(Locale::De),
+            "Die E-Mail-Adresse ada@example.com ist bereits vergeben"
+        );
+        assert_eq!(
+            DirectoryError::EmployeeNotFound(7).message(Locale::De),
+            "Mitarbeiter 7 nicht gefunden"
+        );
+    }
+}
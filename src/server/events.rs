@@ -0,0 +1,153 @@
+// src/server/events.rs
+// Live change feed: Postgres NOTIFY -> broadcast channel -> SSE.
+
+use dioxus::fullstack::ServerEvents;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "server")]
+use tokio::sync::broadcast;
+
+/// Channel the `notify_app_change()` trigger publishes on
+#[cfg(feature = "server")]
+const CHANNEL: &str = "app_changes";
+
+/// Events buffered per subscriber before it starts lagging
+#[cfg(feature = "server")]
+const BUFFER: usize = 256;
+
+//
+// -------------------- CHANGE EVENT --------------------
+//
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeTable {
+    #[serde(rename = "employee")]
+    Employee,
+    #[serde(rename = "users")]
+    User,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ChangeOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// One changed row, as published by the database trigger
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    pub table: ChangeTable,
+    pub op: ChangeOp,
+    pub id: i32,
+}
+
+//
+// -------------------- SUBSCRIPTION --------------------
+//
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("subscribe_changes"))]
+pub async fn subscribe_changes() -> Result<ServerEvents<ChangeEvent>, ServerFnError> {
+    let user =
+        crate::server::session::require_permission(crate::server::auth::EMPLOYEE_READ).await?;
+    // Account changes only go to those who may list accounts
+    let users_visible = user.can(crate::server::auth::USER_READ);
+    let mut rx = changes();
+
+    Ok(ServerEvents::new(move |mut tx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) if event.table == ChangeTable::User && !users_visible => continue,
+                Ok(event) => {
+                    if tx.send(event).await.is_err() {
+                        // Client went away
+                        break;
+                    }
+                }
+                // A slow client misses some rows; the next change catches it up
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }))
+}
+
+/// Subscribe to row changes; the first call starts the database listener
+#[cfg(feature = "server")]
+pub fn changes() -> broadcast::Receiver<ChangeEvent> {
+    static SENDER: OnceLock<broadcast::Sender<ChangeEvent>> = OnceLock::new();
+
+    SENDER
+        .get_or_init(|| {
+            let (tx, _) = broadcast::channel(BUFFER);
+            tokio::spawn(listen(tx.clone()));
+            tx
+        })
+        .subscribe()
+}
+
+/// Forward NOTIFY payloads into the broadcast channel, reconnecting on error
+#[cfg(feature = "server")]
+async fn listen(tx: broadcast::Sender<ChangeEvent>) {
+    use sea_orm::sqlx::postgres::PgListener;
+
+    use crate::server::db_connection::get_db;
+
+    // NOTIFY comes from Postgres triggers; other backends have no feed
+    if let Ok(db) = get_db().await
+        && crate::server::migrate::is_sqlite(&db)
+    {
+        tracing::info!("Live updates are not available on SQLite");
+        return;
+    }
+
+    loop {
+        let result: Result<(), String> = async {
+            let db = get_db().await.map_err(|e| e.to_string())?;
+            let mut listener = PgListener::connect_with(db.get_postgres_connection_pool())
+                .await
+                .map_err(|e| e.to_string())?;
+            listener.listen(CHANNEL).await.map_err(|e| e.to_string())?;
+
+            loop {
+                let notification = listener.recv().await.map_err(|e| e.to_string())?;
+                match serde_json::from_str::<ChangeEvent>(notification.payload()) {
+                    // No subscribers is not an error
+                    Ok(event) => {
+                        let _ = tx.send(event);
+                    }
+                    Err(e) => tracing::warn!("Ignoring malformed {} payload: {}", CHANNEL, e),
+                }
+            }
+        }
+        .await;
+
+        if let Err(e) = result {
+            tracing::warn!("Change listener stopped, retrying in 5s: {}", e);
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_trigger_payload() {
+        let event: ChangeEvent =
+            serde_json::from_str(r#"{"table": "users", "op": "DELETE", "id": 7}"#).unwrap();
+        assert_eq!(
+            event,
+            ChangeEvent {
+                table: ChangeTable::User,
+                op: ChangeOp::Delete,
+                id: 7,
+            }
+        );
+    }
+}
@@ -0,0 +1,575 @@
+//! GraphQL endpoint at `/graphql` over employees, users and roles
+//!
+//! Relations follow the `Related` impls in `entities/` and are resolved
+//! through per-request DataLoaders, so a list of N employees with their
+//! user and role costs three queries instead of 2N + 1. Mutations go
+//! through the same helpers as the server functions in `db.rs`.
+//! The GraphiQL page (`GET /graphql`) only exists in debug builds.
+//!
+//! Like the REST API, `POST /graphql` needs a session token as the cookie or
+//! as `Authorization: Bearer <token>`. Each field checks the permission it
+//! needs against the account's current role: employees need
+//! `employee.read`, users and roles `user.read`, mutations `employee.write`.
+
+use std::collections::HashMap;
+
+use async_graphql::dataloader::{DataLoader, Loader};
+use async_graphql::{
+    ComplexObject, Context, EmptySubscription, Error, InputObject, Object, Result, Schema,
+    SimpleObject,
+};
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+};
+use dioxus::prelude::ServerFnError;
+use sea_orm::*;
+
+use crate::entities::{app_role, employee, users};
+use crate::server::auth::{EMPLOYEE_READ, EMPLOYEE_WRITE, SessionUser, USER_READ};
+use crate::server::db::{self as db_api, Employee};
+use crate::server::db_connection::get_db;
+use crate::server::session;
+use crate::validation::FieldErrors;
+
+pub type AppSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// Default and maximum number of items returned by list fields
+const DEFAULT_LIMIT: u64 = 100;
+const MAX_LIMIT: u64 = 500;
+
+pub fn schema() -> AppSchema {
+    let builder = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .limit_depth(10)
+        .limit_complexity(1000);
+
+    if cfg!(debug_assertions) {
+        builder.finish()
+    } else {
+        builder.disable_introspection().finish()
+    }
+}
+
+pub fn router() -> Router {
+    let route = post(graphql_handler);
+
+    #[cfg(debug_assertions)]
+    let route = route.get(graphiql);
+
+    Router::new().route("/graphql", route).with_state(schema())
+}
+
+async fn graphql_handler(
+    State(schema): State<AppSchema>,
+    headers: HeaderMap,
+    Json(request): Json<async_graphql::Request>,
+) -> Response {
+    let error = |status: StatusCode, message: String| {
+        let errors = vec![async_graphql::ServerError::new(message, None)];
+        (status, Json(async_graphql::Response::from_errors(errors))).into_response()
+    };
+
+    let db = match get_db().await {
+        Ok(db) => db,
+        Err(e) => {
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("DB connection failed: {}", e),
+            );
+        }
+    };
+    let user = match session::request_token(&headers) {
+        Some(token) => session::resolve(&db, &token).await,
+        None => Ok(None),
+    };
+    let user = match user {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return error(
+                StatusCode::UNAUTHORIZED,
+                "Sign in and send the session token".to_string(),
+            );
+        }
+        Err(e) => {
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Query failed: {}", e),
+            );
+        }
+    };
+
+    // Loaders are created per request so cached rows never outlive it
+    let request = request
+        .data(DataLoader::new(EmployeeLoader(db.clone()), tokio::spawn))
+        .data(DataLoader::new(RoleLoader(db.clone()), tokio::spawn))
+        .data(DataLoader::new(
+            UserByEmployeeLoader(db.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(UsersByRoleLoader(db.clone()), tokio::spawn))
+        .data(db)
+        .data(user);
+
+    Json(schema.execute(request).await).into_response()
+}
+
+#[cfg(debug_assertions)]
+async fn graphiql() -> axum::response::Html<String> {
+    axum::response::Html(
+        async_graphql::http::GraphiQLSource::build()
+            .endpoint("/graphql")
+            .finish(),
+    )
+}
+
+fn db<'a>(ctx: &Context<'a>) -> Result<&'a DatabaseConnection> {
+    ctx.data::<DatabaseConnection>()
+}
+
+/// Fail unless the signed-in user's role grants `permission`
+fn require(ctx: &Context<'_>, permission: &str) -> Result<()> {
+    if ctx.data::<SessionUser>()?.can(permission) {
+        Ok(())
+    } else {
+        Err(Error::new(format!(
+            "This needs the {} permission",
+            permission
+        )))
+    }
+}
+
+fn db_err(e: DbErr) -> Error {
+    Error::new(format!("Query failed: {}", e))
+}
+
+fn server_fn_err(e: ServerFnError) -> Error {
+    if let Some(errors) = FieldErrors::from_server(&e) {
+        return Error::new(format!("Invalid input: {}", errors));
+    }
+    match e {
+        ServerFnError::ServerError { message, .. } => Error::new(message),
+        other => Error::new(other.to_string()),
+    }
+}
+
+fn page(limit: Option<u64>, offset: Option<u64>) -> (u64, u64) {
+    (
+        limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT),
+        offset.unwrap_or(0),
+    )
+}
+
+//
+// -------------------- TYPES --------------------
+//
+
+#[derive(SimpleObject)]
+#[graphql(name = "Employee", complex)]
+pub struct EmployeeNode {
+    id: i32,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    email: Option<String>,
+}
+
+impl From<employee::Model> for EmployeeNode {
+    fn from(m: employee::Model) -> Self {
+        Self {
+            id: m.id,
+            first_name: m.first_name,
+            last_name: m.last_name,
+            email: m.email,
+        }
+    }
+}
+
+impl From<Employee> for EmployeeNode {
+    fn from(e: Employee) -> Self {
+        Self {
+            id: e.id,
+            first_name: e.first_name,
+            last_name: e.last_name,
+            email: e.email,
+        }
+    }
+}
+
+#[ComplexObject]
+impl EmployeeNode {
+    /// The user account linked to this employee, if any
+    async fn user(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        require(ctx, USER_READ)?;
+        let loader = ctx.data::<DataLoader<UserByEmployeeLoader>>()?;
+        Ok(loader.load_one(self.id).await?.map(Into::into))
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "User", complex)]
+pub struct UserNode {
+    id: i32,
+    email: String,
+    active: bool,
+    created_at: Option<String>,
+    #[graphql(skip)]
+    role_id: i32,
+    #[graphql(skip)]
+    employee_id: Option<i32>,
+}
+
+impl From<users::Model> for UserNode {
+    fn from(m: users::Model) -> Self {
+        Self {
+            id: m.id,
+            email: m.email,
+            active: m.is_active,
+            created_at: m.created_at.map(|c| c.to_rfc3339()),
+            role_id: m.role_id,
+            employee_id: m.employee_id,
+        }
+    }
+}
+
+#[ComplexObject]
+impl UserNode {
+    async fn role(&self, ctx: &Context<'_>) -> Result<Option<RoleNode>> {
+        let loader = ctx.data::<DataLoader<RoleLoader>>()?;
+        Ok(loader.load_one(self.role_id).await?.map(Into::into))
+    }
+
+    async fn employee(&self, ctx: &Context<'_>) -> Result<Option<EmployeeNode>> {
+        require(ctx, EMPLOYEE_READ)?;
+        let Some(employee_id) = self.employee_id else {
+            return Ok(None);
+        };
+        let loader = ctx.data::<DataLoader<EmployeeLoader>>()?;
+        Ok(loader.load_one(employee_id).await?.map(Into::into))
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "Role", complex)]
+pub struct RoleNode {
+    id: i32,
+    name: String,
+}
+
+impl From<app_role::Model> for RoleNode {
+    fn from(m: app_role::Model) -> Self {
+        Self {
+            id: m.id,
+            name: m.name,
+        }
+    }
+}
+
+#[ComplexObject]
+impl RoleNode {
+    /// All users holding this role
+    async fn users(&self, ctx: &Context<'_>) -> Result<Vec<UserNode>> {
+        require(ctx, USER_READ)?;
+        let loader = ctx.data::<DataLoader<UsersByRoleLoader>>()?;
+        Ok(loader
+            .load_one(self.id)
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+}
+
+#[derive(InputObject)]
+pub struct EmployeeInput {
+    first_name: String,
+    last_name: String,
+    email: String,
+}
+
+//
+// -------------------- QUERIES --------------------
+//
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn employees(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<EmployeeNode>> {
+        require(ctx, EMPLOYEE_READ)?;
+        let (limit, offset) = page(limit, offset);
+        let models = employee::Entity::find()
+            .order_by_asc(employee::Column::Id)
+            .limit(limit)
+            .offset(offset)
+            .all(db(ctx)?)
+            .await
+            .map_err(db_err)?;
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    async fn employee(&self, ctx: &Context<'_>, id: i32) -> Result<Option<EmployeeNode>> {
+        require(ctx, EMPLOYEE_READ)?;
+        let loader = ctx.data::<DataLoader<EmployeeLoader>>()?;
+        Ok(loader.load_one(id).await?.map(Into::into))
+    }
+
+    async fn users(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<UserNode>> {
+        require(ctx, USER_READ)?;
+        let (limit, offset) = page(limit, offset);
+        let models = users::Entity::find()
+            .order_by_asc(users::Column::Id)
+            .limit(limit)
+            .offset(offset)
+            .all(db(ctx)?)
+            .await
+            .map_err(db_err)?;
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    async fn user(&self, ctx: &Context<'_>, id: i32) -> Result<Option<UserNode>> {
+        require(ctx, USER_READ)?;
+        let model = users::Entity::find_by_id(id)
+            .one(db(ctx)?)
+            .await
+            .map_err(db_err)?;
+        Ok(model.map(Into::into))
+    }
+
+    async fn roles(&self, ctx: &Context<'_>) -> Result<Vec<RoleNode>> {
+        require(ctx, USER_READ)?;
+        let models = app_role::Entity::find()
+            .order_by_asc(app_role::Column::Id)
+            .all(db(ctx)?)
+            .await
+            .map_err(db_err)?;
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    async fn role(&self, ctx: &Context<'_>, id: i32) -> Result<Option<RoleNode>> {
+        require(ctx, USER_READ)?;
+        let loader = ctx.data::<DataLoader<RoleLoader>>()?;
+        Ok(loader.load_one(id).await?.map(Into::into))
+    }
+}
+
+//
+// -------------------- MUTATIONS --------------------
+//
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn create_employee(
+        &self,
+        ctx: &Context<'_>,
+        input: EmployeeInput,
+    ) -> Result<EmployeeNode> {
+        require(ctx, EMPLOYEE_WRITE)?;
+        db_api::insert_employee(db(ctx)?, input.first_name, input.last_name, input.email)
+            .await
+            .map(Into::into)
+            .map_err(server_fn_err)
+    }
+
+    async fn update_employee(
+        &self,
+        ctx: &Context<'_>,
+        id: i32,
+        input: EmployeeInput,
+    ) -> Result<EmployeeNode> {
+        require(ctx, EMPLOYEE_WRITE)?;
+        db_api::modify_employee(db(ctx)?, id, input.first_name, input.last_name, input.email)
+            .await
+            .map(Into::into)
+            .map_err(server_fn_err)
+    }
+
+    async fn delete_employee(&self, ctx: &Context<'_>, id: i32) -> Result<bool> {
+        require(ctx, EMPLOYEE_WRITE)?;
+        db_api::remove_employee(db(ctx)?, id)
+            .await
+            .map_err(server_fn_err)?;
+        Ok(true)
+    }
+}
+
+//
+// -------------------- DATALOADERS --------------------
+//
+
+/// Employees by id
+pub struct EmployeeLoader(DatabaseConnection);
+
+impl Loader<i32> for EmployeeLoader {
+    type Value = employee::Model;
+    type Error = Error;
+
+    async fn load(&self, keys: &[i32]) -> Result<HashMap<i32, Self::Value>, Self::Error> {
+        let models = employee::Entity::find()
+            .filter(employee::Column::Id.is_in(keys.iter().copied()))
+            .all(&self.0)
+            .await
+            .map_err(db_err)?;
+        Ok(models.into_iter().map(|m| (m.id, m)).collect())
+    }
+}
+
+/// Roles by id
+pub struct RoleLoader(DatabaseConnection);
+
+impl Loader<i32> for RoleLoader {
+    type Value = app_role::Model;
+    type Error = Error;
+
+    async fn load(&self, keys: &[i32]) -> Result<HashMap<i32, Self::Value>, Self::Error> {
+        let models = app_role::Entity::find()
+            .filter(app_role::Column::Id.is_in(keys.iter().copied()))
+            .all(&self.0)
+            .await
+            .map_err(db_err)?;
+        Ok(models.into_iter().map(|m| (m.id, m)).collect())
+    }
+}
+
+/// The user linked to each employee id (`users.employee_id` is unique)
+pub struct UserByEmployeeLoader(DatabaseConnection);
+
+impl Loader<i32> for UserByEmployeeLoader {
+    type Value = users::Model;
+    type Error = Error;
+
+    async fn load(&self, keys: &[i32]) -> Result<HashMap<i32, Self::Value>, Self::Error> {
+        let models = users::Entity::find()
+            .filter(users::Column::EmployeeId.is_in(keys.iter().copied()))
+            .all(&self.0)
+            .await
+            .map_err(db_err)?;
+        Ok(models
+            .into_iter()
+            .filter_map(|m| m.employee_id.map(|employee_id| (employee_id, m)))
+            .collect())
+    }
+}
+
+/// All users per role id
+pub struct UsersByRoleLoader(DatabaseConnection);
+
+impl Loader<i32> for UsersByRoleLoader {
+    type Value = Vec<users::Model>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[i32]) -> Result<HashMap<i32, Self::Value>, Self::Error> {
+        let models = users::Entity::find()
+            .filter(users::Column::RoleId.is_in(keys.iter().copied()))
+            .order_by_asc(users::Column::Id)
+            .all(&self.0)
+            .await
+            .map_err(db_err)?;
+
+        let mut grouped: HashMap<i32, Vec<users::Model>> = HashMap::new();
+        for model in models {
+            grouped.entry(model.role_id).or_default().push(model);
+        }
+        Ok(grouped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::{Body, to_bytes};
+    use axum::http::{Request, header};
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::server::auth::{ROLE_HR, ROLE_MANAGER};
+    use crate::server::testing::{Fixtures, TestDb};
+
+    async fn send(token: Option<&str>, query: &str) -> (StatusCode, Value) {
+        let mut request =
+            Request::post("/graphql").header(header::CONTENT_TYPE, "application/json");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let body = Body::from(json!({ "query": query }).to_string());
+        let response = router().oneshot(request.body(body).unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    fn error(response: &Value) -> &str {
+        response["errors"][0]["message"]
+            .as_str()
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "sqlite"),
+        ignore = "requires the sqlite feature or TEST_DATABASE_URL"
+    )]
+    async fn fields_check_the_permissions_of_the_session() {
+        let t = TestDb::new().await;
+        let fx = t.fixtures().await;
+        let hr = session::encode(&Fixtures::session(&fx.ada_user, ROLE_HR));
+
+        t.run(async {
+            let (status, response) = send(None, "{ employees { id } }").await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert!(error(&response).contains("session token"));
+
+            let (status, response) = send(Some(&hr), "{ users { email employee { id } } }").await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(response["data"]["users"].as_array().unwrap().len(), 2);
+
+            let create = r#"mutation {
+                createEmployee(input: { firstName: "Alan", lastName: "Turing", email: "alan@example.com" }) { id }
+            }"#;
+            let (_, response) = send(Some(&hr), create).await;
+            assert!(response["data"]["createEmployee"]["id"].is_number(), "{}", response);
+
+            // A role without employee.write or user.read
+            let manager = t.as_role(ROLE_MANAGER, async {
+                session::current().unwrap()
+            }).await;
+            let manager = session::encode(&manager);
+
+            let (_, response) = send(Some(&manager), create).await;
+            assert!(error(&response).contains("employee.write"), "{}", response);
+            let (_, response) = send(Some(&manager), "{ users { id } }").await;
+            assert!(error(&response).contains("user.read"), "{}", response);
+            let (_, response) = send(Some(&manager), "{ employees { id user { email } } }").await;
+            assert!(error(&response).contains("user.read"), "{}", response);
+            let (_, response) = send(Some(&manager), "{ employees { id } }").await;
+            assert_eq!(response["data"]["employees"].as_array().unwrap().len(), 3);
+        })
+        .await;
+
+        t.finish().await;
+    }
+
+    #[test]
+    fn schema_exposes_relations() {
+        let sdl = schema().sdl();
+        assert!(sdl.contains("type Employee"));
+        assert!(sdl.contains("user: User"));
+        assert!(sdl.contains("role: Role"));
+        assert!(sdl.contains("users: [User!]!"));
+        assert!(sdl.contains("createEmployee(input: EmployeeInput!): Employee!"));
+    }
+}
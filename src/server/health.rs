@@ -0,0 +1,322 @@
+//! Liveness and readiness probes, and graceful shutdown
+//!
+//! - `GET /healthz`: the process is up and serving; never touches the DB.
+//! - `GET /readyz`: the pool hands out a working connection, the schema is at
+//!   `EXPECTED_SCHEMA_VERSION` and the `users_extended` view exists. Returns
+//!   503 with the failing check otherwise, and from the moment shutdown starts.
+//!   On SQLite only the connection is checked; there are no migrations or view.
+//!
+//! On SIGTERM or Ctrl-C readiness flips to failing first, the server keeps
+//! serving for `shutdown.grace_secs` so the orchestrator can stop routing to
+//! it, then waits for in-flight requests before exiting.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use axum::{
+    Json, Router,
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, DbErr, Statement};
+use serde::Serialize;
+
+use crate::server::db_connection::get_db;
+
+/// Newest embedded migration; the schema must be exactly at this version
+pub const EXPECTED_SCHEMA_VERSION: i32 = crate::server::migrate::LATEST_VERSION;
+
+/// Upper bound for all readiness checks together
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Longest we wait for in-flight requests before exiting anyway
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+}
+
+//
+// -------------------- PROBES --------------------
+//
+
+#[derive(Debug, Serialize)]
+struct Check {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+impl Check {
+    fn pass() -> Self {
+        Self {
+            ok: true,
+            detail: None,
+        }
+    }
+
+    /// Not applicable to this backend; does not fail readiness
+    fn skipped(detail: impl Into<String>) -> Self {
+        Self {
+            ok: true,
+            detail: Some(detail.into()),
+        }
+    }
+
+    fn fail(detail: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Readiness {
+    status: &'static str,
+    shutting_down: bool,
+    database: Check,
+    migrations: Check,
+    users_extended: Check,
+    elapsed_ms: u64,
+}
+
+impl Readiness {
+    fn is_ready(&self) -> bool {
+        !self.shutting_down && self.database.ok && self.migrations.ok && self.users_extended.ok
+    }
+}
+
+async fn healthz() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+async fn readyz() -> Response {
+    let started = Instant::now();
+    let shutting_down = SHUTTING_DOWN.load(Ordering::SeqCst);
+
+    let (database, migrations, users_extended) =
+        match tokio::time::timeout(CHECK_TIMEOUT, run_checks()).await {
+            Ok(checks) => checks,
+            Err(_) => {
+                let timed_out = || Check::fail("timed out");
+                (timed_out(), timed_out(), timed_out())
+            }
+        };
+
+    let mut readiness = Readiness {
+        status: "ready",
+        shutting_down,
+        database,
+        migrations,
+        users_extended,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    };
+
+    let status = if readiness.is_ready() {
+        StatusCode::OK
+    } else {
+        readiness.status = "not_ready";
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(readiness)).into_response()
+}
+
+async fn run_checks() -> (Check, Check, Check) {
+    let db = match get_db().await {
+        Ok(db) => db,
+        Err(e) => {
+            let skipped = || Check::fail("database unavailable");
+            return (Check::fail(e.to_string()), skipped(), skipped());
+        }
+    };
+
+    if let Err(e) = db.ping().await {
+        let skipped = || Check::fail("database unavailable");
+        return (Check::fail(e.to_string()), skipped(), skipped());
+    }
+
+    if crate::server::migrate::is_sqlite(&db) {
+        return (
+            Check::pass(),
+            Check::skipped("SQLite: schema is created from the entities"),
+            Check::skipped("SQLite: view is not used"),
+        );
+    }
+
+    let migrations = match crate::server::migrate::current_version(&db).await {
+        Ok(version) => migration_check(version),
+        Err(e) => Check::fail(e.to_string()),
+    };
+
+    let users_extended = match relation_exists(&db, "public.users_extended").await {
+        Ok(true) => Check::pass(),
+        Ok(false) => Check::fail("view users_extended is missing"),
+        Err(e) => Check::fail(e.to_string()),
+    };
+
+    (Check::pass(), migrations, users_extended)
+}
+
+fn migration_check(version: Option<i32>) -> Check {
+    match version {
+        Some(v) if v == EXPECTED_SCHEMA_VERSION => Check::pass(),
+        Some(v) => Check::fail(format!(
+            "schema at version {}, expected {}",
+            v, EXPECTED_SCHEMA_VERSION
+        )),
+        None => Check::fail("schema_migrations is missing or empty"),
+    }
+}
+
+/// Whether a table or view exists. `name` may carry a `public.` schema
+/// prefix, which is ignored on SQLite.
+pub async fn relation_exists(db: &DatabaseConnection, name: &str) -> Result<bool, DbErr> {
+    let backend = db.get_database_backend();
+    let stmt = match backend {
+        DatabaseBackend::Sqlite => Statement::from_sql_and_values(
+            backend,
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master \
+             WHERE type IN ('table', 'view') AND name = $1) AS present",
+            [name.trim_start_matches("public.").into()],
+        ),
+        _ => Statement::from_sql_and_values(
+            backend,
+            "SELECT to_regclass($1) IS NOT NULL AS present",
+            [name.into()],
+        ),
+    };
+    let row = db.query_one(stmt).await?;
+
+    match row {
+        Some(row) => row.try_get::<bool>("", "present"),
+        None => Ok(false),
+    }
+}
+
+//
+// -------------------- SHUTDOWN --------------------
+//
+
+/// Middleware: count requests until their response is produced.
+/// Streaming bodies (SSE) are not waited for.
+pub async fn track_in_flight(request: Request, next: Next) -> Response {
+    struct Guard;
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    IN_FLIGHT.fetch_add(1, Ordering::SeqCst);
+    let _guard = Guard;
+    next.run(request).await
+}
+
+/// Install the SIGTERM / Ctrl-C handler; replaces the default immediate exit
+pub fn spawn_shutdown_handler() {
+    static STARTED: AtomicBool = AtomicBool::new(false);
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let grace = Duration::from_secs(crate::server::config::get().shutdown.grace_secs);
+
+    tokio::spawn(async move {
+        shutdown_signal().await;
+
+        SHUTTING_DOWN.store(true, Ordering::SeqCst);
+        tracing::info!(
+            grace_secs = grace.as_secs(),
+            "Shutdown requested, readiness now failing"
+        );
+        tokio::time::sleep(grace).await;
+
+        let deadline = Instant::now() + DRAIN_TIMEOUT;
+        while IN_FLIGHT.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        let remaining = IN_FLIGHT.load(Ordering::SeqCst);
+        if remaining > 0 {
+            tracing::warn!(
+                remaining,
+                "Drain timed out, exiting with requests in flight"
+            );
+        } else {
+            tracing::info!("Drained, exiting");
+        }
+        std::process::exit(0);
+    });
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::warn!("Cannot listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migration_version_must_match() {
+        assert!(migration_check(Some(EXPECTED_SCHEMA_VERSION)).ok);
+        assert!(!migration_check(Some(EXPECTED_SCHEMA_VERSION - 1)).ok);
+        assert!(!migration_check(None).ok);
+    }
+
+    #[test]
+    fn shutdown_fails_readiness() {
+        let readiness = Readiness {
+            status: "ready",
+            shutting_down: true,
+            database: Check::pass(),
+            migrations: Check::pass(),
+            users_extended: Check::pass(),
+            elapsed_ms: 0,
+        };
+        assert!(!readiness.is_ready());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a local PostgreSQL database (DATABASE_URL)"]
+    async fn migrated_database_is_ready() {
+        dotenvy::dotenv().ok();
+        let (database, migrations, users_extended) = run_checks().await;
+        assert!(database.ok, "{:?}", database);
+        assert!(migrations.ok, "{:?}", migrations);
+        assert!(users_extended.ok, "{:?}", users_extended);
+    }
+}
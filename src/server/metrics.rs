@@ -0,0 +1,287 @@
+//! Prometheus metrics at `/metrics`
+//!
+//! Exposed in one of two ways:
+//! - `metrics.addr` / `METRICS_ADDR` (e.g. `127.0.0.1:9100`): a separate
+//!   listener meant for an internal network, no authentication
+//! - `metrics.token` / `METRICS_TOKEN`: mounted on the main router, requires
+//!   `Authorization: Bearer <token>`
+//!
+//! With neither set the endpoint does not exist; metrics are still recorded.
+//!
+//! `db_pool_connections` has the states `idle` and `in_use` but no `waiting`.
+//! The sqlx pool behind SeaORM reports its size and idle count only, and
+//! SeaORM acquires a connection inside every query, so there is no place
+//! here to count tasks blocked on acquire (`get_db` only clones the pool
+//! handle and never waits). `in_use` reaching `db_pool_max_connections` is
+//! the saturation signal instead.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use axum::{
+    Router,
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use metrics::{counter, gauge, histogram};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use sea_orm::*;
+
+use crate::entities::{app_role, employee, users};
+use crate::server::auth::constant_time_eq;
+use crate::server::db_connection::get_db;
+
+pub const SERVER_FN_REQUESTS: &str = "server_fn_requests_total";
+pub const SERVER_FN_DURATION: &str = "server_fn_duration_seconds";
+pub const DB_QUERY_DURATION: &str = "db_query_duration_seconds";
+pub const DB_QUERY_ERRORS: &str = "db_query_errors_total";
+pub const DB_POOL_CONNECTIONS: &str = "db_pool_connections";
+pub const DB_POOL_MAX: &str = "db_pool_max_connections";
+pub const LOGIN_ATTEMPTS: &str = "auth_login_attempts_total";
+pub const EMPLOYEES: &str = "directory_employees";
+pub const USERS_BY_ROLE: &str = "directory_users";
+
+/// Latency buckets in seconds, shared by all histograms
+const BUCKETS: [f64; 12] = [
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 10.0,
+];
+
+const UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Install the global recorder; call before serving. Later calls are no-ops.
+pub fn init() {
+    HANDLE.get_or_init(|| {
+        let handle = PrometheusBuilder::new()
+            .set_buckets_for_metric(Matcher::Suffix("_seconds".into()), &BUCKETS)
+            .expect("bucket list is not empty")
+            .install_recorder()
+            .expect("metrics recorder installed twice");
+
+        metrics::describe_counter!(
+            SERVER_FN_REQUESTS,
+            "Server function calls by function and outcome"
+        );
+        metrics::describe_histogram!(
+            SERVER_FN_DURATION,
+            metrics::Unit::Seconds,
+            "Server function latency"
+        );
+        metrics::describe_histogram!(
+            DB_QUERY_DURATION,
+            metrics::Unit::Seconds,
+            "Database query latency"
+        );
+        metrics::describe_counter!(DB_QUERY_ERRORS, "Failed database queries");
+        metrics::describe_gauge!(DB_POOL_CONNECTIONS, "Pool connections by state");
+        metrics::describe_gauge!(DB_POOL_MAX, "Configured pool size");
+        metrics::describe_counter!(LOGIN_ATTEMPTS, "Login attempts by result");
+        metrics::describe_gauge!(EMPLOYEES, "Number of employees");
+        metrics::describe_gauge!(USERS_BY_ROLE, "Number of users per app_role");
+
+        handle
+    });
+}
+
+/// Serve `/metrics` according to `metrics.addr` / `metrics.token`.
+/// Returns the routes to merge into the main router (empty when unused).
+pub fn router() -> Router {
+    let config = &crate::server::config::get().metrics;
+
+    if let Some(addr) = config.addr {
+        tokio::spawn(async move {
+            let listener = match tokio::net::TcpListener::bind(&addr).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    tracing::error!("Cannot bind metrics listener on {}: {}", addr, e);
+                    return;
+                }
+            };
+            tracing::info!("Serving /metrics on {}", addr);
+            let app = Router::new().route("/metrics", get(render));
+            if let Err(e) = axum::serve(listener, app).await {
+                tracing::error!("Metrics listener stopped: {}", e);
+            }
+        });
+        return Router::new();
+    }
+
+    match config.token.clone() {
+        Some(token) => Router::new()
+            .route("/metrics", get(render))
+            .layer(middleware::from_fn_with_state(token, require_token)),
+        None => Router::new(),
+    }
+}
+
+async fn require_token(State(expected): State<String>, request: Request, next: Next) -> Response {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or_default();
+
+    if constant_time_eq(expected.as_bytes(), provided.trim().as_bytes()) {
+        next.run(request).await
+    } else {
+        StatusCode::UNAUTHORIZED.into_response()
+    }
+}
+
+async fn render() -> Response {
+    let Some(handle) = HANDLE.get() else {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    };
+
+    // Scrape-time gauges; a DB outage must not break the other metrics
+    if let Err(e) = refresh_gauges().await {
+        tracing::warn!("Could not refresh directory gauges: {}", e);
+    }
+    handle.run_upkeep();
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        handle.render(),
+    )
+        .into_response()
+}
+
+/// Open, idle and maximum connections of whichever pool backs `db`
+fn pool_stats(db: &DatabaseConnection) -> (usize, usize, u32) {
+    match db.get_database_backend() {
+        #[cfg(feature = "sqlite")]
+        DatabaseBackend::Sqlite => {
+            let pool = db.get_sqlite_connection_pool();
+            (
+                pool.size() as usize,
+                pool.num_idle(),
+                pool.options().get_max_connections(),
+            )
+        }
+        _ => {
+            let pool = db.get_postgres_connection_pool();
+            (
+                pool.size() as usize,
+                pool.num_idle(),
+                pool.options().get_max_connections(),
+            )
+        }
+    }
+}
+
+async fn refresh_gauges() -> Result<(), DbErr> {
+    let db = get_db().await?;
+
+    let (size, idle, max) = pool_stats(&db);
+    gauge!(DB_POOL_CONNECTIONS, "state" => "idle").set(idle as f64);
+    gauge!(DB_POOL_CONNECTIONS, "state" => "in_use").set((size - idle) as f64);
+    gauge!(DB_POOL_MAX).set(max as f64);
+
+    gauge!(EMPLOYEES).set(employee::Entity::find().count(&db).await? as f64);
+
+    // One grouped query; roles without users report 0
+    let counts: Vec<(i32, i64)> = users::Entity::find()
+        .select_only()
+        .column(users::Column::RoleId)
+        .column_as(users::Column::Id.count(), "count")
+        .group_by(users::Column::RoleId)
+        .into_tuple()
+        .all(&db)
+        .await?;
+    for role in app_role::Entity::find().all(&db).await? {
+        let count = counts
+            .iter()
+            .find(|(role_id, _)| *role_id == role.id)
+            .map_or(0, |(_, count)| *count);
+        gauge!(USERS_BY_ROLE, "role" => role.name).set(count as f64);
+    }
+
+    Ok(())
+}
+
+/// Spawn the periodic upkeep that keeps histogram memory bounded
+pub fn spawn_upkeep() {
+    static STARTED: OnceLock<()> = OnceLock::new();
+    STARTED.get_or_init(|| {
+        tokio::spawn(async {
+            loop {
+                tokio::time::sleep(UPKEEP_INTERVAL).await;
+                if let Some(handle) = HANDLE.get() {
+                    handle.run_upkeep();
+                }
+            }
+        });
+    });
+}
+
+//
+// -------------------- RECORDING --------------------
+//
+
+pub fn record_server_fn(name: &'static str, outcome: &'static str, elapsed: Duration) {
+    counter!(SERVER_FN_REQUESTS, "server_fn" => name, "outcome" => outcome).increment(1);
+    histogram!(SERVER_FN_DURATION, "server_fn" => name).record(elapsed.as_secs_f64());
+}
+
+pub fn record_query(elapsed: Duration, failed: bool) {
+    histogram!(DB_QUERY_DURATION).record(elapsed.as_secs_f64());
+    if failed {
+        counter!(DB_QUERY_ERRORS).increment(1);
+    }
+}
+
+pub fn record_login(success: bool) {
+    let result = if success { "success" } else { "failure" };
+    counter!(LOGIN_ATTEMPTS, "result" => result).increment(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn token_protects_metrics() {
+        init();
+        record_login(false);
+
+        let app =
+            Router::new()
+                .route("/metrics", get(render))
+                .layer(middleware::from_fn_with_state(
+                    "t0ken".to_string(),
+                    require_token,
+                ));
+
+        let denied = app
+            .clone()
+            .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(denied.status(), StatusCode::UNAUTHORIZED);
+
+        let allowed = app
+            .oneshot(
+                Request::get("/metrics")
+                    .header(header::AUTHORIZATION, "Bearer t0ken")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(allowed.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(allowed.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains(r#"auth_login_attempts_total{result="failure"}"#));
+    }
+}
@@ -0,0 +1,693 @@
+//! Embedded SQL migrations and `schema_migrations` bookkeeping
+//!
+//! The files in `migrations/` are compiled in. Each has an UP part and a
+//! commented-out `-- DOWN Migration` part, which is uncommented for rollback.
+//! Only versions above the highest applied one are run, so older files never
+//! re-run against a database that was migrated by hand and then baselined.
+//!
+//! The SQL files are PostgreSQL-only. A SQLite database gets its tables from
+//! the entities instead (`create_sqlite_schema`) and has no version tracking.
+
+use std::fmt;
+
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ActiveValue::Set, ConnectionTrait, DatabaseBackend, DatabaseConnection, DbErr, EntityTrait,
+    IdenStatic, Iterable, PaginatorTrait, Schema, Statement, TransactionTrait,
+};
+
+use crate::server::health::relation_exists;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrationKind {
+    Schema,
+    /// Sample data; `up` records it without running it, `seed --demo` runs it
+    DemoData,
+}
+
+#[derive(Debug)]
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    pub kind: MigrationKind,
+    sql: &'static str,
+}
+
+const fn migration(
+    version: i32,
+    name: &'static str,
+    kind: MigrationKind,
+    sql: &'static str,
+) -> Migration {
+    Migration {
+        version,
+        name,
+        kind,
+        sql,
+    }
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    migration(
+        0,
+        "base_schema",
+        MigrationKind::Schema,
+        include_str!("../../migrations/000_base_schema.sql"),
+    ),
+    migration(
+        1,
+        "add_employee_id_to_users",
+        MigrationKind::Schema,
+        include_str!("../../migrations/001_add_employee_id_to_users.sql"),
+    ),
+    migration(
+        2,
+        "seed_initial_users",
+        MigrationKind::DemoData,
+        include_str!("../../migrations/002_seed_initial_users.sql"),
+    ),
+    migration(
+        3,
+        "scim_provisioning",
+        MigrationKind::Schema,
+        include_str!("../../migrations/003_scim_provisioning.sql"),
+    ),
+    migration(
+        4,
+        "change_notifications",
+        MigrationKind::Schema,
+        include_str!("../../migrations/004_change_notifications.sql"),
+    ),
+    migration(
+        5,
+        "webhooks",
+        MigrationKind::Schema,
+        include_str!("../../migrations/005_webhooks.sql"),
+    ),
+    migration(
+        6,
+        "schema_migrations",
+        MigrationKind::Schema,
+        include_str!("../../migrations/006_schema_migrations.sql"),
+    ),
+    migration(
+        7,
+        "drop_email_triggers",
+        MigrationKind::Schema,
+        include_str!("../../migrations/007_drop_email_triggers.sql"),
+    ),
+    migration(
+        8,
+        "user_locale",
+        MigrationKind::Schema,
+        include_str!("../../migrations/008_user_locale.sql"),
+    ),
+    migration(
+        9,
+        "user_theme",
+        MigrationKind::Schema,
+        include_str!("../../migrations/009_user_theme.sql"),
+    ),
+    migration(
+        10,
+        "activity_timestamps",
+        MigrationKind::Schema,
+        include_str!("../../migrations/010_activity_timestamps.sql"),
+    ),
+    migration(
+        11,
+        "role_permissions",
+        MigrationKind::Schema,
+        include_str!("../../migrations/011_role_permissions.sql"),
+    ),
+    migration(
+        12,
+        "user_deactivation",
+        MigrationKind::Schema,
+        include_str!("../../migrations/012_user_deactivation.sql"),
+    ),
+    migration(
+        13,
+        "unique_user_employee",
+        MigrationKind::Schema,
+        include_str!("../../migrations/013_unique_user_employee.sql"),
+    ),
+];
+
+/// Version of the newest embedded migration
+pub const LATEST_VERSION: i32 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+/// Creates `schema_migrations`; rolling it back would lose the bookkeeping
+const TRACKING_VERSION: i32 = 6;
+
+/// Demo employees inserted by `seed --demo` before the demo accounts
+pub const DEMO_EMPLOYEES_SQL: &str = include_str!("../../migrations/seed_demo_employees.sql");
+
+const DOWN_MARKER: &str = "-- DOWN Migration";
+
+const CREATE_TRACKING_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS schema_migrations (
+        version     INTEGER PRIMARY KEY,
+        name        VARCHAR(255) NOT NULL,
+        applied_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )";
+
+impl Migration {
+    /// Everything before the DOWN marker
+    pub fn up_sql(&self) -> &'static str {
+        match self.sql.find(DOWN_MARKER) {
+            Some(end) => &self.sql[..end],
+            None => self.sql,
+        }
+    }
+
+    /// The commented statements after the DOWN marker, uncommented
+    pub fn down_sql(&self) -> Option<String> {
+        let start = self.sql.find(DOWN_MARKER)?;
+        let statements: Vec<&str> = self.sql[start..]
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.strip_prefix("-- "))
+            .collect();
+        (!statements.is_empty()).then(|| statements.join("\n"))
+    }
+}
+
+pub fn find(version: i32) -> Option<&'static Migration> {
+    MIGRATIONS.iter().find(|m| m.version == version)
+}
+
+//
+// -------------------- ERRORS --------------------
+//
+
+#[derive(Debug)]
+pub enum MigrateError {
+    Db(DbErr),
+    /// Tables exist but `schema_migrations` does not
+    NotBaselined,
+    /// A migration failed; nothing of it was applied
+    Failed {
+        version: i32,
+        source: DbErr,
+    },
+    NoDownSql(i32),
+    Irreversible(i32),
+    UnknownVersion(i32),
+    /// The command needs the PostgreSQL migrations
+    Sqlite(&'static str),
+}
+
+impl fmt::Display for MigrateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Db(e) => write!(f, "{}", e),
+            Self::NotBaselined => write!(
+                f,
+                "the database has tables but no schema_migrations; \
+                 run `rfc-admin migrate baseline <VERSION>` with the last migration applied by hand"
+            ),
+            Self::Failed { version, source } => {
+                write!(f, "migration {:03} failed: {}", version, source)
+            }
+            Self::NoDownSql(v) => write!(f, "migration {:03} has no DOWN section", v),
+            Self::Irreversible(v) => write!(
+                f,
+                "migration {:03} creates schema_migrations and cannot be rolled back",
+                v
+            ),
+            Self::UnknownVersion(v) => write!(f, "there is no migration {:03}", v),
+            Self::Sqlite(command) => write!(
+                f,
+                "`migrate {}` is not available on SQLite; the schema is created from the entities",
+                command
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MigrateError {}
+
+impl From<DbErr> for MigrateError {
+    fn from(e: DbErr) -> Self {
+        Self::Db(e)
+    }
+}
+
+//
+// -------------------- STATE --------------------
+//
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Applied {
+    pub version: i32,
+    pub name: String,
+    pub applied_at: String,
+}
+
+/// Applied migrations in version order; `None` when the table is missing,
+/// which is always the case on SQLite
+pub async fn applied(db: &DatabaseConnection) -> Result<Option<Vec<Applied>>, DbErr> {
+    if is_sqlite(db) || !relation_exists(db, "public.schema_migrations").await? {
+        return Ok(None);
+    }
+
+    let rows = db
+        .query_all(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT version, name, applied_at::TEXT AS applied_at \
+             FROM schema_migrations ORDER BY version",
+        ))
+        .await?;
+
+    rows.iter()
+        .map(|row| {
+            Ok(Applied {
+                version: row.try_get("", "version")?,
+                name: row.try_get("", "name")?,
+                applied_at: row.try_get("", "applied_at")?,
+            })
+        })
+        .collect::<Result<_, DbErr>>()
+        .map(Some)
+}
+
+/// Highest applied version, `None` for an untracked database
+pub async fn current_version(db: &DatabaseConnection) -> Result<Option<i32>, DbErr> {
+    Ok(applied(db)
+        .await?
+        .and_then(|rows| rows.last().map(|a| a.version)))
+}
+
+//
+// -------------------- COMMANDS --------------------
+//
+
+/// Apply every migration above the current version, each in its own
+/// transaction. Returns the versions that were applied.
+pub async fn up(db: &DatabaseConnection) -> Result<Vec<i32>, MigrateError> {
+    if is_sqlite(db) {
+        create_sqlite_schema(db).await?;
+        return Ok(Vec::new());
+    }
+
+    let current = match current_version(db).await? {
+        Some(version) => Some(version),
+        None if relation_exists(db, "public.users").await? => {
+            return Err(MigrateError::NotBaselined);
+        }
+        None => None,
+    };
+
+    db.execute_unprepared(CREATE_TRACKING_TABLE).await?;
+
+    let mut done = Vec::new();
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|m| current.is_none_or(|c| m.version > c))
+    {
+        let txn = db.begin().await?;
+        let result = async {
+            if migration.kind == MigrationKind::Schema {
+                txn.execute_unprepared(migration.up_sql()).await?;
+            }
+            record(&txn, migration).await?;
+            Ok::<_, DbErr>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => txn.commit().await?,
+            Err(source) => {
+                txn.rollback().await?;
+                return Err(MigrateError::Failed {
+                    version: migration.version,
+                    source,
+                });
+            }
+        }
+        done.push(migration.version);
+    }
+    Ok(done)
+}
+
+/// Roll back the newest `steps` migrations. Returns the versions rolled back.
+pub async fn down(db: &DatabaseConnection, steps: usize) -> Result<Vec<i32>, MigrateError> {
+    if is_sqlite(db) {
+        return Err(MigrateError::Sqlite("down"));
+    }
+    let applied = applied(db).await?.unwrap_or_default();
+
+    let mut done = Vec::new();
+    for entry in applied.iter().rev().take(steps) {
+        let migration = find(entry.version).ok_or(MigrateError::UnknownVersion(entry.version))?;
+        if migration.version == TRACKING_VERSION {
+            return Err(MigrateError::Irreversible(migration.version));
+        }
+
+        let down_sql = match (migration.kind, migration.down_sql()) {
+            (_, Some(sql)) => Some(sql),
+            // Demo rows stay; only the bookkeeping is removed
+            (MigrationKind::DemoData, None) => None,
+            (MigrationKind::Schema, None) => {
+                return Err(MigrateError::NoDownSql(migration.version));
+            }
+        };
+
+        let txn = db.begin().await?;
+        let result = async {
+            if let Some(sql) = &down_sql {
+                txn.execute_unprepared(sql).await?;
+            }
+            txn.execute(Statement::from_sql_and_values(
+                txn.get_database_backend(),
+                "DELETE FROM schema_migrations WHERE version = $1",
+                [migration.version.into()],
+            ))
+            .await?;
+            Ok::<_, DbErr>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => txn.commit().await?,
+            Err(source) => {
+                txn.rollback().await?;
+                return Err(MigrateError::Failed {
+                    version: migration.version,
+                    source,
+                });
+            }
+        }
+        done.push(migration.version);
+    }
+    Ok(done)
+}
+
+/// Mark migrations up to `version` as applied without running them
+pub async fn baseline(db: &DatabaseConnection, version: i32) -> Result<(), MigrateError> {
+    if is_sqlite(db) {
+        return Err(MigrateError::Sqlite("baseline"));
+    }
+    find(version).ok_or(MigrateError::UnknownVersion(version))?;
+
+    let txn = db.begin().await?;
+    txn.execute_unprepared(CREATE_TRACKING_TABLE).await?;
+    for migration in MIGRATIONS.iter().filter(|m| m.version <= version) {
+        record(&txn, migration).await?;
+    }
+    txn.commit().await?;
+    Ok(())
+}
+
+async fn record<C: ConnectionTrait>(db: &C, migration: &Migration) -> Result<(), DbErr> {
+    db.execute(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        "INSERT INTO schema_migrations (version, name) VALUES ($1, $2) \
+         ON CONFLICT (version) DO NOTHING",
+        [migration.version.into(), migration.name.into()],
+    ))
+    .await?;
+    Ok(())
+}
+
+//
+// -------------------- SEED --------------------
+//
+
+/// Reference roles, also inserted by 000 for databases created by `up`.
+/// Everything refers to them by name.
+pub const ROLES: &[&str] = &[
+    crate::server::auth::ROLE_ADMIN,
+    crate::server::auth::ROLE_MANAGER,
+    crate::server::auth::ROLE_HR,
+    crate::server::auth::ROLE_IT,
+    crate::server::auth::ROLE_EMPLOYEE,
+];
+
+/// Insert any missing reference role
+pub async fn ensure_roles<C: ConnectionTrait>(db: &C) -> Result<(), DbErr> {
+    use crate::entities::app_role;
+
+    app_role::Entity::insert_many(ROLES.iter().map(|name| app_role::ActiveModel {
+        name: Set(name.to_string()),
+        ..Default::default()
+    }))
+    .on_conflict(
+        OnConflict::column(app_role::Column::Name)
+            .do_nothing()
+            .to_owned(),
+    )
+    .do_nothing()
+    .exec(db)
+    .await?;
+    Ok(())
+}
+
+/// Grant the reference roles `DEFAULT_PERMISSIONS` while no role has any
+/// permission, that is on a fresh database. Later edits are left alone.
+pub async fn ensure_default_permissions<C: ConnectionTrait>(db: &C) -> Result<(), DbErr> {
+    use crate::entities::{app_role, role_permission};
+
+    if role_permission::Entity::find().count(db).await? > 0 {
+        return Ok(());
+    }
+    let roles = app_role::Entity::find().all(db).await?;
+    let grants = crate::server::auth::DEFAULT_PERMISSIONS
+        .iter()
+        .filter_map(|(name, permissions)| {
+            let role = roles.iter().find(|r| r.name == *name)?;
+            Some(permissions.iter().map(|p| role_permission::ActiveModel {
+                role_id: Set(role.id),
+                permission: Set(p.to_string()),
+            }))
+        })
+        .flatten();
+    role_permission::Entity::insert_many(grants)
+        .do_nothing()
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Ensure the reference roles exist; with `demo`, add the sample employees
+/// and the `password123` demo accounts. Safe to run repeatedly.
+pub async fn seed(db: &DatabaseConnection, demo: bool) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+    ensure_roles(&txn).await?;
+    ensure_default_permissions(&txn).await?;
+    if demo {
+        txn.execute_unprepared(DEMO_EMPLOYEES_SQL).await?;
+        let accounts = MIGRATIONS
+            .iter()
+            .find(|m| m.kind == MigrationKind::DemoData)
+            .expect("demo accounts migration is embedded");
+        txn.execute_unprepared(accounts.up_sql()).await?;
+    }
+    txn.commit().await
+}
+
+//
+// -------------------- SQLITE --------------------
+//
+
+pub fn is_sqlite<C: ConnectionTrait>(db: &C) -> bool {
+    db.get_database_backend() == DatabaseBackend::Sqlite
+}
+
+/// Create every entity's table (parents first), the reference roles and
+/// their permissions. Existing tables are left alone, so this runs on every
+/// connect.
+pub async fn create_sqlite_schema<C: ConnectionTrait>(db: &C) -> Result<(), DbErr> {
+    use crate::entities::{app_role, employee, role_permission, users, webhook, webhook_delivery};
+
+    let schema = Schema::new(DatabaseBackend::Sqlite);
+    let backend = db.get_database_backend();
+    for mut table in [
+        schema.create_table_from_entity(app_role::Entity),
+        schema.create_table_from_entity(role_permission::Entity),
+        schema.create_table_from_entity(employee::Entity),
+        schema.create_table_from_entity(users::Entity),
+        schema.create_table_from_entity(webhook::Entity),
+        schema.create_table_from_entity(webhook_delivery::Entity),
+    ] {
+        table.if_not_exists();
+        db.execute(backend.build(&table)).await?;
+    }
+    // New tables already have `users.employee_id` unique (the entity says
+    // so); this covers files created before it was, as 013 does on PostgreSQL
+    db.execute_unprepared(
+        "CREATE UNIQUE INDEX IF NOT EXISTS unique_users_employee_id ON users (employee_id)",
+    )
+    .await?;
+    ensure_roles(db).await?;
+    ensure_default_permissions(db).await
+}
+
+//
+// -------------------- SCHEMA DRIFT --------------------
+//
+
+/// A difference between the entities and the live schema
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Drift {
+    MissingTable(String),
+    MissingColumn {
+        table: String,
+        column: String,
+    },
+    /// Harmless unless unexpected: the entities ignore it
+    ExtraColumn {
+        table: String,
+        column: String,
+    },
+}
+
+impl Drift {
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Self::ExtraColumn { .. })
+    }
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingTable(table) => write!(f, "table {} is missing", table),
+            Self::MissingColumn { table, column } => {
+                write!(f, "column {}.{} is missing", table, column)
+            }
+            Self::ExtraColumn { table, column } => {
+                write!(f, "column {}.{} is not mapped by any entity", table, column)
+            }
+        }
+    }
+}
+
+/// Compare every entity's columns with `information_schema`, or with
+/// `pragma_table_info` on SQLite
+pub async fn drift(db: &DatabaseConnection) -> Result<Vec<Drift>, DbErr> {
+    use crate::entities::{app_role, employee, role_permission, users, webhook, webhook_delivery};
+
+    let mut found = Vec::new();
+    entity_drift(db, app_role::Entity, &mut found).await?;
+    entity_drift(db, role_permission::Entity, &mut found).await?;
+    entity_drift(db, employee::Entity, &mut found).await?;
+    entity_drift(db, users::Entity, &mut found).await?;
+    entity_drift(db, webhook::Entity, &mut found).await?;
+    entity_drift(db, webhook_delivery::Entity, &mut found).await?;
+    Ok(found)
+}
+
+async fn entity_drift<E: EntityTrait>(
+    db: &DatabaseConnection,
+    entity: E,
+    found: &mut Vec<Drift>,
+) -> Result<(), DbErr> {
+    let table = entity.table_name().to_string();
+
+    let sql = if is_sqlite(db) {
+        "SELECT name AS column_name FROM pragma_table_info($1)"
+    } else {
+        "SELECT column_name::TEXT AS column_name FROM information_schema.columns \
+         WHERE table_schema = 'public' AND table_name = $1"
+    };
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            sql,
+            [table.clone().into()],
+        ))
+        .await?;
+    let actual: Vec<String> = rows
+        .iter()
+        .map(|row| row.try_get("", "column_name"))
+        .collect::<Result<_, _>>()?;
+
+    if actual.is_empty() {
+        found.push(Drift::MissingTable(table));
+        return Ok(());
+    }
+
+    let expected: Vec<String> = E::Column::iter().map(|c| c.as_str().to_owned()).collect();
+    for column in &expected {
+        if !actual.iter().any(|a| a == column) {
+            found.push(Drift::MissingColumn {
+                table: table.clone(),
+                column: column.clone(),
+            });
+        }
+    }
+    for column in actual {
+        if !expected.contains(&column) {
+            found.push(Drift::ExtraColumn {
+                table: table.clone(),
+                column,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrations_are_ordered_and_named_after_their_files() {
+        for pair in MIGRATIONS.windows(2) {
+            assert_eq!(pair[1].version, pair[0].version + 1);
+        }
+        assert_eq!(LATEST_VERSION, 13);
+        assert!(find(6).unwrap().up_sql().contains("schema_migrations"));
+    }
+
+    #[test]
+    fn splits_up_and_down_sections() {
+        let webhooks = find(5).unwrap();
+        assert!(webhooks.up_sql().contains("CREATE TABLE webhook ("));
+        assert!(!webhooks.up_sql().contains("DROP TABLE"));
+
+        let down = webhooks.down_sql().unwrap();
+        assert!(down.starts_with("DROP TRIGGER IF EXISTS trg_webhook_user_role_change"));
+        assert!(down.contains("DROP TABLE IF EXISTS webhook;"));
+        assert!(!down.contains("-- "));
+
+        // The seed has no DOWN section
+        assert_eq!(find(2).unwrap().down_sql(), None);
+    }
+
+    #[test]
+    fn every_schema_migration_can_be_rolled_back() {
+        for migration in MIGRATIONS {
+            if migration.kind == MigrationKind::Schema {
+                assert!(
+                    migration.down_sql().is_some(),
+                    "{:03} has no DOWN section",
+                    migration.version
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn sqlite_schema_matches_entities_and_takes_the_demo_seed() {
+        use crate::entities::users;
+        use sea_orm::{ConnectOptions, Database, PaginatorTrait};
+
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1);
+        let db = Database::connect(options).await.unwrap();
+
+        create_sqlite_schema(&db).await.unwrap();
+        // Runs on every connect, so it must tolerate existing tables
+        create_sqlite_schema(&db).await.unwrap();
+        seed(&db, true).await.unwrap();
+        seed(&db, true).await.unwrap();
+
+        let drift = drift(&db).await.unwrap();
+        assert!(drift.is_empty(), "{:?}", drift);
+        assert_eq!(users::Entity::find().count(&db).await.unwrap(), 4);
+        assert!(applied(&db).await.unwrap().is_none());
+    }
+}
@@ -1,4 +1,46 @@
 pub mod auth;
+pub mod bulk;
+pub mod dashboard;
 pub mod db;
 pub mod db_connection;
-pub mod state;
\ No newline at end of file
+pub mod events;
+pub mod roles;
+pub mod state;
+
+#[cfg(feature = "server")]
+pub mod config;
+
+#[cfg(feature = "server")]
+pub mod directory;
+
+#[cfg(feature = "server")]
+pub mod graphql;
+
+#[cfg(feature = "server")]
+pub mod health;
+
+#[cfg(feature = "server")]
+pub mod metrics;
+
+#[cfg(feature = "server")]
+pub mod migrate;
+
+#[cfg(feature = "server")]
+pub mod rest;
+
+#[cfg(feature = "server")]
+pub mod scim;
+
+#[cfg(feature = "server")]
+pub mod search;
+
+#[cfg(feature = "server")]
+pub mod session;
+
+#[cfg(feature = "server")]
+pub mod telemetry;
+
+#[cfg(all(feature = "server", test))]
+pub mod testing;
+
+pub mod webhooks;
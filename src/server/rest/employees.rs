@@ -0,0 +1,247 @@
+//! `/api/v1/employees`
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use sea_orm::sea_query::{Expr, Func};
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{ApiError, PageQuery, Problem, check_if_match, db, json_with_etag, require};
+use crate::server::auth::{EMPLOYEE_READ, EMPLOYEE_WRITE, SessionUser};
+use crate::server::db::{Employee, EmployeeEntity, employee};
+use crate::server::directory;
+use crate::server::search::contains;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EmployeeFilter {
+    /// Case-insensitive substring of first name, last name or email
+    pub q: Option<String>,
+    /// Exact email address
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct EmployeeInput {
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+}
+
+impl EmployeeInput {
+    fn validate(&self) -> Result<(), ApiError> {
+        let errors = crate::validation::employee(&self.first_name, &self.last_name, &self.email);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::Unprocessable(errors.to_string()))
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmployeePage {
+    pub items: Vec<Employee>,
+    pub page: u64,
+    pub per_page: u64,
+    pub total: u64,
+}
+
+async fn find<C: ConnectionTrait>(db: &C, id: i32) -> Result<employee::Model, ApiError> {
+    EmployeeEntity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Employee {} not found", id)))
+}
+
+/// List employees
+#[utoipa::path(
+    get,
+    path = "/api/v1/employees",
+    tag = "employees",
+    params(EmployeeFilter, PageQuery),
+    responses(
+        (status = 200, description = "A page of employees", body = EmployeePage),
+        (status = 304, description = "Not modified (If-None-Match)"),
+        (status = 401, description = "No valid session token", body = Problem),
+        (status = 403, description = "Missing permission", body = Problem),
+    )
+)]
+pub async fn list(
+    Extension(user): Extension<SessionUser>,
+    headers: HeaderMap,
+    Query(filter): Query<EmployeeFilter>,
+    Query(page): Query<PageQuery>,
+) -> Result<Response, ApiError> {
+    require(&user, EMPLOYEE_READ)?;
+    let db = db().await?;
+
+    let mut query = EmployeeEntity::find();
+    if let Some(q) = filter.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        let q = q.to_lowercase();
+        let lower = |c: employee::Column| Expr::expr(Func::lower(Expr::col(c)));
+        query = query.filter(
+            Condition::any()
+                .add(lower(employee::Column::FirstName).like(contains(&q)))
+                .add(lower(employee::Column::LastName).like(contains(&q)))
+                .add(lower(employee::Column::Email).like(contains(&q))),
+        );
+    }
+    if let Some(email) = filter.email {
+        query = query.filter(employee::Column::Email.eq(email));
+    }
+
+    let total = query.clone().count(&db).await?;
+    let models = query
+        .order_by_asc(employee::Column::Id)
+        .offset(page.offset())
+        .limit(page.per_page())
+        .all(&db)
+        .await?;
+
+    Ok(json_with_etag(
+        &headers,
+        EmployeePage {
+            items: models.into_iter().map(Into::into).collect(),
+            page: page.page(),
+            per_page: page.per_page(),
+            total,
+        },
+    ))
+}
+
+/// Get one employee
+#[utoipa::path(
+    get,
+    path = "/api/v1/employees/{id}",
+    tag = "employees",
+    params(("id" = i32, Path, description = "Employee id")),
+    responses(
+        (status = 200, description = "The employee", body = Employee),
+        (status = 304, description = "Not modified (If-None-Match)"),
+        (status = 404, description = "Unknown employee", body = Problem),
+        (status = 401, description = "No valid session token", body = Problem),
+        (status = 403, description = "Missing permission", body = Problem),
+    )
+)]
+pub async fn get(
+    Extension(user): Extension<SessionUser>,
+    headers: HeaderMap,
+    Path(id): Path<i32>,
+) -> Result<Response, ApiError> {
+    require(&user, EMPLOYEE_READ)?;
+    let db = db().await?;
+    let employee: Employee = find(&db, id).await?.into();
+    Ok(json_with_etag(&headers, employee))
+}
+
+/// Create an employee
+#[utoipa::path(
+    post,
+    path = "/api/v1/employees",
+    tag = "employees",
+    request_body = EmployeeInput,
+    responses(
+        (status = 201, description = "Created", body = Employee),
+        (status = 409, description = "Email already in use", body = Problem),
+        (status = 422, description = "Invalid input", body = Problem),
+        (status = 401, description = "No valid session token", body = Problem),
+        (status = 403, description = "Missing permission", body = Problem),
+    )
+)]
+pub async fn create(
+    Extension(user): Extension<SessionUser>,
+    Json(input): Json<EmployeeInput>,
+) -> Result<Response, ApiError> {
+    require(&user, EMPLOYEE_WRITE)?;
+    input.validate()?;
+    let db = db().await?;
+
+    let created: Employee =
+        directory::insert_employee(&db, input.first_name, input.last_name, input.email)
+            .await?
+            .into();
+
+    let location = HeaderValue::from_str(&format!("/api/v1/employees/{}", created.id))
+        .expect("Location is valid ASCII");
+    Ok((
+        StatusCode::CREATED,
+        [(header::LOCATION, location)],
+        Json(created),
+    )
+        .into_response())
+}
+
+/// Replace an employee
+#[utoipa::path(
+    put,
+    path = "/api/v1/employees/{id}",
+    tag = "employees",
+    params(("id" = i32, Path, description = "Employee id")),
+    request_body = EmployeeInput,
+    responses(
+        (status = 200, description = "Updated", body = Employee),
+        (status = 404, description = "Unknown employee", body = Problem),
+        (status = 409, description = "Email already in use", body = Problem),
+        (status = 412, description = "If-Match does not match the current ETag", body = Problem),
+        (status = 422, description = "Invalid input", body = Problem),
+        (status = 401, description = "No valid session token", body = Problem),
+        (status = 403, description = "Missing permission", body = Problem),
+    )
+)]
+pub async fn update(
+    Extension(user): Extension<SessionUser>,
+    headers: HeaderMap,
+    Path(id): Path<i32>,
+    Json(input): Json<EmployeeInput>,
+) -> Result<Response, ApiError> {
+    require(&user, EMPLOYEE_WRITE)?;
+    input.validate()?;
+    let db = db().await?;
+    let txn = db.begin().await?;
+
+    let existing = find(&txn, id).await?;
+    check_if_match(&headers, &Employee::from(existing))?;
+
+    let updated: Employee =
+        directory::update_employee(&txn, id, input.first_name, input.last_name, input.email)
+            .await?
+            .into();
+    txn.commit().await?;
+    Ok(json_with_etag(&HeaderMap::new(), updated))
+}
+
+/// Delete an employee
+#[utoipa::path(
+    delete,
+    path = "/api/v1/employees/{id}",
+    tag = "employees",
+    params(("id" = i32, Path, description = "Employee id")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, description = "Unknown employee", body = Problem),
+        (status = 412, description = "If-Match does not match the current ETag", body = Problem),
+        (status = 401, description = "No valid session token", body = Problem),
+        (status = 403, description = "Missing permission", body = Problem),
+    )
+)]
+pub async fn delete(
+    Extension(user): Extension<SessionUser>,
+    headers: HeaderMap,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, ApiError> {
+    require(&user, EMPLOYEE_WRITE)?;
+    let db = db().await?;
+
+    let existing = find(&db, id).await?;
+    check_if_match(&headers, &Employee::from(existing))?;
+
+    let deactivate = crate::server::config::get().auth.deactivate_with_employee;
+    directory::delete_employee(&db, id, deactivate).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
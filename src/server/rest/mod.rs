@@ -0,0 +1,571 @@
+//! Versioned REST JSON API mounted at `/api/v1`
+//!
+//! Unlike the Dioxus server functions, whose wire format is an
+//! implementation detail, this router is a stable public interface.
+//! The OpenAPI 3 document is generated from the handler annotations
+//! and served at `/api/v1/openapi.json`.
+//!
+//! Everything else needs a session token, sent as
+//! `Authorization: Bearer <token>` or as the `session` cookie. Reading
+//! takes `employee.read` or `user.read`, writing `employee.write`, checked
+//! against the role the account has at the time of the request.
+
+mod employees;
+mod roles;
+mod users;
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use axum::{
+    Json, Router,
+    extract::Request,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use sea_orm::{DatabaseConnection, DbErr, SqlErr};
+use serde::{Deserialize, Serialize};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{IntoParams, Modify, OpenApi, ToSchema};
+
+use crate::server::auth::SessionUser;
+use crate::server::db::{Employee, Role, UserExtended};
+use crate::server::db_connection::get_db;
+use crate::server::directory::DirectoryError;
+use crate::server::session;
+
+/// Default and maximum page sizes for list endpoints
+const DEFAULT_PER_PAGE: u64 = 20;
+const MAX_PER_PAGE: u64 = 100;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Rust Fullstack Core API", version = "1.0.0"),
+    paths(
+        employees::list,
+        employees::get,
+        employees::create,
+        employees::update,
+        employees::delete,
+        users::list,
+        users::get,
+        roles::list,
+        roles::get,
+    ),
+    components(schemas(
+        Employee,
+        employees::EmployeeInput,
+        employees::EmployeePage,
+        UserExtended,
+        users::UserPage,
+        Role,
+        Problem,
+    )),
+    tags(
+        (name = "employees", description = "Employee master data"),
+        (name = "users", description = "User accounts with role and linked employee"),
+        (name = "roles", description = "Application roles"),
+    ),
+    modifiers(&SessionAuth),
+    security(("bearer" = []), ("cookie" = []))
+)]
+pub struct ApiDoc;
+
+/// The session token, as a bearer token or as the cookie `login` sets
+struct SessionAuth;
+
+impl Modify for SessionAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "cookie",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new(session::COOKIE))),
+        );
+    }
+}
+
+pub fn router() -> Router {
+    let resources = Router::new()
+        .route(
+            "/api/v1/employees",
+            get(employees::list).post(employees::create),
+        )
+        .route(
+            "/api/v1/employees/{id}",
+            get(employees::get)
+                .put(employees::update)
+                .delete(employees::delete),
+        )
+        .route("/api/v1/users", get(users::list))
+        .route("/api/v1/users/{id}", get(users::get))
+        .route("/api/v1/roles", get(roles::list))
+        .route("/api/v1/roles/{id}", get(roles::get))
+        .route_layer(middleware::from_fn(authenticate));
+
+    Router::new()
+        .route("/api/v1/openapi.json", get(openapi_json))
+        .merge(resources)
+}
+
+async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+//
+// -------------------- AUTH --------------------
+//
+
+/// Resolve the request's session token and hand the user to the handler as
+/// an `Extension<SessionUser>`
+async fn authenticate(mut request: Request, next: Next) -> Result<Response, ApiError> {
+    let token = session::request_token(request.headers()).ok_or(ApiError::Unauthorized)?;
+    let user = session::resolve(&db().await?, &token)
+        .await?
+        .ok_or(ApiError::Unauthorized)?;
+    request.extensions_mut().insert(user);
+    Ok(next.run(request).await)
+}
+
+/// Fail with `403` unless `user`'s role grants `permission`
+pub fn require(user: &SessionUser, permission: &str) -> Result<(), ApiError> {
+    if user.can(permission) {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(format!(
+            "This needs the {} permission",
+            permission
+        )))
+    }
+}
+
+//
+// -------------------- PAGING --------------------
+//
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageQuery {
+    /// 1-based page number (default 1)
+    pub page: Option<u64>,
+    /// Items per page (default 20, max 100)
+    pub per_page: Option<u64>,
+}
+
+impl PageQuery {
+    pub fn page(&self) -> u64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn per_page(&self) -> u64 {
+        self.per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+
+    /// Rows to skip. Absurd page numbers saturate instead of overflowing and
+    /// stay within the `BIGINT` that SQL accepts, so they return an empty page.
+    pub fn offset(&self) -> u64 {
+        self.page()
+            .saturating_sub(1)
+            .saturating_mul(self.per_page())
+            .min(i64::MAX as u64)
+    }
+}
+
+//
+// -------------------- ETAGS --------------------
+//
+
+/// Weak ETag derived from the serialized representation
+pub fn etag_of<T: Serialize>(value: &T) -> String {
+    let bytes = serde_json::to_vec(value).unwrap_or_default();
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    format!("W/\"{:016x}\"", hasher.finish())
+}
+
+fn header_matches(headers: &HeaderMap, name: header::HeaderName, etag: &str) -> Option<bool> {
+    let value = headers.get(name)?.to_str().ok()?;
+    Some(
+        value
+            .split(',')
+            .map(str::trim)
+            .any(|candidate| candidate == "*" || candidate == etag),
+    )
+}
+
+/// Respond with `200` and an ETag, or `304` when `If-None-Match` matches
+pub fn json_with_etag<T: Serialize>(headers: &HeaderMap, value: T) -> Response {
+    let etag = etag_of(&value);
+    let etag_header = HeaderValue::from_str(&etag).expect("ETag is valid ASCII");
+
+    if header_matches(headers, header::IF_NONE_MATCH, &etag) == Some(true) {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag_header)]).into_response();
+    }
+
+    ([(header::ETAG, etag_header)], Json(value)).into_response()
+}
+
+/// Reject the request with `412` when `If-Match` is present and stale
+pub fn check_if_match<T: Serialize>(headers: &HeaderMap, current: &T) -> Result<(), ApiError> {
+    match header_matches(headers, header::IF_MATCH, &etag_of(current)) {
+        Some(false) => Err(ApiError::PreconditionFailed),
+        _ => Ok(()),
+    }
+}
+
+//
+// -------------------- ERRORS --------------------
+//
+
+/// RFC 7807 problem details
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Problem {
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    /// No session token, or an invalid, expired or revoked one
+    Unauthorized,
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    Unprocessable(String),
+    PreconditionFailed,
+    Internal(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, detail) = match self {
+            ApiError::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                "Sign in and send the session token".to_string(),
+            ),
+            ApiError::Forbidden(d) => (StatusCode::FORBIDDEN, d),
+            ApiError::NotFound(d) => (StatusCode::NOT_FOUND, d),
+            ApiError::Conflict(d) => (StatusCode::CONFLICT, d),
+            ApiError::Unprocessable(d) => (StatusCode::UNPROCESSABLE_ENTITY, d),
+            ApiError::PreconditionFailed => (
+                StatusCode::PRECONDITION_FAILED,
+                "The resource was modified since it was fetched".to_string(),
+            ),
+            ApiError::Internal(d) => (StatusCode::INTERNAL_SERVER_ERROR, d),
+        };
+
+        let problem = Problem {
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail,
+        };
+
+        let mut response = (status, Json(problem)).into_response();
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/problem+json"),
+        );
+        if status == StatusCode::UNAUTHORIZED {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        }
+        response
+    }
+}
+
+impl From<DirectoryError> for ApiError {
+    fn from(e: DirectoryError) -> Self {
+        match e {
+            DirectoryError::EmployeeNotFound(_)
+            | DirectoryError::UserNotFound(_)
+            | DirectoryError::RoleNotFound(_)
+            | DirectoryError::NoAccount(_) => ApiError::NotFound(e.to_string()),
+            DirectoryError::EmailConflict(_) | DirectoryError::EmployeeAlreadyLinked(..) => {
+                ApiError::Conflict(e.to_string())
+            }
+            DirectoryError::DeactivateSelf | DirectoryError::RoleNotGrantable(_) => {
+                ApiError::Unprocessable(e.to_string())
+            }
+            DirectoryError::Db(e) => e.into(),
+        }
+    }
+}
+
+impl From<DbErr> for ApiError {
+    fn from(e: DbErr) -> Self {
+        if let Some(SqlErr::UniqueConstraintViolation(msg)) = e.sql_err() {
+            return ApiError::Conflict(msg);
+        }
+        ApiError::Internal(format!("Query failed: {}", e))
+    }
+}
+
+pub async fn db() -> Result<DatabaseConnection, ApiError> {
+    get_db()
+        .await
+        .map_err(|e| ApiError::Internal(format!("DB connection failed: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::{Body, to_bytes};
+    use axum::http::Method;
+    use sea_orm::*;
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::entities::{app_role, users};
+    use crate::server::auth::{ROLE_ADMIN, ROLE_HR, ROLE_MANAGER};
+    use crate::server::testing::{Fixtures, TestDb};
+
+    enum Auth<'a> {
+        None,
+        Bearer(&'a str),
+        Cookie(&'a str),
+    }
+
+    async fn send(method: Method, uri: &str, auth: Auth<'_>, body: Option<Value>) -> Response {
+        let mut request = Request::builder().method(method).uri(uri);
+        request = match auth {
+            Auth::None => request,
+            Auth::Bearer(token) => {
+                request.header(header::AUTHORIZATION, format!("Bearer {}", token))
+            }
+            Auth::Cookie(token) => {
+                request.header(header::COOKIE, format!("{}={}", session::COOKIE, token))
+            }
+        };
+        let body = match body {
+            Some(json) => {
+                request = request.header(header::CONTENT_TYPE, "application/json");
+                Body::from(json.to_string())
+            }
+            None => Body::empty(),
+        };
+        router().oneshot(request.body(body).unwrap()).await.unwrap()
+    }
+
+    async fn json_of(response: Response) -> Value {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "sqlite"),
+        ignore = "requires the sqlite feature or TEST_DATABASE_URL"
+    )]
+    async fn resources_need_a_session_token() {
+        let t = TestDb::new().await;
+        let fx = t.fixtures().await;
+        let token = session::encode(&Fixtures::session(&fx.admin, ROLE_ADMIN));
+
+        t.run(async {
+            let response = send(Method::GET, "/api/v1/employees", Auth::None, None).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+
+            let mut forged = token.clone();
+            forged.pop();
+            let response = send(Method::GET, "/api/v1/users", Auth::Bearer(&forged), None).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+            let response = send(Method::GET, "/api/v1/users", Auth::Bearer(&token), None).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(json_of(response).await["total"], 2);
+
+            let response = send(Method::GET, "/api/v1/roles", Auth::Cookie(&token), None).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            // Past the last page, however far
+            let uri = format!("/api/v1/employees?page={}&per_page=100", u64::MAX);
+            let response = send(Method::GET, &uri, Auth::Bearer(&token), None).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(json_of(response).await["items"], json!([]));
+
+            // The API description stays public
+            let response = send(Method::GET, "/api/v1/openapi.json", Auth::None, None).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        })
+        .await;
+
+        t.finish().await;
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "sqlite"),
+        ignore = "requires the sqlite feature or TEST_DATABASE_URL"
+    )]
+    async fn search_matches_wildcards_literally() {
+        let t = TestDb::new().await;
+        let fx = t.fixtures().await;
+        let token = session::encode(&Fixtures::session(&fx.admin, ROLE_ADMIN));
+
+        t.run(async {
+            let total = |uri: &'static str| {
+                let token = token.clone();
+                async move {
+                    let response = send(Method::GET, uri, Auth::Bearer(&token), None).await;
+                    assert_eq!(response.status(), StatusCode::OK);
+                    json_of(response).await["total"].clone()
+                }
+            };
+            assert_eq!(total("/api/v1/employees?q=LOVE").await, 1);
+            assert_eq!(total("/api/v1/employees?q=%25").await, 0);
+            assert_eq!(total("/api/v1/employees?q=_").await, 0);
+            assert_eq!(total("/api/v1/users?q=ADA").await, 1);
+            assert_eq!(total("/api/v1/users?q=%25").await, 0);
+            assert_eq!(total("/api/v1/users?q=a_").await, 0);
+        })
+        .await;
+
+        t.finish().await;
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "sqlite"),
+        ignore = "requires the sqlite feature or TEST_DATABASE_URL"
+    )]
+    async fn writes_need_employee_write_on_the_current_role() {
+        let t = TestDb::new().await;
+        let fx = t.fixtures().await;
+        let token = session::encode(&Fixtures::session(&fx.ada_user, ROLE_HR));
+        let grace = json!({
+            "first_name": "Grace",
+            "last_name": "Hopper",
+            "email": "grace@example.com",
+        });
+        let alan = json!({
+            "first_name": "Alan",
+            "last_name": "Turing",
+            "email": "alan@example.com",
+        });
+
+        t.run(async {
+            let response = send(
+                Method::POST,
+                "/api/v1/employees",
+                Auth::Bearer(&token),
+                Some(grace),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::CONFLICT);
+
+            let response = send(
+                Method::POST,
+                "/api/v1/employees",
+                Auth::Bearer(&token),
+                Some(alan.clone()),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::CREATED);
+            let id = json_of(response).await["id"].as_i64().unwrap();
+
+            // Demoted after signing in: the token still says ROLE_HR
+            let db = get_db().await.unwrap();
+            let manager = app_role::Entity::find()
+                .filter(app_role::Column::Name.eq(ROLE_MANAGER))
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            let mut account: users::ActiveModel = fx.ada_user.clone().into();
+            account.role_id = Set(manager.id);
+            account.update(&db).await.unwrap();
+
+            let uri = format!("/api/v1/employees/{}", id);
+            let response = send(Method::DELETE, &uri, Auth::Bearer(&token), None).await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+            let response = send(Method::GET, &uri, Auth::Bearer(&token), None).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let response = send(Method::GET, "/api/v1/users", Auth::Bearer(&token), None).await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        })
+        .await;
+
+        t.finish().await;
+    }
+
+    #[test]
+    fn openapi_documents_every_resource() {
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let paths = doc["paths"].as_object().unwrap();
+
+        for path in [
+            "/api/v1/employees",
+            "/api/v1/employees/{id}",
+            "/api/v1/users",
+            "/api/v1/users/{id}",
+            "/api/v1/roles",
+            "/api/v1/roles/{id}",
+        ] {
+            assert!(paths.contains_key(path), "missing {}", path);
+        }
+        assert!(doc["openapi"].as_str().unwrap().starts_with("3."));
+        let schemes = &doc["components"]["securitySchemes"];
+        assert_eq!(schemes["bearer"]["scheme"], "bearer");
+        assert_eq!(schemes["cookie"]["in"], "cookie");
+    }
+
+    #[test]
+    fn paging_defaults_clamps_and_saturates() {
+        let page = |page, per_page| PageQuery { page, per_page };
+        assert_eq!(page(None, None).offset(), 0);
+        assert_eq!(page(Some(0), Some(0)).per_page(), 1);
+        assert_eq!(page(Some(3), Some(500)).offset(), 2 * MAX_PER_PAGE);
+        assert_eq!(page(Some(u64::MAX), Some(100)).offset(), i64::MAX as u64);
+    }
+
+    #[test]
+    fn etag_changes_with_content() {
+        let a = Role {
+            id: 1,
+            name: "ROLE_ADMIN".into(),
+        };
+        let b = Role {
+            id: 1,
+            name: "ROLE_HR".into(),
+        };
+        assert_eq!(etag_of(&a), etag_of(&a.clone()));
+        assert_ne!(etag_of(&a), etag_of(&b));
+    }
+
+    #[test]
+    fn if_match_rejects_stale_etag() {
+        let role = Role {
+            id: 1,
+            name: "ROLE_ADMIN".into(),
+        };
+
+        let mut headers = HeaderMap::new();
+        assert!(check_if_match(&headers, &role).is_ok());
+
+        headers.insert(header::IF_MATCH, HeaderValue::from_static("W/\"stale\""));
+        assert!(matches!(
+            check_if_match(&headers, &role),
+            Err(ApiError::PreconditionFailed)
+        ));
+
+        headers.insert(
+            header::IF_MATCH,
+            HeaderValue::from_str(&etag_of(&role)).unwrap(),
+        );
+        assert!(check_if_match(&headers, &role).is_ok());
+    }
+}
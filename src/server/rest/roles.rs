@@ -0,0 +1,69 @@
+//! `/api/v1/roles` (read-only)
+
+use axum::{Extension, extract::Path, http::HeaderMap, response::Response};
+use sea_orm::*;
+
+use super::{ApiError, Problem, db, json_with_etag, require};
+use crate::server::auth::{SessionUser, USER_READ};
+use crate::server::db::{Role, app_role};
+
+/// List all roles
+#[utoipa::path(
+    get,
+    path = "/api/v1/roles",
+    tag = "roles",
+    responses(
+        (status = 200, description = "All roles", body = Vec<Role>),
+        (status = 304, description = "Not modified (If-None-Match)"),
+        (status = 401, description = "No valid session token", body = Problem),
+        (status = 403, description = "Missing permission", body = Problem),
+    )
+)]
+pub async fn list(
+    Extension(user): Extension<SessionUser>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    require(&user, USER_READ)?;
+    let db = db().await?;
+
+    let roles: Vec<Role> = app_role::Entity::find()
+        .order_by_asc(app_role::Column::Id)
+        .all(&db)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(json_with_etag(&headers, roles))
+}
+
+/// Get one role
+#[utoipa::path(
+    get,
+    path = "/api/v1/roles/{id}",
+    tag = "roles",
+    params(("id" = i32, Path, description = "Role id")),
+    responses(
+        (status = 200, description = "The role", body = Role),
+        (status = 304, description = "Not modified (If-None-Match)"),
+        (status = 404, description = "Unknown role", body = Problem),
+        (status = 401, description = "No valid session token", body = Problem),
+        (status = 403, description = "Missing permission", body = Problem),
+    )
+)]
+pub async fn get(
+    Extension(user): Extension<SessionUser>,
+    headers: HeaderMap,
+    Path(id): Path<i32>,
+) -> Result<Response, ApiError> {
+    require(&user, USER_READ)?;
+    let db = db().await?;
+
+    let role: Role = app_role::Entity::find_by_id(id)
+        .one(&db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Role {} not found", id)))?
+        .into();
+
+    Ok(json_with_etag(&headers, role))
+}
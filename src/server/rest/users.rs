@@ -0,0 +1,172 @@
+//! `/api/v1/users` (read-only)
+
+use std::collections::HashMap;
+
+use axum::{
+    Extension,
+    extract::{Path, Query},
+    http::HeaderMap,
+    response::Response,
+};
+use sea_orm::sea_query::{Expr, Func};
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{ApiError, PageQuery, Problem, db, json_with_etag, require};
+use crate::entities::{app_role, employee, users};
+use crate::server::auth::{SessionUser, USER_READ};
+use crate::server::db::UserExtended;
+use crate::server::search::contains;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UserFilter {
+    /// Case-insensitive substring of the email
+    pub q: Option<String>,
+    /// Role name, e.g. `ROLE_HR`
+    pub role: Option<String>,
+    /// Only users with (`true`) or without (`false`) a linked employee
+    pub linked: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserPage {
+    pub items: Vec<UserExtended>,
+    pub page: u64,
+    pub per_page: u64,
+    pub total: u64,
+}
+
+fn to_dto(
+    user: users::Model,
+    employee: Option<employee::Model>,
+    roles: &HashMap<i32, String>,
+) -> UserExtended {
+    UserExtended {
+        user_id: user.id,
+        email: user.email,
+        role_name: roles.get(&user.role_id).cloned().unwrap_or_default(),
+        employee_id: user.employee_id,
+        employee_name: employee.map(|e| {
+            format!(
+                "{} {}",
+                e.first_name.unwrap_or_default(),
+                e.last_name.unwrap_or_default()
+            )
+        }),
+        is_active: user.is_active,
+        deactivated_at: user.deactivated_at.map(|t| t.to_rfc3339()),
+    }
+}
+
+async fn role_names(db: &DatabaseConnection) -> Result<HashMap<i32, String>, ApiError> {
+    Ok(app_role::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|r| (r.id, r.name))
+        .collect())
+}
+
+/// List users
+#[utoipa::path(
+    get,
+    path = "/api/v1/users",
+    tag = "users",
+    params(UserFilter, PageQuery),
+    responses(
+        (status = 200, description = "A page of users", body = UserPage),
+        (status = 304, description = "Not modified (If-None-Match)"),
+        (status = 401, description = "No valid session token", body = Problem),
+        (status = 403, description = "Missing permission", body = Problem),
+    )
+)]
+pub async fn list(
+    Extension(user): Extension<SessionUser>,
+    headers: HeaderMap,
+    Query(filter): Query<UserFilter>,
+    Query(page): Query<PageQuery>,
+) -> Result<Response, ApiError> {
+    require(&user, USER_READ)?;
+    let db = db().await?;
+    let roles = role_names(&db).await?;
+
+    let mut query = users::Entity::find();
+    if let Some(q) = filter.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        query = query.filter(
+            Expr::expr(Func::lower(Expr::col((
+                users::Entity,
+                users::Column::Email,
+            ))))
+            .like(contains(&q.to_lowercase())),
+        );
+    }
+    if let Some(role) = filter.role {
+        let role_id = roles
+            .iter()
+            .find(|(_, name)| **name == role)
+            .map(|(id, _)| *id);
+        // An unknown role matches nobody
+        query = query.filter(users::Column::RoleId.eq(role_id.unwrap_or(-1)));
+    }
+    match filter.linked {
+        Some(true) => query = query.filter(users::Column::EmployeeId.is_not_null()),
+        Some(false) => query = query.filter(users::Column::EmployeeId.is_null()),
+        None => {}
+    }
+
+    let total = query.clone().count(&db).await?;
+    let rows = query
+        .find_also_related(employee::Entity)
+        .order_by_asc(users::Column::Id)
+        .offset(page.offset())
+        .limit(page.per_page())
+        .all(&db)
+        .await?;
+
+    Ok(json_with_etag(
+        &headers,
+        UserPage {
+            items: rows
+                .into_iter()
+                .map(|(u, e)| to_dto(u, e, &roles))
+                .collect(),
+            page: page.page(),
+            per_page: page.per_page(),
+            total,
+        },
+    ))
+}
+
+/// Get one user
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{id}",
+    tag = "users",
+    params(("id" = i32, Path, description = "User id")),
+    responses(
+        (status = 200, description = "The user", body = UserExtended),
+        (status = 304, description = "Not modified (If-None-Match)"),
+        (status = 404, description = "Unknown user", body = Problem),
+        (status = 401, description = "No valid session token", body = Problem),
+        (status = 403, description = "Missing permission", body = Problem),
+    )
+)]
+pub async fn get(
+    Extension(user): Extension<SessionUser>,
+    headers: HeaderMap,
+    Path(id): Path<i32>,
+) -> Result<Response, ApiError> {
+    require(&user, USER_READ)?;
+    let db = db().await?;
+    let roles = role_names(&db).await?;
+
+    let (user, employee) = users::Entity::find_by_id(id)
+        .find_also_related(employee::Entity)
+        .one(&db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("User {} not found", id)))?;
+
+    Ok(json_with_etag(&headers, to_dto(user, employee, &roles)))
+}
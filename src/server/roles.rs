@@ -0,0 +1,263 @@
+// src/server/roles.rs
+// Roles and what they may do. Roles are rows in `app_role`; their
+// permissions (codes from `auth::PERMISSIONS`) are rows in `role_permission`.
+// Server functions check them with `session::require_permission`.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use sea_orm::*;
+
+#[cfg(feature = "server")]
+use crate::entities::{app_role, role_permission};
+
+#[cfg(feature = "server")]
+use crate::i18n::Translate;
+
+#[cfg(feature = "server")]
+use crate::server::auth::{PERMISSIONS, ROLE_MANAGE, USER_MANAGE};
+
+#[cfg(feature = "server")]
+use crate::server::db_connection::get_db;
+
+#[cfg(feature = "server")]
+use crate::server::session::{AccessError, require_permission, require_permissions};
+
+//
+// -------------------- DTOs --------------------
+//
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Role {
+    pub id: i32,
+    pub name: String,
+    /// In `PERMISSIONS` order
+    pub permissions: Vec<String>,
+}
+
+#[cfg(feature = "server")]
+impl Role {
+    fn new(role: app_role::Model, grants: &[role_permission::Model]) -> Self {
+        Self {
+            id: role.id,
+            name: role.name,
+            permissions: PERMISSIONS
+                .iter()
+                .filter(|p| grants.iter().any(|g| g.permission == **p))
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[derive(Debug)]
+pub enum RoleError {
+    NotFound(i32),
+    NameTaken(String),
+    UnknownPermission(String),
+    /// The caller does not hold this permission, so cannot grant it
+    NotGrantable(String),
+    /// Taking `role.manage` from one's own role would leave nobody to give
+    /// it back
+    OwnRoleLockout,
+    Db(DbErr),
+}
+
+#[cfg(feature = "server")]
+impl Translate for RoleError {
+    fn key(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) => "error.role_not_found",
+            Self::NameTaken(_) => "error.role_name_taken",
+            Self::UnknownPermission(_) => "error.unknown_permission",
+            Self::NotGrantable(_) => "error.permission_not_grantable",
+            Self::OwnRoleLockout => "error.own_role_lockout",
+            Self::Db(_) => "error.query_failed",
+        }
+    }
+
+    fn args(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::NotFound(id) => vec![("role", id.to_string())],
+            Self::NameTaken(name) => vec![("role", name.clone())],
+            Self::UnknownPermission(code) | Self::NotGrantable(code) => {
+                vec![("permission", code.clone())]
+            }
+            Self::OwnRoleLockout => Vec::new(),
+            Self::Db(e) => vec![("error", e.to_string())],
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl From<DbErr> for RoleError {
+    fn from(e: DbErr) -> Self {
+        Self::Db(e)
+    }
+}
+
+//
+// -------------------- SERVER FUNCTIONS --------------------
+//
+
+/// Every role with its permissions, for the Roles page and the user editor
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("get_roles_server"))]
+pub async fn get_roles_server() -> Result<Vec<Role>, ServerFnError> {
+    let viewer = require_permissions().await?;
+    if !viewer.can(ROLE_MANAGE) && !viewer.can(USER_MANAGE) {
+        return Err(AccessError::PermissionDenied.into());
+    }
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let roles = app_role::Entity::find()
+        .find_with_related(role_permission::Entity)
+        .order_by_asc(app_role::Column::Id)
+        .all(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?;
+
+    Ok(roles
+        .into_iter()
+        .map(|(role, grants)| Role::new(role, &grants))
+        .collect())
+}
+
+/// Add a role without permissions
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("create_role"))]
+pub async fn create_role(name: String) -> Result<Role, ServerFnError> {
+    require_permission(ROLE_MANAGE).await?;
+    let name = name.trim().to_string();
+    crate::validation::role(&name).into_result()?;
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    insert_role(&db, name)
+        .await
+        .map_err(|e| crate::i18n::server_error(&e))
+}
+
+/// Replace a role's permissions with `permissions`
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("set_role_permissions"))]
+pub async fn set_role_permissions(
+    role_id: i32,
+    permissions: Vec<String>,
+) -> Result<Role, ServerFnError> {
+    let viewer = require_permission(ROLE_MANAGE).await?;
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    replace_permissions(&db, &viewer.role, &viewer.permissions, role_id, permissions)
+        .await
+        .map_err(|e| crate::i18n::server_error(&e))
+}
+
+//
+// -------------------- QUERIES --------------------
+//
+
+/// The permission codes granted to the role called `role`
+#[cfg(feature = "server")]
+pub async fn permissions_of<C: ConnectionTrait>(db: &C, role: &str) -> Result<Vec<String>, DbErr> {
+    let grants = role_permission::Entity::find()
+        .inner_join(app_role::Entity)
+        .filter(app_role::Column::Name.eq(role))
+        .all(db)
+        .await?;
+    Ok(grants.into_iter().map(|g| g.permission).collect())
+}
+
+#[cfg(feature = "server")]
+async fn insert_role<C: ConnectionTrait>(db: &C, name: String) -> Result<Role, RoleError> {
+    let taken = app_role::Entity::find()
+        .filter(app_role::Column::Name.eq(&name))
+        .one(db)
+        .await?;
+    if taken.is_some() {
+        return Err(RoleError::NameTaken(name));
+    }
+
+    let created = app_role::ActiveModel {
+        name: Set(name),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    Ok(Role::new(created, &[]))
+}
+
+/// Swap the grants in one transaction. `viewer_role` is the caller's, which
+/// must keep `role.manage`. Permissions the role does not have yet must be
+/// in `granter` (the caller's own); otherwise `role.manage` alone could hand
+/// out every permission, even to one's own role.
+#[cfg(feature = "server")]
+async fn replace_permissions<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    viewer_role: &str,
+    granter: &[String],
+    role_id: i32,
+    mut permissions: Vec<String>,
+) -> Result<Role, RoleError> {
+    if let Some(unknown) = permissions
+        .iter()
+        .find(|p| !PERMISSIONS.contains(&p.as_str()))
+    {
+        return Err(RoleError::UnknownPermission(unknown.clone()));
+    }
+    permissions.sort();
+    permissions.dedup();
+
+    let txn = db.begin().await?;
+
+    let role = app_role::Entity::find_by_id(role_id)
+        .one(&txn)
+        .await?
+        .ok_or(RoleError::NotFound(role_id))?;
+    if role.name == viewer_role && !permissions.iter().any(|p| p == ROLE_MANAGE) {
+        return Err(RoleError::OwnRoleLockout);
+    }
+    let current = role_permission::Entity::find()
+        .filter(role_permission::Column::RoleId.eq(role_id))
+        .all(&txn)
+        .await?;
+    if let Some(added) = permissions
+        .iter()
+        .find(|p| !current.iter().any(|g| g.permission == **p) && !granter.contains(p))
+    {
+        return Err(RoleError::NotGrantable(added.clone()));
+    }
+
+    role_permission::Entity::delete_many()
+        .filter(role_permission::Column::RoleId.eq(role_id))
+        .exec(&txn)
+        .await?;
+    role_permission::Entity::insert_many(permissions.iter().map(|p| {
+        role_permission::ActiveModel {
+            role_id: Set(role_id),
+            permission: Set(p.clone()),
+        }
+    }))
+    .do_nothing()
+    .exec(&txn)
+    .await?;
+
+    txn.commit().await?;
+    let grants: Vec<role_permission::Model> = permissions
+        .into_iter()
+        .map(|permission| role_permission::Model {
+            role_id,
+            permission,
+        })
+        .collect();
+    Ok(Role::new(role, &grants))
+}
@@ -0,0 +1,196 @@
+//! Parser for the SCIM filter syntax (RFC 7644, section 3.4.2.2).
+//!
+//! Supports attribute comparisons (`eq`, `ne`, `co`, `sw`, `ew`, `gt`, `ge`,
+//! `lt`, `le`), presence (`pr`), `and` / `or` / `not` and parentheses.
+//! Value paths such as `emails[type eq "work"]` are not supported.
+
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Co,
+    Sw,
+    Ew,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Filter {
+    Compare {
+        attr: String,
+        op: CompareOp,
+        value: Value,
+    },
+    Present(String),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    LParen,
+    RParen,
+}
+
+/// Parse a filter expression, returning a human readable message on failure.
+pub fn parse(input: &str) -> Result<Filter, String> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let filter = parser.parse_or()?;
+
+    if parser.pos != parser.tokens.len() {
+        return Err(format!("Unexpected token at position {}", parser.pos));
+    }
+
+    Ok(filter)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            '"' => {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => s.push(escaped),
+                            None => return Err("Unterminated string".into()),
+                        },
+                        Some('"') => break,
+                        Some(ch) => s.push(ch),
+                        None => return Err("Unterminated string".into()),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || ch == '(' || ch == ')' || ch == '"' {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Filter, String> {
+        let mut left = self.parse_and()?;
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = Filter::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Filter, String> {
+        let mut left = self.parse_unary()?;
+        while self.peek_keyword("and") {
+            self.pos += 1;
+            let right = self.parse_unary()?;
+            left = Filter::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Filter, String> {
+        if self.peek_keyword("not") {
+            self.pos += 1;
+            return Ok(Filter::Not(Box::new(self.parse_unary()?)));
+        }
+
+        match self.next() {
+            Some(Token::LParen) => {
+                let inner = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(inner),
+                    _ => Err("Missing closing parenthesis".into()),
+                }
+            }
+            Some(Token::Word(attr)) => self.parse_comparison(attr),
+            _ => Err("Expected attribute name".into()),
+        }
+    }
+
+    fn parse_comparison(&mut self, attr: String) -> Result<Filter, String> {
+        let op = match self.next() {
+            Some(Token::Word(op)) => op.to_ascii_lowercase(),
+            _ => return Err(format!("Expected operator after '{}'", attr)),
+        };
+
+        let op = match op.as_str() {
+            "pr" => return Ok(Filter::Present(attr)),
+            "eq" => CompareOp::Eq,
+            "ne" => CompareOp::Ne,
+            "co" => CompareOp::Co,
+            "sw" => CompareOp::Sw,
+            "ew" => CompareOp::Ew,
+            "gt" => CompareOp::Gt,
+            "ge" => CompareOp::Ge,
+            "lt" => CompareOp::Lt,
+            "le" => CompareOp::Le,
+            other => return Err(format!("Unknown operator '{}'", other)),
+        };
+
+        let value = match self.next() {
+            Some(Token::Str(s)) => Value::String(s),
+            Some(Token::Word(w)) => match w.as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "null" => Value::Null,
+                number => serde_json::from_str::<serde_json::Number>(number)
+                    .map(Value::Number)
+                    .map_err(|_| format!("Invalid comparison value '{}'", number))?,
+            },
+            _ => return Err(format!("Expected value after '{} {:?}'", attr, op)),
+        };
+
+        Ok(Filter::Compare { attr, op, value })
+    }
+}
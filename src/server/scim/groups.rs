@@ -0,0 +1,493 @@
+//! `/scim/v2/Groups`: SCIM groups backed by `app_role`.
+//!
+//! Every user has exactly one role, so group membership is `users.role_id`.
+//! Adding a member moves the user into the group; removing a member moves
+//! the user back to the default role (removals from the default role are
+//! ignored).
+
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    Json,
+    extract::{Path, Query},
+    http::StatusCode,
+};
+use sea_orm::sea_query::{Expr, SimpleExpr};
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::filter::{self, CompareOp, Filter};
+use super::{
+    Attr, DEFAULT_ROLE, ListQuery, ListResponse, PatchKind, PatchRequest, ScimError, ScimJson, db,
+    normalize_attr, parse_id, value_as_string,
+};
+use crate::entities::{app_role, users};
+
+pub const SCHEMA_GROUP: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+
+//
+// -------------------- RESOURCE --------------------
+//
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroup {
+    schemas: Vec<&'static str>,
+    id: String,
+    display_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    members: Option<Vec<ScimMember>>,
+    meta: ScimGroupMeta,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScimMember {
+    value: String,
+    display: String,
+    #[serde(rename = "$ref")]
+    reference: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroupMeta {
+    resource_type: &'static str,
+    location: String,
+}
+
+fn to_resource(role: app_role::Model, members: Option<Vec<users::Model>>) -> ScimGroup {
+    ScimGroup {
+        schemas: vec![SCHEMA_GROUP],
+        id: role.id.to_string(),
+        display_name: role.name,
+        members: members.map(|list| {
+            list.into_iter()
+                .map(|u| ScimMember {
+                    value: u.id.to_string(),
+                    display: u.email,
+                    reference: format!("/scim/v2/Users/{}", u.id),
+                })
+                .collect()
+        }),
+        meta: ScimGroupMeta {
+            resource_type: "Group",
+            location: format!("/scim/v2/Groups/{}", role.id),
+        },
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroupInput {
+    display_name: String,
+    members: Option<Value>,
+}
+
+//
+// -------------------- QUERIES --------------------
+//
+
+fn group_attr(attr: &str) -> Option<Attr> {
+    let col = |c: app_role::Column| -> SimpleExpr { Expr::col((app_role::Entity, c)).into() };
+
+    match attr {
+        "id" => Some(Attr::Int(col(app_role::Column::Id))),
+        "displayname" => Some(Attr::Text(col(app_role::Column::Name))),
+        _ => None,
+    }
+}
+
+async fn load<C: ConnectionTrait>(db: &C, id: i32) -> Result<app_role::Model, ScimError> {
+    app_role::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ScimError::not_found(format!("Group {} not found", id)))
+}
+
+async fn members_of<C: ConnectionTrait>(
+    db: &C,
+    role_ids: &[i32],
+) -> Result<HashMap<i32, Vec<users::Model>>, ScimError> {
+    let mut members: HashMap<i32, Vec<users::Model>> = HashMap::new();
+    for user in users::Entity::find()
+        .filter(users::Column::RoleId.is_in(role_ids.iter().copied()))
+        .order_by_asc(users::Column::Id)
+        .all(db)
+        .await?
+    {
+        members.entry(user.role_id).or_default().push(user);
+    }
+    Ok(members)
+}
+
+async fn load_resource<C: ConnectionTrait>(db: &C, id: i32) -> Result<ScimGroup, ScimError> {
+    let role = load(db, id).await?;
+    let members = members_of(db, &[id]).await?.remove(&id).unwrap_or_default();
+    Ok(to_resource(role, Some(members)))
+}
+
+async fn default_role_id<C: ConnectionTrait>(db: &C) -> Result<i32, ScimError> {
+    app_role::Entity::find()
+        .filter(app_role::Column::Name.eq(DEFAULT_ROLE))
+        .one(db)
+        .await?
+        .map(|r| r.id)
+        .ok_or_else(|| ScimError::internal(format!("Default role {} is missing", DEFAULT_ROLE)))
+}
+
+/// Parse member ids from `[{ "value": "12" }, ...]`
+fn member_ids(value: Option<&Value>) -> Result<Vec<i32>, ScimError> {
+    let items = match value {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(Value::Array(items)) => items.clone(),
+        Some(other) => vec![other.clone()],
+    };
+
+    items
+        .iter()
+        .map(|item| {
+            value_as_string(item)
+                .and_then(|id| id.parse().ok())
+                .ok_or_else(|| {
+                    ScimError::bad_request("invalidValue", format!("Invalid member {}", item))
+                })
+        })
+        .collect()
+}
+
+/// Parse the member id out of a `members[value eq "12"]` path
+fn member_id_from_path(path: &str) -> Result<Option<i32>, ScimError> {
+    let Some(inner) = path
+        .strip_prefix("members[")
+        .and_then(|rest| rest.strip_suffix(']'))
+    else {
+        return Ok(None);
+    };
+
+    match filter::parse(inner) {
+        Ok(Filter::Compare {
+            attr,
+            op: CompareOp::Eq,
+            value,
+        }) if attr.eq_ignore_ascii_case("value") => value_as_string(&value)
+            .and_then(|id| id.parse().ok())
+            .map(Some)
+            .ok_or_else(|| {
+                ScimError::bad_request("invalidValue", format!("Invalid member {}", value))
+            }),
+        _ => Err(ScimError::bad_request(
+            "invalidPath",
+            format!("Unsupported member path '{}'", path),
+        )),
+    }
+}
+
+async fn ensure_users_exist<C: ConnectionTrait>(db: &C, ids: &[i32]) -> Result<(), ScimError> {
+    let found: HashSet<i32> = users::Entity::find()
+        .filter(users::Column::Id.is_in(ids.iter().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|u| u.id)
+        .collect();
+
+    match ids.iter().find(|id| !found.contains(id)) {
+        Some(missing) => Err(ScimError::bad_request(
+            "invalidValue",
+            format!("User {} not found", missing),
+        )),
+        None => Ok(()),
+    }
+}
+
+async fn add_members<C: ConnectionTrait>(
+    db: &C,
+    role_id: i32,
+    ids: &[i32],
+) -> Result<(), ScimError> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    ensure_users_exist(db, ids).await?;
+
+    users::Entity::update_many()
+        .col_expr(users::Column::RoleId, Expr::value(role_id))
+        .filter(users::Column::Id.is_in(ids.iter().copied()))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Move members back to the default role. `None` removes every member.
+async fn remove_members<C: ConnectionTrait>(
+    db: &C,
+    role_id: i32,
+    ids: Option<&[i32]>,
+) -> Result<(), ScimError> {
+    let default_id = default_role_id(db).await?;
+    if role_id == default_id {
+        return Ok(());
+    }
+
+    let mut query = users::Entity::update_many()
+        .col_expr(users::Column::RoleId, Expr::value(default_id))
+        .filter(users::Column::RoleId.eq(role_id));
+    if let Some(ids) = ids {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        query = query.filter(users::Column::Id.is_in(ids.iter().copied()));
+    }
+
+    query.exec(db).await?;
+    Ok(())
+}
+
+async fn replace_members<C: ConnectionTrait>(
+    db: &C,
+    role_id: i32,
+    ids: &[i32],
+) -> Result<(), ScimError> {
+    let current: Vec<i32> = members_of(db, &[role_id])
+        .await?
+        .remove(&role_id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|u| u.id)
+        .collect();
+    let removed: Vec<i32> = current.into_iter().filter(|id| !ids.contains(id)).collect();
+
+    remove_members(db, role_id, Some(removed.as_slice())).await?;
+    add_members(db, role_id, ids).await
+}
+
+async fn rename<C: ConnectionTrait>(
+    db: &C,
+    role: app_role::Model,
+    name: String,
+) -> Result<(), ScimError> {
+    if role.name == name {
+        return Ok(());
+    }
+    if role.name == DEFAULT_ROLE {
+        return Err(ScimError::new(
+            StatusCode::BAD_REQUEST,
+            Some("mutability"),
+            "The default group cannot be renamed",
+        ));
+    }
+
+    let mut model: app_role::ActiveModel = role.into();
+    model.name = Set(name);
+    model.update(db).await?;
+    Ok(())
+}
+
+//
+// -------------------- HANDLERS --------------------
+//
+
+pub async fn list(
+    Query(query): Query<ListQuery>,
+) -> Result<ScimJson<ListResponse<ScimGroup>>, ScimError> {
+    let condition = query.condition(group_attr)?;
+    let db = db().await?;
+
+    let base = app_role::Entity::find().filter(condition);
+    let total = base.clone().count(&db).await?;
+    let roles = base
+        .order_by_asc(app_role::Column::Id)
+        .offset(query.offset())
+        .limit(query.count())
+        .all(&db)
+        .await?;
+
+    let resources = if query.excludes("members") {
+        roles.into_iter().map(|r| to_resource(r, None)).collect()
+    } else {
+        let ids: Vec<i32> = roles.iter().map(|r| r.id).collect();
+        let mut members = members_of(&db, &ids).await?;
+        roles
+            .into_iter()
+            .map(|r| {
+                let list = members.remove(&r.id).unwrap_or_default();
+                to_resource(r, Some(list))
+            })
+            .collect()
+    };
+
+    Ok(ScimJson::ok(ListResponse::new(
+        total,
+        query.start_index(),
+        resources,
+    )))
+}
+
+pub async fn get(
+    Path(id): Path<String>,
+    Query(query): Query<ListQuery>,
+) -> Result<ScimJson<ScimGroup>, ScimError> {
+    let id = parse_id(&id)?;
+    let db = db().await?;
+
+    let resource = if query.excludes("members") {
+        to_resource(load(&db, id).await?, None)
+    } else {
+        load_resource(&db, id).await?
+    };
+
+    Ok(ScimJson::ok(resource))
+}
+
+pub async fn create(Json(input): Json<ScimGroupInput>) -> Result<ScimJson<ScimGroup>, ScimError> {
+    let ids = member_ids(input.members.as_ref())?;
+
+    let db = db().await?;
+    let txn = db.begin().await?;
+
+    let exists = app_role::Entity::find()
+        .filter(app_role::Column::Name.eq(input.display_name.as_str()))
+        .one(&txn)
+        .await?;
+    if exists.is_some() {
+        return Err(ScimError::conflict(format!(
+            "Group {} already exists",
+            input.display_name
+        )));
+    }
+
+    let role = app_role::ActiveModel {
+        id: NotSet,
+        name: Set(input.display_name),
+    }
+    .insert(&txn)
+    .await?;
+
+    add_members(&txn, role.id, &ids).await?;
+
+    let resource = load_resource(&txn, role.id).await?;
+    txn.commit().await?;
+
+    let location = resource.meta.location.clone();
+    Ok(ScimJson::created(location, resource))
+}
+
+pub async fn replace(
+    Path(id): Path<String>,
+    Json(input): Json<ScimGroupInput>,
+) -> Result<ScimJson<ScimGroup>, ScimError> {
+    let id = parse_id(&id)?;
+    let ids = member_ids(input.members.as_ref())?;
+
+    let db = db().await?;
+    let txn = db.begin().await?;
+
+    let role = load(&txn, id).await?;
+    rename(&txn, role, input.display_name).await?;
+    replace_members(&txn, id, &ids).await?;
+
+    let resource = load_resource(&txn, id).await?;
+    txn.commit().await?;
+
+    Ok(ScimJson::ok(resource))
+}
+
+pub async fn patch(
+    Path(id): Path<String>,
+    Json(request): Json<PatchRequest>,
+) -> Result<StatusCode, ScimError> {
+    let id = parse_id(&id)?;
+    request.validate()?;
+
+    let db = db().await?;
+    let txn = db.begin().await?;
+    load(&txn, id).await?;
+
+    for operation in &request.operations {
+        let kind = operation.kind()?;
+        let value = operation.value.as_ref();
+
+        // Without a path the value holds the attributes to change
+        let targets: Vec<(String, Option<&Value>)> = match (&operation.path, value) {
+            (Some(path), _) => vec![(path.clone(), value)],
+            (None, Some(Value::Object(attributes))) => attributes
+                .iter()
+                .map(|(k, v)| (k.clone(), Some(v)))
+                .collect(),
+            (None, _) => {
+                return Err(ScimError::bad_request(
+                    "noTarget",
+                    "Patch operation without a path needs an object value",
+                ));
+            }
+        };
+
+        for (path, value) in targets {
+            let path = normalize_attr(&path);
+
+            if let Some(member) = member_id_from_path(&path)? {
+                if kind != PatchKind::Remove {
+                    return Err(ScimError::bad_request(
+                        "invalidPath",
+                        "Only remove is supported on a filtered member path",
+                    ));
+                }
+                remove_members(&txn, id, Some(&[member][..])).await?;
+                continue;
+            }
+
+            match (path.as_str(), kind) {
+                ("members", PatchKind::Add) => add_members(&txn, id, &member_ids(value)?).await?,
+                ("members", PatchKind::Replace) => {
+                    replace_members(&txn, id, &member_ids(value)?).await?
+                }
+                ("members", PatchKind::Remove) => match value {
+                    None => remove_members(&txn, id, None).await?,
+                    Some(v) => {
+                        remove_members(&txn, id, Some(member_ids(Some(v))?.as_slice())).await?
+                    }
+                },
+                ("displayname", PatchKind::Add | PatchKind::Replace) => {
+                    let name = value.and_then(value_as_string).ok_or_else(|| {
+                        ScimError::bad_request("invalidValue", "displayName must be a string")
+                    })?;
+                    let role = load(&txn, id).await?;
+                    rename(&txn, role, name).await?;
+                }
+                ("displayname", PatchKind::Remove) => {
+                    return Err(ScimError::new(
+                        StatusCode::BAD_REQUEST,
+                        Some("mutability"),
+                        "displayName is required",
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    txn.commit().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn delete(Path(id): Path<String>) -> Result<StatusCode, ScimError> {
+    let id = parse_id(&id)?;
+    let db = db().await?;
+
+    let role = load(&db, id).await?;
+    let member_count = users::Entity::find()
+        .filter(users::Column::RoleId.eq(role.id))
+        .count(&db)
+        .await?;
+    if member_count > 0 {
+        return Err(ScimError::new(
+            StatusCode::CONFLICT,
+            None,
+            format!("Group {} still has {} members", role.name, member_count),
+        ));
+    }
+
+    app_role::Entity::delete_by_id(id).exec(&db).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,557 @@
+//! SCIM 2.0 provisioning endpoint (RFC 7643 / RFC 7644)
+//!
+//! `/scim/v2/Users` maps onto `users` plus the linked `employee`,
+//! `/scim/v2/Groups` maps onto `app_role`. All routes require
+//! `Authorization: Bearer <SCIM_BEARER_TOKEN>`.
+
+pub mod filter;
+mod groups;
+mod users;
+
+#[cfg(test)]
+mod tests;
+
+use axum::{
+    Json, Router,
+    extract::{Request, State},
+    http::{HeaderValue, StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use sea_orm::sea_query::{Expr, Func, SimpleExpr};
+use sea_orm::{Condition, DatabaseConnection, DbErr, SqlErr};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::server::auth::constant_time_eq;
+use crate::server::db_connection::get_db;
+use crate::server::directory::DirectoryError;
+use crate::server::search::{escape_like, like};
+use filter::{CompareOp, Filter};
+
+pub const SCIM_CONTENT_TYPE: &str = "application/scim+json";
+
+const SCHEMA_LIST_RESPONSE: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+const SCHEMA_PATCH_OP: &str = "urn:ietf:params:scim:api:messages:2.0:PatchOp";
+const SCHEMA_ERROR: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+
+/// Role assigned to users that are created without a role or removed from a group
+pub const DEFAULT_ROLE: &str = "ROLE_EMPLOYEE";
+
+/// Largest page a client may request
+const MAX_PAGE_SIZE: u64 = 200;
+
+//
+// -------------------- ROUTER --------------------
+//
+
+/// Build the SCIM router, reading the bearer token from `SCIM_BEARER_TOKEN`
+pub fn router() -> Router {
+    router_with_token(crate::server::config::get().scim.bearer_token.clone())
+}
+
+/// Build the SCIM router with an explicit bearer token.
+/// Without a token every request is rejected.
+pub fn router_with_token(token: Option<String>) -> Router {
+    let token = token.filter(|t| !t.is_empty());
+
+    Router::new()
+        .route("/scim/v2/Users", get(users::list).post(users::create))
+        .route(
+            "/scim/v2/Users/{id}",
+            get(users::get)
+                .put(users::replace)
+                .patch(users::patch)
+                .delete(users::delete),
+        )
+        .route("/scim/v2/Groups", get(groups::list).post(groups::create))
+        .route(
+            "/scim/v2/Groups/{id}",
+            get(groups::get)
+                .put(groups::replace)
+                .patch(groups::patch)
+                .delete(groups::delete),
+        )
+        .route(
+            "/scim/v2/ServiceProviderConfig",
+            get(service_provider_config),
+        )
+        .route("/scim/v2/ResourceTypes", get(resource_types))
+        .layer(middleware::from_fn_with_state(token, require_bearer))
+}
+
+async fn require_bearer(
+    State(expected): State<Option<String>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    match (expected, provided) {
+        (Some(expected), Some(provided))
+            if constant_time_eq(expected.as_bytes(), provided.trim().as_bytes()) =>
+        {
+            next.run(request).await
+        }
+        _ => {
+            let mut response =
+                ScimError::new(StatusCode::UNAUTHORIZED, None, "Invalid bearer token")
+                    .into_response();
+            response.headers_mut().insert(
+                header::WWW_AUTHENTICATE,
+                HeaderValue::from_static("Bearer realm=\"scim\""),
+            );
+            response
+        }
+    }
+}
+
+async fn service_provider_config() -> ScimJson<Value> {
+    ScimJson::ok(json!({
+        "schemas": ["urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig"],
+        "patch": { "supported": true },
+        "bulk": { "supported": false, "maxOperations": 0, "maxPayloadSize": 0 },
+        "filter": { "supported": true, "maxResults": MAX_PAGE_SIZE },
+        "changePassword": { "supported": true },
+        "sort": { "supported": false },
+        "etag": { "supported": false },
+        "authenticationSchemes": [{
+            "type": "oauthbearertoken",
+            "name": "OAuth Bearer Token",
+            "description": "Authentication with a static bearer token",
+            "primary": true
+        }],
+        "meta": { "resourceType": "ServiceProviderConfig", "location": "/scim/v2/ServiceProviderConfig" }
+    }))
+}
+
+async fn resource_types() -> ScimJson<Value> {
+    let resources = vec![
+        json!({
+            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:ResourceType"],
+            "id": "User",
+            "name": "User",
+            "endpoint": "/Users",
+            "schema": users::SCHEMA_USER,
+            "meta": { "resourceType": "ResourceType", "location": "/scim/v2/ResourceTypes/User" }
+        }),
+        json!({
+            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:ResourceType"],
+            "id": "Group",
+            "name": "Group",
+            "endpoint": "/Groups",
+            "schema": groups::SCHEMA_GROUP,
+            "meta": { "resourceType": "ResourceType", "location": "/scim/v2/ResourceTypes/Group" }
+        }),
+    ];
+
+    ScimJson::ok(json!(ListResponse::new(2, 1, resources)))
+}
+
+//
+// -------------------- RESPONSES & ERRORS --------------------
+//
+
+/// JSON response with the `application/scim+json` content type
+pub struct ScimJson<T> {
+    status: StatusCode,
+    location: Option<String>,
+    body: T,
+}
+
+impl<T> ScimJson<T> {
+    pub fn ok(body: T) -> Self {
+        Self {
+            status: StatusCode::OK,
+            location: None,
+            body,
+        }
+    }
+
+    pub fn created(location: String, body: T) -> Self {
+        Self {
+            status: StatusCode::CREATED,
+            location: Some(location),
+            body,
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for ScimJson<T> {
+    fn into_response(self) -> Response {
+        let mut response = (self.status, Json(self.body)).into_response();
+        let headers = response.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(SCIM_CONTENT_TYPE),
+        );
+        if let Some(location) = self.location.and_then(|l| HeaderValue::from_str(&l).ok()) {
+            headers.insert(header::LOCATION, location);
+        }
+        response
+    }
+}
+
+#[derive(Serialize)]
+pub struct ListResponse<T> {
+    schemas: Vec<&'static str>,
+    #[serde(rename = "totalResults")]
+    total_results: u64,
+    #[serde(rename = "startIndex")]
+    start_index: u64,
+    #[serde(rename = "itemsPerPage")]
+    items_per_page: usize,
+    #[serde(rename = "Resources")]
+    resources: Vec<T>,
+}
+
+impl<T> ListResponse<T> {
+    pub fn new(total_results: u64, start_index: u64, resources: Vec<T>) -> Self {
+        Self {
+            schemas: vec![SCHEMA_LIST_RESPONSE],
+            total_results,
+            start_index,
+            items_per_page: resources.len(),
+            resources,
+        }
+    }
+}
+
+/// SCIM error response (RFC 7644, section 3.12)
+#[derive(Debug)]
+pub struct ScimError {
+    status: StatusCode,
+    scim_type: Option<&'static str>,
+    detail: String,
+}
+
+impl ScimError {
+    pub fn new(
+        status: StatusCode,
+        scim_type: Option<&'static str>,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self {
+            status,
+            scim_type,
+            detail: detail.into(),
+        }
+    }
+
+    pub fn bad_request(scim_type: &'static str, detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, Some(scim_type), detail)
+    }
+
+    pub fn not_found(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, None, detail)
+    }
+
+    pub fn conflict(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, Some("uniqueness"), detail)
+    }
+
+    pub fn internal(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, None, detail)
+    }
+}
+
+impl From<DirectoryError> for ScimError {
+    fn from(e: DirectoryError) -> Self {
+        match e {
+            DirectoryError::EmployeeNotFound(_)
+            | DirectoryError::UserNotFound(_)
+            | DirectoryError::RoleNotFound(_)
+            | DirectoryError::NoAccount(_) => Self::not_found(e.to_string()),
+            DirectoryError::EmailConflict(_) | DirectoryError::EmployeeAlreadyLinked(..) => {
+                Self::conflict(e.to_string())
+            }
+            DirectoryError::DeactivateSelf | DirectoryError::RoleNotGrantable(_) => {
+                Self::bad_request("invalidValue", e.to_string())
+            }
+            DirectoryError::Db(e) => e.into(),
+        }
+    }
+}
+
+impl From<DbErr> for ScimError {
+    fn from(e: DbErr) -> Self {
+        if let Some(SqlErr::UniqueConstraintViolation(msg)) = e.sql_err() {
+            return Self::conflict(msg);
+        }
+        Self::internal(format!("Query failed: {}", e))
+    }
+}
+
+impl IntoResponse for ScimError {
+    fn into_response(self) -> Response {
+        let mut body = json!({
+            "schemas": [SCHEMA_ERROR],
+            "status": self.status.as_u16().to_string(),
+            "detail": self.detail,
+        });
+        if let Some(scim_type) = self.scim_type {
+            body["scimType"] = json!(scim_type);
+        }
+
+        let mut response = ScimJson::ok(body).into_response();
+        *response.status_mut() = self.status;
+        response
+    }
+}
+
+//
+// -------------------- SHARED HELPERS --------------------
+//
+
+pub async fn db() -> Result<DatabaseConnection, ScimError> {
+    get_db()
+        .await
+        .map_err(|e| ScimError::internal(format!("DB connection failed: {}", e)))
+}
+
+/// Parse a resource id from the URL, treating malformed ids as missing resources
+pub fn parse_id(id: &str) -> Result<i32, ScimError> {
+    id.parse()
+        .map_err(|_| ScimError::not_found(format!("Resource {} not found", id)))
+}
+
+/// Query parameters accepted by list endpoints
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListQuery {
+    pub filter: Option<String>,
+    pub start_index: Option<u64>,
+    pub count: Option<u64>,
+    pub excluded_attributes: Option<String>,
+}
+
+impl ListQuery {
+    /// 1-based index of the first result
+    pub fn start_index(&self) -> u64 {
+        self.start_index.unwrap_or(1).max(1)
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.unwrap_or(100).min(MAX_PAGE_SIZE)
+    }
+
+    pub fn offset(&self) -> u64 {
+        self.start_index() - 1
+    }
+
+    pub fn excludes(&self, attribute: &str) -> bool {
+        self.excluded_attributes.as_deref().is_some_and(|list| {
+            list.split(',')
+                .any(|a| a.trim().eq_ignore_ascii_case(attribute))
+        })
+    }
+
+    /// Translate the `filter` parameter into a SQL condition
+    pub fn condition(&self, attr: impl Fn(&str) -> Option<Attr>) -> Result<Condition, ScimError> {
+        match self.filter.as_deref().map(str::trim) {
+            None | Some("") => Ok(Condition::all()),
+            Some(raw) => {
+                let parsed =
+                    filter::parse(raw).map_err(|e| ScimError::bad_request("invalidFilter", e))?;
+                to_condition(&parsed, &attr)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PatchRequest {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(rename = "Operations")]
+    pub operations: Vec<PatchOperation>,
+}
+
+impl PatchRequest {
+    pub fn validate(&self) -> Result<(), ScimError> {
+        if !self.schemas.is_empty() && !self.schemas.iter().any(|s| s == SCHEMA_PATCH_OP) {
+            return Err(ScimError::bad_request(
+                "invalidSyntax",
+                "Expected PatchOp schema",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PatchOperation {
+    pub op: String,
+    pub path: Option<String>,
+    pub value: Option<Value>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatchKind {
+    Add,
+    Replace,
+    Remove,
+}
+
+impl PatchOperation {
+    pub fn kind(&self) -> Result<PatchKind, ScimError> {
+        match self.op.to_ascii_lowercase().as_str() {
+            "add" => Ok(PatchKind::Add),
+            "replace" => Ok(PatchKind::Replace),
+            "remove" => Ok(PatchKind::Remove),
+            other => Err(ScimError::bad_request(
+                "invalidSyntax",
+                format!("Unknown patch operation '{}'", other),
+            )),
+        }
+    }
+}
+
+/// Strip a schema URN prefix and lowercase an attribute path
+pub fn normalize_attr(attr: &str) -> String {
+    let attr = if attr.starts_with("urn:") {
+        attr.rsplit(':').next().unwrap_or(attr)
+    } else {
+        attr
+    };
+    attr.to_ascii_lowercase()
+}
+
+/// Read a boolean, accepting the string forms some identity providers send
+pub fn value_as_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) if s.eq_ignore_ascii_case("true") => Some(true),
+        Value::String(s) if s.eq_ignore_ascii_case("false") => Some(false),
+        _ => None,
+    }
+}
+
+/// Read a string, also accepting the first `{ "value": ... }` entry of a multi-valued attribute
+pub fn value_as_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Array(items) => items
+            .iter()
+            .find(|i| i.get("primary").and_then(Value::as_bool) == Some(true))
+            .or_else(|| items.first())
+            .and_then(value_as_string),
+        Value::Object(map) => map.get("value").and_then(value_as_string),
+        _ => None,
+    }
+}
+
+//
+// -------------------- FILTER → SQL --------------------
+//
+
+/// A filterable attribute and the column it maps to
+pub enum Attr {
+    /// Case-insensitive string column
+    Text(SimpleExpr),
+    Int(SimpleExpr),
+    Bool(SimpleExpr),
+}
+
+fn to_condition(
+    filter: &Filter,
+    attr: &impl Fn(&str) -> Option<Attr>,
+) -> Result<Condition, ScimError> {
+    let unknown = |name: &str| {
+        ScimError::bad_request(
+            "invalidFilter",
+            format!("Unsupported filter attribute '{}'", name),
+        )
+    };
+
+    let condition = match filter {
+        Filter::And(a, b) => Condition::all()
+            .add(to_condition(a, attr)?)
+            .add(to_condition(b, attr)?),
+        Filter::Or(a, b) => Condition::any()
+            .add(to_condition(a, attr)?)
+            .add(to_condition(b, attr)?),
+        Filter::Not(inner) => to_condition(inner, attr)?.not(),
+        Filter::Present(name) => match attr(&normalize_attr(name)).ok_or_else(|| unknown(name))? {
+            Attr::Text(col) => Condition::all().add(Expr::expr(col).is_not_null()),
+            Attr::Int(_) | Attr::Bool(_) => Condition::all(),
+        },
+        Filter::Compare {
+            attr: name,
+            op,
+            value,
+        } => {
+            let column = attr(&normalize_attr(name)).ok_or_else(|| unknown(name))?;
+            Condition::all().add(compare(column, *op, value)?)
+        }
+    };
+
+    Ok(condition)
+}
+
+fn compare(column: Attr, op: CompareOp, value: &Value) -> Result<SimpleExpr, ScimError> {
+    let invalid = || {
+        ScimError::bad_request(
+            "invalidFilter",
+            format!("Operator {:?} is not valid for value {}", op, value),
+        )
+    };
+
+    match column {
+        Attr::Text(col) => {
+            if value.is_null() {
+                return match op {
+                    CompareOp::Eq => Ok(Expr::expr(col).is_null()),
+                    CompareOp::Ne => Ok(Expr::expr(col).is_not_null()),
+                    _ => Err(invalid()),
+                };
+            }
+            let v = value_as_string(value).ok_or_else(invalid)?.to_lowercase();
+            let lhs = Expr::expr(Func::lower(col));
+            Ok(match op {
+                CompareOp::Eq => lhs.eq(v),
+                CompareOp::Ne => lhs.ne(v),
+                CompareOp::Co => lhs.like(like(format!("%{}%", escape_like(&v)))),
+                CompareOp::Sw => lhs.like(like(format!("{}%", escape_like(&v)))),
+                CompareOp::Ew => lhs.like(like(format!("%{}", escape_like(&v)))),
+                CompareOp::Gt => lhs.gt(v),
+                CompareOp::Ge => lhs.gte(v),
+                CompareOp::Lt => lhs.lt(v),
+                CompareOp::Le => lhs.lte(v),
+            })
+        }
+        Attr::Int(col) => {
+            let v: i64 = match value {
+                Value::Number(n) => n.as_i64(),
+                Value::String(s) => s.parse().ok(),
+                _ => None,
+            }
+            .ok_or_else(invalid)?;
+            let lhs = Expr::expr(col);
+            match op {
+                CompareOp::Eq => Ok(lhs.eq(v)),
+                CompareOp::Ne => Ok(lhs.ne(v)),
+                CompareOp::Gt => Ok(lhs.gt(v)),
+                CompareOp::Ge => Ok(lhs.gte(v)),
+                CompareOp::Lt => Ok(lhs.lt(v)),
+                CompareOp::Le => Ok(lhs.lte(v)),
+                _ => Err(invalid()),
+            }
+        }
+        Attr::Bool(col) => {
+            let v = value_as_bool(value).ok_or_else(invalid)?;
+            let lhs = Expr::expr(col);
+            match op {
+                CompareOp::Eq => Ok(lhs.eq(v)),
+                CompareOp::Ne => Ok(lhs.ne(v)),
+                _ => Err(invalid()),
+            }
+        }
+    }
+}
@@ -0,0 +1,451 @@
+//! SCIM conformance tests.
+//!
+//! The database tests run on a `TestDb` of their own, so in-memory SQLite
+//! with the `sqlite` feature or a scratch PostgreSQL database with
+//! `TEST_DATABASE_URL`:
+//!
+//! ```bash
+//! cargo test --features sqlite scim
+//! ```
+
+use axum::{
+    Router,
+    body::{Body, to_bytes},
+    http::{Method, Request, StatusCode, header},
+};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+use super::filter::{self, CompareOp, Filter};
+use super::{SCIM_CONTENT_TYPE, router_with_token};
+use crate::server::testing::TestDb;
+
+const TOKEN: &str = "test-token";
+
+fn app() -> Router {
+    router_with_token(Some(TOKEN.to_string()))
+}
+
+async fn send(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", TOKEN));
+
+    let body = match body {
+        Some(json) => {
+            request = request.header(header::CONTENT_TYPE, SCIM_CONTENT_TYPE);
+            Body::from(json.to_string())
+        }
+        None => Body::empty(),
+    };
+
+    let response = app
+        .clone()
+        .oneshot(request.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json = if bytes.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&bytes).unwrap()
+    };
+
+    (status, json)
+}
+
+//
+// -------------------- FILTER PARSER --------------------
+//
+
+#[test]
+fn parses_simple_comparison() {
+    let parsed = filter::parse(r#"userName eq "bjensen@example.com""#).unwrap();
+    assert_eq!(
+        parsed,
+        Filter::Compare {
+            attr: "userName".into(),
+            op: CompareOp::Eq,
+            value: json!("bjensen@example.com"),
+        }
+    );
+}
+
+#[test]
+fn parses_logical_operators_with_precedence() {
+    let parsed =
+        filter::parse(r#"active eq true or userName sw "a" and not (externalId pr)"#).unwrap();
+    match parsed {
+        Filter::Or(left, right) => {
+            assert!(matches!(
+                *left,
+                Filter::Compare {
+                    op: CompareOp::Eq,
+                    ..
+                }
+            ));
+            assert!(matches!(*right, Filter::And(_, _)));
+        }
+        other => panic!("unexpected filter {:?}", other),
+    }
+}
+
+#[test]
+fn rejects_malformed_filters() {
+    assert!(filter::parse("userName eq").is_err());
+    assert!(filter::parse(r#"userName foo "x""#).is_err());
+    assert!(filter::parse(r#"(userName eq "x""#).is_err());
+    assert!(filter::parse(r#"userName eq "unterminated"#).is_err());
+}
+
+//
+// -------------------- AUTHENTICATION --------------------
+//
+
+#[tokio::test]
+async fn rejects_missing_or_wrong_token() {
+    let app = app();
+
+    let response = app
+        .clone()
+        .oneshot(Request::get("/scim/v2/Users").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(response.headers().contains_key(header::WWW_AUTHENTICATE));
+
+    let response = app
+        .oneshot(
+            Request::get("/scim/v2/Users")
+                .header(header::AUTHORIZATION, "Bearer wrong")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn rejects_everything_without_configured_token() {
+    let app = router_with_token(None);
+    let response = app
+        .oneshot(
+            Request::get("/scim/v2/ServiceProviderConfig")
+                .header(header::AUTHORIZATION, "Bearer ")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn serves_service_provider_config() {
+    let (status, body) = send(&app(), Method::GET, "/scim/v2/ServiceProviderConfig", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["patch"]["supported"], json!(true));
+    assert_eq!(body["filter"]["supported"], json!(true));
+}
+
+#[tokio::test]
+async fn invalid_filter_returns_scim_error() {
+    let (status, body) = send(
+        &app(),
+        Method::GET,
+        "/scim/v2/Users?filter=userName%20eq",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["scimType"], json!("invalidFilter"));
+    assert_eq!(body["status"], json!("400"));
+}
+
+//
+// -------------------- USERS (DATABASE) --------------------
+//
+
+#[tokio::test]
+#[cfg_attr(
+    not(feature = "sqlite"),
+    ignore = "requires the sqlite feature or TEST_DATABASE_URL"
+)]
+async fn user_lifecycle() {
+    let t = TestDb::new().await;
+    let app = app();
+    let email = "lifecycle@scim.test";
+    t.run(async {
+        // Create
+        let (status, created) = send(
+            &app,
+            Method::POST,
+            "/scim/v2/Users",
+            Some(json!({
+                "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+                "userName": email,
+                "externalId": format!("ext-{}", email),
+                "name": { "givenName": "Scim", "familyName": "Tester" },
+                "emails": [{ "value": email, "primary": true }],
+                "active": true
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{}", created);
+        let id = created["id"].as_str().unwrap().to_string();
+        assert_eq!(created["userName"], json!(email));
+        assert_eq!(created["name"]["givenName"], json!("Scim"));
+        assert_eq!(created["roles"][0]["value"], json!(super::DEFAULT_ROLE));
+        assert_eq!(created["active"], json!(true));
+
+        // Duplicate userName is a uniqueness conflict
+        let (status, body) = send(
+            &app,
+            Method::POST,
+            "/scim/v2/Users",
+            Some(json!({ "userName": email })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["scimType"], json!("uniqueness"));
+
+        // Filter by userName
+        let uri = format!("/scim/v2/Users?filter=userName%20eq%20%22{}%22", email);
+        let (status, list) = send(&app, Method::GET, &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(list["totalResults"], json!(1));
+        assert_eq!(list["Resources"][0]["id"], json!(id));
+
+        // Filter by linked employee name
+        let uri = format!(
+            "/scim/v2/Users?filter=name.familyName%20eq%20%22tester%22%20and%20userName%20eq%20%22{}%22",
+            email
+        );
+        let (_, list) = send(&app, Method::GET, &uri, None).await;
+        assert_eq!(list["totalResults"], json!(1));
+
+        // PATCH: deactivate and rename (path and path-less forms)
+        let (status, patched) = send(
+            &app,
+            Method::PATCH,
+            &format!("/scim/v2/Users/{}", id),
+            Some(json!({
+                "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+                "Operations": [
+                    { "op": "Replace", "path": "active", "value": "False" },
+                    { "op": "replace", "value": { "name.givenName": "Renamed" } }
+                ]
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", patched);
+        assert_eq!(patched["active"], json!(false));
+        assert_eq!(patched["name"]["givenName"], json!("Renamed"));
+        assert_eq!(patched["name"]["familyName"], json!("Tester"));
+
+        // PUT replaces and reactivates
+        let (status, replaced) = send(
+            &app,
+            Method::PUT,
+            &format!("/scim/v2/Users/{}", id),
+            Some(json!({
+                "userName": email,
+                "name": { "givenName": "Put", "familyName": "Tester" }
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", replaced);
+        assert_eq!(replaced["active"], json!(true));
+        assert_eq!(replaced["externalId"], Value::Null);
+        assert_eq!(replaced["name"]["givenName"], json!("Put"));
+
+        // Delete, then the user is gone
+        let (status, _) = send(
+            &app,
+            Method::DELETE,
+            &format!("/scim/v2/Users/{}", id),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, body) = send(&app, Method::GET, &format!("/scim/v2/Users/{}", id), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["status"], json!("404"));
+    })
+    .await;
+    t.finish().await;
+}
+
+#[tokio::test]
+#[cfg_attr(
+    not(feature = "sqlite"),
+    ignore = "requires the sqlite feature or TEST_DATABASE_URL"
+)]
+async fn list_pagination() {
+    let t = TestDb::new().await;
+    t.fixtures().await;
+    let app = app();
+    t.run(async {
+        let (status, page) = send(
+            &app,
+            Method::GET,
+            "/scim/v2/Users?startIndex=1&count=1",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["startIndex"], json!(1));
+        assert!(page["itemsPerPage"].as_u64().unwrap() <= 1);
+
+        let total = page["totalResults"].as_u64().unwrap();
+        let uri = format!("/scim/v2/Users?startIndex={}&count=10", total + 1);
+        let (_, past_end) = send(&app, Method::GET, &uri, None).await;
+        assert_eq!(past_end["itemsPerPage"], json!(0));
+        assert_eq!(past_end["totalResults"], json!(total));
+    })
+    .await;
+    t.finish().await;
+}
+
+#[tokio::test]
+#[cfg_attr(
+    not(feature = "sqlite"),
+    ignore = "requires the sqlite feature or TEST_DATABASE_URL"
+)]
+async fn substring_filters_match_wildcards_literally() {
+    let t = TestDb::new().await;
+    let app = app();
+    t.run(async {
+        for email in ["a_b@scim.test", "axb@scim.test"] {
+            let (status, _) = send(
+                &app,
+                Method::POST,
+                "/scim/v2/Users",
+                Some(json!({ "userName": email })),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
+
+        let (_, list) = send(
+            &app,
+            Method::GET,
+            "/scim/v2/Users?filter=userName%20co%20%22a_b%22",
+            None,
+        )
+        .await;
+        assert_eq!(list["totalResults"], json!(1));
+        assert_eq!(list["Resources"][0]["userName"], json!("a_b@scim.test"));
+
+        let (_, list) = send(
+            &app,
+            Method::GET,
+            "/scim/v2/Users?filter=userName%20sw%20%22%25%22",
+            None,
+        )
+        .await;
+        assert_eq!(list["totalResults"], json!(0));
+    })
+    .await;
+    t.finish().await;
+}
+
+//
+// -------------------- GROUPS (DATABASE) --------------------
+//
+
+#[tokio::test]
+#[cfg_attr(
+    not(feature = "sqlite"),
+    ignore = "requires the sqlite feature or TEST_DATABASE_URL"
+)]
+async fn group_membership_maps_onto_roles() {
+    let t = TestDb::new().await;
+    let app = app();
+    let email = "groups@scim.test";
+    t.run(async {
+        let (_, user) = send(
+            &app,
+            Method::POST,
+            "/scim/v2/Users",
+            Some(json!({ "userName": email })),
+        )
+        .await;
+        let user_id = user["id"].as_str().unwrap().to_string();
+
+        let (status, groups) = send(
+            &app,
+            Method::GET,
+            "/scim/v2/Groups?filter=displayName%20eq%20%22ROLE_HR%22&excludedAttributes=members",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(groups["totalResults"], json!(1));
+        assert!(groups["Resources"][0].get("members").is_none());
+        let hr_id = groups["Resources"][0]["id"].as_str().unwrap().to_string();
+
+        // Add member
+        let (status, _) = send(
+            &app,
+            Method::PATCH,
+            &format!("/scim/v2/Groups/{}", hr_id),
+            Some(json!({
+                "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+                "Operations": [{ "op": "add", "path": "members", "value": [{ "value": user_id }] }]
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (_, user) = send(
+            &app,
+            Method::GET,
+            &format!("/scim/v2/Users/{}", user_id),
+            None,
+        )
+        .await;
+        assert_eq!(user["roles"][0]["value"], json!("ROLE_HR"));
+        assert_eq!(user["groups"][0]["value"], json!(hr_id));
+
+        // Remove member through a filtered path
+        let (status, _) = send(
+            &app,
+            Method::PATCH,
+            &format!("/scim/v2/Groups/{}", hr_id),
+            Some(json!({
+                "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+                "Operations": [{ "op": "remove", "path": format!("members[value eq \"{}\"]", user_id) }]
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (_, user) = send(
+            &app,
+            Method::GET,
+            &format!("/scim/v2/Users/{}", user_id),
+            None,
+        )
+        .await;
+        assert_eq!(user["roles"][0]["value"], json!(super::DEFAULT_ROLE));
+
+        // Unknown members are rejected
+        let (status, body) = send(
+            &app,
+            Method::PATCH,
+            &format!("/scim/v2/Groups/{}", hr_id),
+            Some(json!({
+                "Operations": [{ "op": "add", "path": "members", "value": [{ "value": "-1" }] }]
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["scimType"], json!("invalidValue"));
+    })
+    .await;
+    t.finish().await;
+}
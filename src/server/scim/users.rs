@@ -0,0 +1,666 @@
+//! `/scim/v2/Users`: SCIM users backed by `users` and the linked `employee`.
+//!
+//! `userName` is the login email. `name.givenName` / `name.familyName` are
+//! stored on the linked employee, which is looked up by email or created
+//! when a user without an employee receives a name.
+
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{Path, Query},
+    http::StatusCode,
+};
+use sea_orm::sea_query::{Expr, SimpleExpr};
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    Attr, DEFAULT_ROLE, ListQuery, ListResponse, PatchKind, PatchRequest, ScimError, ScimJson, db,
+    normalize_attr, parse_id, value_as_bool, value_as_string,
+};
+use crate::entities::{app_role, employee, users};
+use crate::server::auth::{hash_password, random_password};
+use crate::server::directory;
+
+pub const SCHEMA_USER: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+
+//
+// -------------------- RESOURCE --------------------
+//
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUser {
+    schemas: Vec<&'static str>,
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    external_id: Option<String>,
+    user_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<ScimName>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
+    emails: Vec<ScimEmail>,
+    active: bool,
+    roles: Vec<ScimRole>,
+    groups: Vec<ScimGroupRef>,
+    meta: ScimMeta,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimName {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    given_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    family_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScimEmail {
+    value: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    primary: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScimRole {
+    value: String,
+    primary: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScimGroupRef {
+    value: String,
+    display: String,
+    #[serde(rename = "$ref")]
+    reference: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+    resource_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created: Option<String>,
+    location: String,
+}
+
+fn to_resource(
+    user: users::Model,
+    employee: Option<employee::Model>,
+    role_name: String,
+) -> ScimUser {
+    let name = employee.as_ref().map(|e| ScimName {
+        given_name: e.first_name.clone(),
+        family_name: e.last_name.clone(),
+    });
+
+    let display_name =
+        employee.as_ref().and_then(
+            |e| match (e.first_name.as_deref(), e.last_name.as_deref()) {
+                (Some(first), Some(last)) => Some(format!("{} {}", first, last)),
+                (Some(only), None) | (None, Some(only)) => Some(only.to_string()),
+                (None, None) => None,
+            },
+        );
+
+    ScimUser {
+        schemas: vec![SCHEMA_USER],
+        id: user.id.to_string(),
+        external_id: user.external_id,
+        user_name: user.email.clone(),
+        name,
+        display_name,
+        emails: vec![ScimEmail {
+            value: user.email,
+            kind: "work",
+            primary: true,
+        }],
+        active: user.is_active,
+        roles: vec![ScimRole {
+            value: role_name.clone(),
+            primary: true,
+        }],
+        groups: vec![ScimGroupRef {
+            value: user.role_id.to_string(),
+            display: role_name,
+            reference: format!("/scim/v2/Groups/{}", user.role_id),
+        }],
+        meta: ScimMeta {
+            resource_type: "User",
+            created: user.created_at.map(|c| c.to_rfc3339()),
+            location: format!("/scim/v2/Users/{}", user.id),
+        },
+    }
+}
+
+/// Incoming user representation for POST and PUT
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUserInput {
+    external_id: Option<String>,
+    user_name: Option<String>,
+    name: Option<ScimName>,
+    emails: Option<Value>,
+    active: Option<bool>,
+    roles: Option<Value>,
+    password: Option<String>,
+}
+
+/// Attribute changes collected from a POST, PUT or PATCH body.
+/// `Some(None)` clears the external id. Employee names are NOT NULL
+/// columns, so they can be replaced but never removed.
+#[derive(Debug, Default)]
+struct UserChanges {
+    email: Option<String>,
+    external_id: Option<Option<String>>,
+    given_name: Option<String>,
+    family_name: Option<String>,
+    active: Option<bool>,
+    role: Option<String>,
+    password: Option<String>,
+}
+
+impl UserChanges {
+    fn from_input(input: ScimUserInput) -> Self {
+        let email = input
+            .user_name
+            .or_else(|| input.emails.as_ref().and_then(value_as_string));
+        let name = input.name.unwrap_or_default();
+
+        Self {
+            email,
+            external_id: Some(input.external_id),
+            given_name: name.given_name,
+            family_name: name.family_name,
+            active: input.active,
+            role: input.roles.as_ref().and_then(value_as_string),
+            password: input.password,
+        }
+    }
+
+    /// Apply one PATCH operation. Attributes this resource does not store are ignored.
+    fn apply_patch(
+        &mut self,
+        kind: PatchKind,
+        path: &str,
+        value: Option<&Value>,
+    ) -> Result<(), ScimError> {
+        let path = normalize_attr(path);
+        let path = path.as_str();
+
+        let string = |value: Option<&Value>| -> Result<Option<String>, ScimError> {
+            match (kind, value) {
+                (PatchKind::Remove, _) => Ok(None),
+                (_, Some(v)) => value_as_string(v).map(Some).ok_or_else(|| {
+                    ScimError::bad_request(
+                        "invalidValue",
+                        format!("Expected a string for '{}'", path),
+                    )
+                }),
+                (_, None) => Err(ScimError::bad_request(
+                    "invalidValue",
+                    format!("Missing value for '{}'", path),
+                )),
+            }
+        };
+
+        match path {
+            "username" => {
+                self.email = Some(string(value)?.ok_or_else(|| {
+                    ScimError::new(
+                        StatusCode::BAD_REQUEST,
+                        Some("mutability"),
+                        "userName is required",
+                    )
+                })?);
+            }
+            p if p == "emails" || p.starts_with("emails[") || p == "emails.value" => {
+                if let Some(email) = string(value)? {
+                    self.email = Some(email);
+                }
+            }
+            "externalid" => self.external_id = Some(string(value)?),
+            "name.givenname" => {
+                if let Some(first) = string(value)? {
+                    self.given_name = Some(first);
+                }
+            }
+            "name.familyname" => {
+                if let Some(last) = string(value)? {
+                    self.family_name = Some(last);
+                }
+            }
+            "name" => {
+                if let (PatchKind::Add | PatchKind::Replace, Some(v)) = (kind, value) {
+                    let name: ScimName = serde_json::from_value(v.clone()).map_err(|e| {
+                        ScimError::bad_request("invalidValue", format!("Invalid name: {}", e))
+                    })?;
+                    if name.given_name.is_some() {
+                        self.given_name = name.given_name;
+                    }
+                    if name.family_name.is_some() {
+                        self.family_name = name.family_name;
+                    }
+                }
+            }
+            "active" => {
+                self.active = match (kind, value) {
+                    (PatchKind::Remove, _) => Some(false),
+                    (_, Some(v)) => Some(value_as_bool(v).ok_or_else(|| {
+                        ScimError::bad_request("invalidValue", "Expected a boolean for 'active'")
+                    })?),
+                    (_, None) => None,
+                };
+            }
+            p if p == "roles" || p.starts_with("roles[") => {
+                self.role = match kind {
+                    PatchKind::Remove => Some(DEFAULT_ROLE.to_string()),
+                    _ => string(value)?,
+                };
+            }
+            "password" => self.password = string(value)?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+//
+// -------------------- QUERIES --------------------
+//
+
+fn user_attr(attr: &str) -> Option<Attr> {
+    let col = |c: users::Column| -> SimpleExpr { Expr::col((users::Entity, c)).into() };
+    let emp = |c: employee::Column| -> SimpleExpr { Expr::col((employee::Entity, c)).into() };
+
+    match attr {
+        "id" => Some(Attr::Int(col(users::Column::Id))),
+        "username" | "emails" | "emails.value" => Some(Attr::Text(col(users::Column::Email))),
+        "externalid" => Some(Attr::Text(col(users::Column::ExternalId))),
+        "active" => Some(Attr::Bool(col(users::Column::IsActive))),
+        "name.givenname" => Some(Attr::Text(emp(employee::Column::FirstName))),
+        "name.familyname" => Some(Attr::Text(emp(employee::Column::LastName))),
+        _ => None,
+    }
+}
+
+async fn role_names<C: ConnectionTrait>(db: &C) -> Result<HashMap<i32, String>, ScimError> {
+    Ok(app_role::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|r| (r.id, r.name))
+        .collect())
+}
+
+async fn load<C: ConnectionTrait>(
+    db: &C,
+    id: i32,
+) -> Result<(users::Model, Option<employee::Model>), ScimError> {
+    users::Entity::find_by_id(id)
+        .find_also_related(employee::Entity)
+        .one(db)
+        .await?
+        .ok_or_else(|| ScimError::not_found(format!("User {} not found", id)))
+}
+
+async fn load_resource<C: ConnectionTrait>(db: &C, id: i32) -> Result<ScimUser, ScimError> {
+    let (user, employee) = load(db, id).await?;
+    let roles = role_names(db).await?;
+    let role_name = roles.get(&user.role_id).cloned().unwrap_or_default();
+    Ok(to_resource(user, employee, role_name))
+}
+
+async fn role_id_by_name<C: ConnectionTrait>(db: &C, name: &str) -> Result<i32, ScimError> {
+    app_role::Entity::find()
+        .filter(app_role::Column::Name.eq(name))
+        .one(db)
+        .await?
+        .map(|r| r.id)
+        .ok_or_else(|| ScimError::bad_request("invalidValue", format!("Unknown role '{}'", name)))
+}
+
+async fn ensure_unique<C: ConnectionTrait>(
+    db: &C,
+    changes: &UserChanges,
+    own_id: Option<i32>,
+) -> Result<(), ScimError> {
+    let others = |cond: Condition| {
+        let query = users::Entity::find().filter(cond);
+        match own_id {
+            Some(id) => query.filter(users::Column::Id.ne(id)),
+            None => query,
+        }
+    };
+
+    if let Some(email) = &changes.email {
+        let taken = others(Condition::all().add(users::Column::Email.eq(email.as_str())))
+            .one(db)
+            .await?
+            .is_some();
+        if taken {
+            return Err(ScimError::conflict(format!(
+                "User {} already exists",
+                email
+            )));
+        }
+    }
+
+    if let Some(Some(external_id)) = &changes.external_id {
+        let taken =
+            others(Condition::all().add(users::Column::ExternalId.eq(external_id.as_str())))
+                .one(db)
+                .await?
+                .is_some();
+        if taken {
+            return Err(ScimError::conflict(format!(
+                "externalId {} is already assigned",
+                external_id
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Write `changes` to the user and its linked employee, creating or linking
+/// an employee when the user receives a name for the first time.
+async fn apply_changes<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    user: users::Model,
+    employee: Option<employee::Model>,
+    changes: UserChanges,
+) -> Result<(), ScimError> {
+    ensure_unique(db, &changes, Some(user.id)).await?;
+    if let Some(email) = &changes.email {
+        directory::ensure_email_available(db, email, Some(user.id), user.employee_id).await?;
+    }
+
+    let mut model: users::ActiveModel = user.into();
+
+    if let Some(email) = changes.email {
+        model.email = Set(email);
+    }
+    if let Some(external_id) = changes.external_id {
+        model.external_id = Set(external_id);
+    }
+    if let Some(active) = changes.active {
+        directory::set_active(&mut model, active);
+    }
+    if let Some(role) = changes.role {
+        model.role_id = Set(role_id_by_name(db, &role).await?);
+    }
+    if let Some(password) = changes.password {
+        model.password_hash = Set(hash_password(password)
+            .await
+            .map_err(|e| ScimError::internal(format!("Password hashing failed: {}", e)))?);
+    }
+
+    let user = model.update(db).await?;
+    directory::sync_user(db, &user).await?;
+
+    if changes.given_name.is_none() && changes.family_name.is_none() {
+        return Ok(());
+    }
+
+    match employee {
+        Some(existing) => {
+            let mut emp: employee::ActiveModel = existing.into();
+            if let Some(first) = changes.given_name {
+                emp.first_name = Set(Some(first));
+            }
+            if let Some(last) = changes.family_name {
+                emp.last_name = Set(Some(last));
+            }
+            directory::touch(&mut emp);
+            emp.update(db).await?;
+        }
+        None => {
+            let employee_id =
+                link_or_create_employee(db, &user.email, changes.given_name, changes.family_name)
+                    .await?;
+
+            directory::link_employee(db, user.id, employee_id).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Find the unlinked employee with this email, or create one
+async fn link_or_create_employee<C: ConnectionTrait>(
+    db: &C,
+    email: &str,
+    first_name: Option<String>,
+    last_name: Option<String>,
+) -> Result<i32, ScimError> {
+    if let Some(existing) = employee::Entity::find()
+        .filter(employee::Column::Email.eq(email))
+        .one(db)
+        .await?
+    {
+        let linked = users::Entity::find()
+            .filter(users::Column::EmployeeId.eq(existing.id))
+            .one(db)
+            .await?;
+        if linked.is_some() {
+            return Err(ScimError::conflict(format!(
+                "Employee {} is already linked to another user",
+                email
+            )));
+        }
+
+        let id = existing.id;
+        let mut emp: employee::ActiveModel = existing.into();
+        if first_name.is_some() {
+            emp.first_name = Set(first_name);
+        }
+        if last_name.is_some() {
+            emp.last_name = Set(last_name);
+        }
+        directory::touch(&mut emp);
+        emp.update(db).await?;
+        return Ok(id);
+    }
+
+    if first_name.is_none() || last_name.is_none() {
+        return Err(ScimError::bad_request(
+            "invalidValue",
+            "name.givenName and name.familyName are both required to create an employee",
+        ));
+    }
+
+    let created = employee::ActiveModel {
+        id: NotSet,
+        first_name: Set(first_name),
+        last_name: Set(last_name),
+        email: Set(Some(email.to_string())),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(created.id)
+}
+
+//
+// -------------------- HANDLERS --------------------
+//
+
+pub async fn list(
+    Query(query): Query<ListQuery>,
+) -> Result<ScimJson<ListResponse<ScimUser>>, ScimError> {
+    let condition = query.condition(user_attr)?;
+    let db = db().await?;
+
+    let base = users::Entity::find()
+        .join(JoinType::LeftJoin, users::Relation::Employee.def())
+        .filter(condition);
+
+    let total = base.clone().count(&db).await?;
+    let rows = base
+        .select_also(employee::Entity)
+        .order_by_asc(users::Column::Id)
+        .offset(query.offset())
+        .limit(query.count())
+        .all(&db)
+        .await?;
+
+    let roles = role_names(&db).await?;
+    let resources = rows
+        .into_iter()
+        .map(|(user, employee)| {
+            let role_name = roles.get(&user.role_id).cloned().unwrap_or_default();
+            to_resource(user, employee, role_name)
+        })
+        .collect();
+
+    Ok(ScimJson::ok(ListResponse::new(
+        total,
+        query.start_index(),
+        resources,
+    )))
+}
+
+pub async fn get(Path(id): Path<String>) -> Result<ScimJson<ScimUser>, ScimError> {
+    let id = parse_id(&id)?;
+    let db = db().await?;
+    Ok(ScimJson::ok(load_resource(&db, id).await?))
+}
+
+pub async fn create(Json(input): Json<ScimUserInput>) -> Result<ScimJson<ScimUser>, ScimError> {
+    let changes = UserChanges::from_input(input);
+    let email = changes
+        .email
+        .clone()
+        .ok_or_else(|| ScimError::bad_request("invalidValue", "userName is required"))?;
+
+    let db = db().await?;
+    let txn = db.begin().await?;
+
+    ensure_unique(&txn, &changes, None).await?;
+
+    let role = changes.role.as_deref().unwrap_or(DEFAULT_ROLE);
+    role_id_by_name(&txn, role).await?;
+    let password = changes.password.clone().unwrap_or_else(random_password);
+    let password_hash = hash_password(password)
+        .await
+        .map_err(|e| ScimError::internal(format!("Password hashing failed: {}", e)))?;
+
+    let first = changes.given_name.clone();
+    let last = changes.family_name.clone();
+    let has_employee = employee::Entity::find()
+        .filter(employee::Column::Email.eq(email.as_str()))
+        .one(&txn)
+        .await?
+        .is_some();
+    let employee_id = if has_employee || first.is_some() || last.is_some() {
+        Some(link_or_create_employee(&txn, &email, first, last).await?)
+    } else {
+        None
+    };
+
+    // The identity provider holds every permission
+    let user = directory::create_user(
+        &txn,
+        &directory::full_rights(),
+        email,
+        role,
+        employee_id,
+        password_hash,
+    )
+    .await?;
+    let mut model: users::ActiveModel = user.into();
+    model.external_id = Set(changes.external_id.flatten());
+    directory::set_active(&mut model, changes.active.unwrap_or(true));
+    let user = model.update(&txn).await?;
+
+    let resource = load_resource(&txn, user.id).await?;
+    txn.commit().await?;
+
+    let location = resource.meta.location.clone();
+    Ok(ScimJson::created(location, resource))
+}
+
+pub async fn replace(
+    Path(id): Path<String>,
+    Json(input): Json<ScimUserInput>,
+) -> Result<ScimJson<ScimUser>, ScimError> {
+    let id = parse_id(&id)?;
+    let mut changes = UserChanges::from_input(input);
+    if changes.email.is_none() {
+        return Err(ScimError::bad_request(
+            "invalidValue",
+            "userName is required",
+        ));
+    }
+    // PUT replaces the resource, so an omitted `active` means active
+    changes.active.get_or_insert(true);
+
+    let db = db().await?;
+    let txn = db.begin().await?;
+
+    let (user, employee) = load(&txn, id).await?;
+    apply_changes(&txn, user, employee, changes).await?;
+
+    let resource = load_resource(&txn, id).await?;
+    txn.commit().await?;
+
+    Ok(ScimJson::ok(resource))
+}
+
+pub async fn patch(
+    Path(id): Path<String>,
+    Json(request): Json<PatchRequest>,
+) -> Result<ScimJson<ScimUser>, ScimError> {
+    let id = parse_id(&id)?;
+    request.validate()?;
+
+    let mut changes = UserChanges::default();
+    for operation in &request.operations {
+        let kind = operation.kind()?;
+        match (&operation.path, &operation.value) {
+            (Some(path), value) => changes.apply_patch(kind, path, value.as_ref())?,
+            (None, Some(Value::Object(attributes))) => {
+                for (path, value) in attributes {
+                    changes.apply_patch(kind, path, Some(value))?;
+                }
+            }
+            (None, _) => {
+                return Err(ScimError::bad_request(
+                    "noTarget",
+                    "Patch operation without a path needs an object value",
+                ));
+            }
+        }
+    }
+
+    let db = db().await?;
+    let txn = db.begin().await?;
+
+    let (user, employee) = load(&txn, id).await?;
+    apply_changes(&txn, user, employee, changes).await?;
+
+    let resource = load_resource(&txn, id).await?;
+    txn.commit().await?;
+
+    Ok(ScimJson::ok(resource))
+}
+
+pub async fn delete(Path(id): Path<String>) -> Result<StatusCode, ScimError> {
+    let id = parse_id(&id)?;
+    let db = db().await?;
+
+    let result = users::Entity::delete_by_id(id).exec(&db).await?;
+    if result.rows_affected == 0 {
+        return Err(ScimError::not_found(format!("User {} not found", id)));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
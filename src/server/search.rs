@@ -0,0 +1,25 @@
+//! LIKE patterns built from user input
+//!
+//! Search boxes and filters match their text literally: `%`, `_` and `\`
+//! are escaped, and every pattern says `ESCAPE '\'` because SQLite has no
+//! default escape character, unlike PostgreSQL.
+
+use sea_orm::sea_query::LikeExpr;
+
+/// `pattern` with an explicit `ESCAPE '\'`
+pub fn like(pattern: String) -> LikeExpr {
+    LikeExpr::new(pattern).escape('\\')
+}
+
+/// `value` with the LIKE wildcards and the escape character escaped
+pub fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Matches text containing `value`
+pub fn contains(value: &str) -> LikeExpr {
+    like(format!("%{}%", escape_like(value)))
+}
@@ -0,0 +1,304 @@
+//! Signed session cookies and role checks for server functions
+//!
+//! `login` sets an HttpOnly `session` cookie holding an HS256 JWT with the
+//! user's id, email and role, valid for `auth.jwt_ttl_secs`. Server functions
+//! read it back from the current request with `current()` or the `require_*`
+//! helpers. The token's role is only a hint: `require_permission` looks up
+//! the account's current role and its permissions on every call, so role
+//! changes and role edits apply at once. It also refuses tokens of deleted or
+//! deactivated accounts and tokens issued before the last deactivation, which
+//! revokes them. Without `auth.jwt_secret` a random key is generated at
+//! startup, so sessions do not survive a restart. Refusals are `AccessError`s,
+//! worded in the request's language.
+//!
+//! The REST and GraphQL APIs take the same token, as the cookie or as
+//! `Authorization: Bearer <token>`, and check it with `resolve`.
+
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dioxus::fullstack::FullstackContext;
+use dioxus::fullstack::http::{HeaderMap, HeaderValue, header};
+use dioxus::prelude::ServerFnError;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use sea_orm::{ConnectionTrait, DbErr, EntityTrait};
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{app_role, users};
+use crate::i18n::Translate;
+use crate::server::auth::SessionUser;
+
+pub const COOKIE: &str = "session";
+
+pub use crate::server::auth::{ROLE_ADMIN, ROLE_HR, ROLE_IT};
+
+use crate::server::db_connection::get_db;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: i32,
+    email: String,
+    role: String,
+    exp: u64,
+    /// Issue time; tokens signed before it was added count as issued at 0
+    #[serde(default)]
+    iat: u64,
+}
+
+fn secret() -> &'static [u8] {
+    static SECRET: OnceLock<Vec<u8>> = OnceLock::new();
+    SECRET.get_or_init(|| match &crate::server::config::get().auth.jwt_secret {
+        Some(secret) => secret.as_bytes().to_vec(),
+        None => crate::server::auth::random_password().into_bytes(),
+    })
+}
+
+/// Sign a session token for `user`
+pub fn encode(user: &SessionUser) -> String {
+    let ttl = crate::server::config::get().auth.jwt_ttl_secs;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock is after 1970")
+        .as_secs();
+    let claims = Claims {
+        sub: user.id,
+        email: user.email.clone(),
+        role: user.role.clone(),
+        exp: now + ttl,
+        iat: now,
+    };
+    jsonwebtoken::encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(secret()),
+    )
+    .expect("HS256 signing does not fail")
+}
+
+/// The user in a valid, unexpired token
+pub fn decode(token: &str) -> Option<SessionUser> {
+    decode_claims(token).map(|claims| claims.into())
+}
+
+fn decode_claims(token: &str) -> Option<Claims> {
+    jsonwebtoken::decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret()),
+        &Validation::new(Algorithm::HS256),
+    )
+    .ok()
+    .map(|data| data.claims)
+}
+
+impl From<Claims> for SessionUser {
+    fn from(claims: Claims) -> Self {
+        Self {
+            id: claims.sub,
+            email: claims.email,
+            role: claims.role,
+            permissions: Vec::new(),
+        }
+    }
+}
+
+/// Set the session cookie on the current server function response
+pub fn start(user: &SessionUser) {
+    let max_age = crate::server::config::get().auth.jwt_ttl_secs;
+    set_cookie(&format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
+        COOKIE,
+        encode(user),
+        max_age
+    ));
+}
+
+/// Expire the session cookie
+pub fn end() {
+    set_cookie(&format!(
+        "{}=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0",
+        COOKIE
+    ));
+}
+
+/// Add a `Set-Cookie` header to the current server function response
+pub fn set_cookie(value: &str) {
+    if let Some(ctx) = FullstackContext::current() {
+        let value = HeaderValue::from_str(value).expect("cookie is visible ASCII");
+        ctx.add_response_header(header::SET_COOKIE, value);
+    }
+}
+
+/// The value of cookie `name` on the current request
+pub fn request_cookie(name: &str) -> Option<String> {
+    let ctx = FullstackContext::current()?;
+    let parts = ctx.parts_mut();
+    cookie(&parts.headers, name)
+}
+
+/// The value of cookie `name` in `headers`
+pub fn cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(cookie, _)| *cookie == name)
+        .map(|(_, value)| value.to_string())
+}
+
+/// The session token of an API request: `Authorization: Bearer <token>`,
+/// or else the session cookie
+pub fn request_token(headers: &HeaderMap) -> Option<String> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string());
+    bearer.or_else(|| cookie(headers, COOKIE))
+}
+
+/// The signed-in user of the current request, if any
+pub fn current() -> Option<SessionUser> {
+    let user = request_cookie(COOKIE).and_then(|token| decode(&token))?;
+    crate::server::telemetry::record_user(&user);
+    Some(user)
+}
+
+/// Why a server function refused the request
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccessError {
+    NotSignedIn,
+    PermissionDenied,
+}
+
+impl Translate for AccessError {
+    fn key(&self) -> &'static str {
+        match self {
+            Self::NotSignedIn => "error.not_signed_in",
+            Self::PermissionDenied => "error.permission_denied",
+        }
+    }
+}
+
+impl From<AccessError> for ServerFnError {
+    fn from(e: AccessError) -> Self {
+        crate::i18n::server_error(&e)
+    }
+}
+
+/// Fail unless someone is signed in
+pub fn require_user() -> Result<SessionUser, ServerFnError> {
+    current().ok_or_else(|| AccessError::NotSignedIn.into())
+}
+
+/// The signed-in user with the permissions their role has now. `None`
+/// without a session, or with an invalid or revoked one, whose cookie is
+/// then cleared.
+pub async fn current_with_permissions() -> Result<Option<SessionUser>, ServerFnError> {
+    let Some(token) = request_cookie(COOKIE) else {
+        return Ok(None);
+    };
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+    let user = resolve(&db, &token)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?;
+    match &user {
+        Some(user) => crate::server::telemetry::record_user(user),
+        None => end(),
+    }
+    Ok(user)
+}
+
+/// The user behind `token` with the permissions their role has now. `None`
+/// for invalid or expired tokens and for revoked sessions.
+pub async fn resolve<C: ConnectionTrait>(
+    db: &C,
+    token: &str,
+) -> Result<Option<SessionUser>, DbErr> {
+    let Some(claims) = decode_claims(token) else {
+        return Ok(None);
+    };
+    let account = users::Entity::find_by_id(claims.sub)
+        .find_also_related(app_role::Entity)
+        .one(db)
+        .await?;
+    let revoked = match &account {
+        Some((user, _)) => is_revoked(user, claims.iat),
+        None => true,
+    };
+    if revoked {
+        return Ok(None);
+    }
+
+    let mut user = SessionUser::from(claims);
+    // The role the account has now, not the one it had at sign-in
+    if let Some((_, Some(role))) = account {
+        user.role = role.name;
+    }
+    user.permissions = crate::server::roles::permissions_of(db, &user.role).await?;
+    Ok(Some(user))
+}
+
+/// Fail unless someone is signed in with a session that was not revoked;
+/// the user comes back with the permissions their role has now
+pub async fn require_permissions() -> Result<SessionUser, ServerFnError> {
+    current_with_permissions()
+        .await?
+        .ok_or_else(|| AccessError::NotSignedIn.into())
+}
+
+/// Whether a token for `user` issued at `issued_at` (seconds since the
+/// epoch) is void: the account is deactivated, or was deactivated since
+fn is_revoked(user: &users::Model, issued_at: u64) -> bool {
+    let deactivated_since = user
+        .deactivated_at
+        .is_some_and(|at| at.timestamp() >= issued_at as i64);
+    !user.is_active || deactivated_since
+}
+
+/// Fail unless the signed-in user's role grants `permission`
+pub async fn require_permission(permission: &str) -> Result<SessionUser, ServerFnError> {
+    let user = require_permissions().await?;
+    if user.can(permission) {
+        Ok(user)
+    } else {
+        Err(AccessError::PermissionDenied.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(role: &str) -> SessionUser {
+        SessionUser {
+            id: 7,
+            email: "ada@example.com".into(),
+            role: role.into(),
+            permissions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn tokens_round_trip_and_reject_tampering() {
+        let token = encode(&user(ROLE_HR));
+        assert_eq!(decode(&token), Some(user(ROLE_HR)));
+
+        let mut forged = token.clone();
+        forged.pop();
+        assert_eq!(decode(&forged), None);
+        assert_eq!(decode("not-a-token"), None);
+    }
+
+    #[tokio::test]
+    async fn no_request_means_nobody_is_signed_in() {
+        assert_eq!(current(), None);
+        let err = require_permission(crate::server::auth::EMPLOYEE_READ)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Not signed in"), "{}", err);
+    }
+}
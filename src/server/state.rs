@@ -1,6 +1,24 @@
 use dioxus::prelude::*;
 
-#[derive(Clone)]
+use crate::server::auth::SessionUser;
+
+/// The signed-in account as far as the UI knows. The server checks the
+/// session cookie on every call; this only decides what to show.
+#[derive(Clone, Copy)]
 pub struct AuthState {
-    pub logged_in: Signal<bool>,
-}
\ No newline at end of file
+    pub user: Signal<Option<SessionUser>>,
+}
+
+impl AuthState {
+    pub fn logged_in(&self) -> bool {
+        self.user.read().is_some()
+    }
+
+    /// Signed in with a role that grants `permission`
+    pub fn can(&self, permission: &str) -> bool {
+        self.user
+            .read()
+            .as_ref()
+            .is_some_and(|user| user.can(permission))
+    }
+}
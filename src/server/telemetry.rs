@@ -0,0 +1,347 @@
+//! Logging setup, request ids and per-server-function spans
+//!
+//! - `init()` installs the global subscriber before `dioxus::serve` would
+//!   install its own. `log.format = "json"` switches to one JSON object per
+//!   line; the filter comes from `RUST_LOG`, else `log.filter`.
+//! - `request_id` wraps every HTTP request in a `request` span carrying an
+//!   `X-Request-Id` (taken from the client when well-formed, else generated).
+//! - `ServerFnLayer` opens a `server_fn` span below it with the function name,
+//!   DB time and outcome, and feeds the per-function Prometheus metrics.
+//!   `session` fills in `user_id` and `role` once it knows who is calling.
+//!
+//! Only the path and a redacted query string are logged; headers, bodies and
+//! SQL bind values never are.
+
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::{Instrument, field::Empty};
+use tracing_subscriber::{EnvFilter, fmt, prelude::*};
+
+use crate::server::auth::SessionUser;
+use crate::server::config::{LogConfig, LogFormat};
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Query parameters whose values never reach the log
+const SECRET_PARAMS: [&str; 6] = [
+    "password",
+    "token",
+    "secret",
+    "key",
+    "authorization",
+    "code",
+];
+
+tokio::task_local! {
+    /// DB time spent by the current server function
+    static DB_TIME: Cell<Duration>;
+}
+
+/// Install the global subscriber; `RUST_LOG` overrides `log.filter`
+pub fn init(config: &LogConfig) {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&config.filter));
+    let json = config.format == LogFormat::Json;
+
+    let registry = tracing_subscriber::registry().with(filter);
+    let result = if json {
+        registry
+            .with(
+                fmt::layer()
+                    .json()
+                    .with_current_span(true)
+                    .with_span_list(true),
+            )
+            .try_init()
+    } else {
+        registry.with(fmt::layer().pretty()).try_init()
+    };
+
+    if let Err(e) = result {
+        eprintln!("Logging was already initialized: {}", e);
+    }
+}
+
+//
+// -------------------- REQUEST IDS --------------------
+//
+
+/// Middleware: open the `request` span and echo the id in the response
+pub async fn request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| is_valid_request_id(v))
+        .map(str::to_string)
+        .unwrap_or_else(new_request_id);
+
+    let header = HeaderValue::from_str(&id).expect("request id is ASCII");
+    request
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER, header.clone());
+
+    let query = request.uri().query().map(redact_query);
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %request.method(),
+        path = %request.uri().path(),
+        query = query.as_deref(),
+    );
+
+    async move {
+        let started = Instant::now();
+        let mut response = next.run(request).await;
+        tracing::info!(
+            status = response.status().as_u16(),
+            latency_ms = started.elapsed().as_millis() as u64,
+            "request finished"
+        );
+        response.headers_mut().insert(REQUEST_ID_HEADER, header);
+        response
+    }
+    .instrument(span)
+    .await
+}
+
+fn new_request_id() -> String {
+    let mut bytes = [0u8; 8];
+    getrandom::getrandom(&mut bytes).expect("OS random number generator unavailable");
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn is_valid_request_id(id: &str) -> bool {
+    (1..=64).contains(&id.len())
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+/// Replace the values of secret-looking query parameters with `***`
+pub fn redact_query(query: &str) -> String {
+    query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if is_secret(name) => format!("{}=***", name),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn is_secret(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_PARAMS.iter().any(|s| name.contains(s))
+}
+
+//
+// -------------------- SERVER FUNCTION SPANS --------------------
+//
+
+/// Tower layer for `#[middleware(...)]` on a server function
+#[derive(Clone, Copy)]
+pub struct ServerFnLayer {
+    name: &'static str,
+}
+
+impl ServerFnLayer {
+    pub fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+}
+
+impl<S> tower::Layer<S> for ServerFnLayer {
+    type Service = ServerFnService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ServerFnService {
+            name: self.name,
+            inner,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ServerFnService<S> {
+    name: &'static str,
+    inner: S,
+}
+
+impl<S> tower::Service<Request> for ServerFnService<S>
+where
+    S: tower::Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // Take the service that was driven to readiness, leave a fresh clone
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let name = self.name;
+        let span = tracing::info_span!(
+            "server_fn",
+            name,
+            user_id = Empty,
+            role = Empty,
+            db_ms = Empty,
+            outcome = Empty,
+        );
+
+        let future = async move {
+            let started = Instant::now();
+            let result = inner.call(request).await;
+
+            let span = tracing::Span::current();
+            let db_time = DB_TIME.with(Cell::get);
+            span.record("db_ms", db_time.as_millis() as u64);
+
+            let outcome = match &result {
+                Ok(response) if response.status().is_success() => "ok",
+                _ => "error",
+            };
+            crate::server::metrics::record_server_fn(name, outcome, started.elapsed());
+
+            match &result {
+                Ok(response) if response.status().is_success() => {
+                    span.record("outcome", "ok");
+                    tracing::info!(
+                        elapsed_ms = started.elapsed().as_millis() as u64,
+                        "server fn done"
+                    );
+                }
+                Ok(response) => {
+                    span.record("outcome", "error");
+                    tracing::warn!(
+                        status = response.status().as_u16(),
+                        elapsed_ms = started.elapsed().as_millis() as u64,
+                        "server fn failed"
+                    );
+                }
+                Err(_) => {
+                    span.record("outcome", "error");
+                }
+            }
+            result
+        };
+
+        Box::pin(DB_TIME.scope(Cell::new(Duration::ZERO), future.instrument(span)))
+    }
+}
+
+/// Note the signed-in user on the current `server_fn` span
+pub fn record_user(user: &SessionUser) {
+    let span = tracing::Span::current();
+    span.record("user_id", user.id);
+    span.record("role", user.role.as_str());
+}
+
+/// sea-orm metric callback: add the query time to the current server function
+/// and to the `db_query_duration_seconds` histogram
+pub fn record_query(info: &sea_orm::metric::Info<'_>) {
+    let _ = DB_TIME.try_with(|total| total.set(total.get() + info.elapsed));
+    crate::server::metrics::record_query(info.elapsed, info.failed);
+
+    // The statement is not logged: its values may contain password hashes
+    if info.failed {
+        tracing::warn!(
+            elapsed_ms = info.elapsed.as_millis() as u64,
+            "db query failed"
+        );
+    } else {
+        tracing::trace!(elapsed_ms = info.elapsed.as_millis() as u64, "db query");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing_subscriber::layer::Context as LayerContext;
+
+    use super::*;
+
+    /// Collects the values recorded into `user_id` and `role`
+    #[derive(Clone, Default)]
+    struct Recorded(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for Recorded {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "user_id" || field.name() == "role" {
+                let entry = format!("{}={:?}", field.name(), value);
+                self.0.lock().unwrap().push(entry);
+            }
+        }
+    }
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for Recorded {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _: &Id, _: LayerContext<'_, S>) {
+            attrs.record(&mut self.clone());
+        }
+
+        fn on_record(&self, _: &Id, values: &Record<'_>, _: LayerContext<'_, S>) {
+            values.record(&mut self.clone());
+        }
+    }
+
+    #[test]
+    fn records_the_user_on_the_server_fn_span() {
+        let recorded = Recorded::default();
+        let subscriber = tracing_subscriber::registry().with(recorded.clone());
+        let user = SessionUser {
+            id: 7,
+            email: "ada@example.com".into(),
+            role: "ROLE_HR".into(),
+            permissions: Vec::new(),
+        };
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("server_fn", user_id = Empty, role = Empty);
+            span.in_scope(|| record_user(&user));
+        });
+
+        assert_eq!(
+            *recorded.0.lock().unwrap(),
+            ["user_id=7", "role=\"ROLE_HR\""]
+        );
+    }
+
+    #[test]
+    fn redacts_secret_query_params() {
+        assert_eq!(
+            redact_query("page=2&access_token=abc&Password=hunter2&q=smith"),
+            "page=2&access_token=***&Password=***&q=smith"
+        );
+        assert_eq!(redact_query("flag"), "flag");
+    }
+
+    #[test]
+    fn accepts_only_sane_request_ids() {
+        assert!(is_valid_request_id("0af7651916cd43dd"));
+        assert!(is_valid_request_id("req-42_a.b"));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("a b"));
+        assert!(!is_valid_request_id(&"x".repeat(65)));
+        assert!(is_valid_request_id(&new_request_id()));
+    }
+}
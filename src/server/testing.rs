@@ -0,0 +1,270 @@
+//! Test harness for server functions
+//!
+//! `TestDb::new()` provisions a database that belongs to one test:
+//!
+//! - with `TEST_DATABASE_URL=postgres://...` a fresh database is created on
+//!   that server and brought up with `migrate::up`; `finish()` drops it;
+//! - otherwise, with the `sqlite` feature, an in-memory SQLite database with
+//!   the schema created from the entities.
+//!
+//! Server function bodies are plain async functions on the server build.
+//! `run`, `as_role` and `as_user` call them with `get_db()` pointing at the
+//! test database and with a session cookie for the given account, so the
+//! same `require_permission` checks apply as over HTTP. Sessions need a real
+//! account, since the checks load it; `as_role` makes one per role. `in_locale` adds the
+//! language cookie for checking translated errors.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use dioxus::fullstack::FullstackContext;
+use dioxus::fullstack::http::{Request, header};
+use sea_orm::*;
+
+use crate::entities::{app_role, employee, users};
+use crate::i18n::{self, Locale};
+use crate::server::auth::SessionUser;
+use crate::server::db_connection::TEST_DB;
+use crate::server::{migrate, session};
+
+pub struct TestDb {
+    db: DatabaseConnection,
+    /// Server connection and name of a Postgres database to drop
+    postgres: Option<(DatabaseConnection, String)>,
+}
+
+impl TestDb {
+    pub async fn new() -> Self {
+        dotenvy::dotenv().ok();
+        match std::env::var("TEST_DATABASE_URL") {
+            Ok(url) => Self::postgres(&url).await,
+            Err(_) => Self::sqlite().await,
+        }
+    }
+
+    async fn postgres(server_url: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "rfc_test_{}_{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        );
+
+        let server = Database::connect(server_url)
+            .await
+            .expect("TEST_DATABASE_URL is reachable");
+        server
+            .execute_unprepared(&format!("CREATE DATABASE {}", name))
+            .await
+            .expect("test database can be created");
+
+        let db = Database::connect(database_url(server_url, &name))
+            .await
+            .expect("test database is reachable");
+        migrate::up(&db).await.expect("migrations apply");
+
+        Self {
+            db,
+            postgres: Some((server, name)),
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    async fn sqlite() -> Self {
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1);
+        let db = Database::connect(options).await.expect("SQLite opens");
+        migrate::create_sqlite_schema(&db)
+            .await
+            .expect("schema is created");
+        Self { db, postgres: None }
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    async fn sqlite() -> Self {
+        panic!("set TEST_DATABASE_URL or enable the `sqlite` feature");
+    }
+
+    /// Drop the Postgres test database; a no-op on SQLite
+    pub async fn finish(self) {
+        self.db.close().await.ok();
+        if let Some((server, name)) = self.postgres {
+            server
+                .execute_unprepared(&format!("DROP DATABASE IF EXISTS {} WITH (FORCE)", name))
+                .await
+                .expect("test database can be dropped");
+        }
+    }
+
+    /// Run `fut` against this database without a session
+    pub async fn run<F: Future>(&self, fut: F) -> F::Output {
+        self.scoped(None, None, fut).await
+    }
+
+    /// Run `fut` without a session, with the language cookie set to `locale`
+    pub async fn in_locale<F: Future>(&self, locale: Locale, fut: F) -> F::Output {
+        self.scoped(None, Some(locale), fut).await
+    }
+
+    /// Run `fut` signed in as `<role>@test.invalid`, an account with `role`
+    /// and no employee that is added on first use
+    pub async fn as_role<F: Future>(&self, role: &str, fut: F) -> F::Output {
+        let email = format!("{}@test.invalid", role.to_lowercase());
+        let existing = users::Entity::find()
+            .filter(users::Column::Email.eq(&email))
+            .one(&self.db)
+            .await
+            .expect("users are readable");
+        let account = match existing {
+            Some(account) => account,
+            None => user(&self.db, &email, role, None).await,
+        };
+        self.as_user(&Fixtures::session(&account, role), fut).await
+    }
+
+    /// Run `fut` signed in as `user`
+    pub async fn as_user<F: Future>(&self, user: &SessionUser, fut: F) -> F::Output {
+        self.scoped(Some(user), None, fut).await
+    }
+
+    async fn scoped<F: Future>(
+        &self,
+        user: Option<&SessionUser>,
+        locale: Option<Locale>,
+        fut: F,
+    ) -> F::Output {
+        let mut request = Request::builder().method("POST").uri("/api/test");
+        if let Some(user) = user {
+            request = request.header(
+                header::COOKIE,
+                format!("{}={}", session::COOKIE, session::encode(user)),
+            );
+        }
+        if let Some(locale) = locale {
+            request = request.header(
+                header::COOKIE,
+                format!("{}={}", i18n::COOKIE, locale.code()),
+            );
+        }
+        let parts = request.body(()).expect("request is valid").into_parts().0;
+
+        FullstackContext::new(parts)
+            .scope(TEST_DB.scope(self.db.clone(), fut))
+            .await
+    }
+
+    /// Insert the standard fixtures
+    pub async fn fixtures(&self) -> Fixtures {
+        let db = &self.db;
+
+        let ada = employee(db, "Ada", "Lovelace", "ada@example.com").await;
+        let grace = employee(db, "Grace", "Hopper", "grace@example.com").await;
+        let ada_user = user(db, "ada@example.com", session::ROLE_HR, Some(ada.id)).await;
+        let admin = user(db, "admin@example.com", session::ROLE_ADMIN, None).await;
+
+        Fixtures {
+            ada,
+            grace,
+            ada_user,
+            admin,
+        }
+    }
+}
+
+/// Rows inserted by `TestDb::fixtures`
+pub struct Fixtures {
+    /// Linked to `ada_user`
+    pub ada: employee::Model,
+    /// Has no user account
+    pub grace: employee::Model,
+    /// ROLE_HR, linked to `ada`
+    pub ada_user: users::Model,
+    /// ROLE_ADMIN, no employee
+    pub admin: users::Model,
+}
+
+impl Fixtures {
+    pub fn session(user: &users::Model, role: &str) -> SessionUser {
+        SessionUser {
+            id: user.id,
+            email: user.email.clone(),
+            role: role.to_string(),
+            permissions: Vec::new(),
+        }
+    }
+}
+
+async fn employee(
+    db: &DatabaseConnection,
+    first: &str,
+    last: &str,
+    email: &str,
+) -> employee::Model {
+    employee::ActiveModel {
+        first_name: Set(Some(first.into())),
+        last_name: Set(Some(last.into())),
+        email: Set(Some(email.into())),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .expect("fixture employee inserts")
+}
+
+async fn user(
+    db: &DatabaseConnection,
+    email: &str,
+    role: &str,
+    employee_id: Option<i32>,
+) -> users::Model {
+    let role_id = app_role::Entity::find()
+        .filter(app_role::Column::Name.eq(role))
+        .one(db)
+        .await
+        .expect("roles are readable")
+        .expect("reference role exists")
+        .id;
+
+    users::ActiveModel {
+        email: Set(email.into()),
+        // Not a bcrypt hash; fixtures never log in
+        password_hash: Set("-".into()),
+        role_id: Set(role_id),
+        employee_id: Set(employee_id),
+        is_active: Set(true),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .expect("fixture user inserts")
+}
+
+/// `server_url` with its database name replaced by `name`
+fn database_url(server_url: &str, name: &str) -> String {
+    let (base, query) = match server_url.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (server_url, None),
+    };
+    let root = base.rsplit_once('/').map_or(base, |(root, _)| root);
+    match query {
+        Some(query) => format!("{}/{}?{}", root, name, query),
+        None => format!("{}/{}", root, name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_the_database_name() {
+        assert_eq!(
+            database_url("postgres://u:p@localhost:5432/app", "t1"),
+            "postgres://u:p@localhost:5432/t1"
+        );
+        assert_eq!(
+            database_url("postgres://localhost/app?sslmode=disable", "t1"),
+            "postgres://localhost/t1?sslmode=disable"
+        );
+    }
+}
@@ -0,0 +1,279 @@
+// src/server/webhooks/mod.rs
+// Outbound webhooks: registrations, delivery log and manual redelivery.
+// Deliveries are enqueued by database triggers (migrations/005_webhooks.sql)
+// and sent by `worker`.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+pub mod worker;
+
+#[cfg(feature = "server")]
+use sea_orm::*;
+
+#[cfg(feature = "server")]
+use crate::entities::{webhook, webhook_delivery};
+
+#[cfg(feature = "server")]
+use crate::server::db_connection::get_db;
+
+/// Event types a webhook can subscribe to
+pub const EVENT_TYPES: [&str; 4] = [
+    "employee.created",
+    "employee.updated",
+    "employee.deleted",
+    "user.role_changed",
+];
+
+/// Number of log entries returned by `list_webhook_deliveries`
+#[cfg(feature = "server")]
+const DELIVERY_LOG_LIMIT: u64 = 100;
+
+//
+// -------------------- DTOs --------------------
+//
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: i32,
+    pub url: String,
+    pub event_type: String,
+    pub secret: String,
+    pub is_active: bool,
+    pub created_at: String,
+}
+
+#[cfg(feature = "server")]
+impl From<webhook::Model> for Webhook {
+    fn from(m: webhook::Model) -> Self {
+        Self {
+            id: m.id,
+            url: m.url,
+            event_type: m.event_type,
+            secret: m.secret,
+            is_active: m.is_active,
+            created_at: m.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: i32,
+    pub webhook_id: i32,
+    pub url: String,
+    pub event_type: String,
+    pub status: String,
+    pub attempts: i32,
+    pub last_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub next_attempt_at: String,
+    pub delivered_at: Option<String>,
+}
+
+#[cfg(feature = "server")]
+impl WebhookDelivery {
+    fn from_models(d: webhook_delivery::Model, url: String) -> Self {
+        Self {
+            id: d.id,
+            webhook_id: d.webhook_id,
+            url,
+            event_type: d.event_type,
+            status: d.status,
+            attempts: d.attempts,
+            last_status: d.last_status,
+            last_error: d.last_error,
+            created_at: d.created_at.to_rfc3339(),
+            next_attempt_at: d.next_attempt_at.to_rfc3339(),
+            delivered_at: d.delivered_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+/// Reject anything that is not an absolute http(s) URL or a known event type
+pub fn validate_registration(url: &str, event_type: &str) -> Result<(), String> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .ok_or("URL must start with http:// or https://")?;
+    if rest.is_empty() || rest.starts_with('/') || url.chars().any(char::is_whitespace) {
+        return Err("URL is not valid".into());
+    }
+    if !EVENT_TYPES.contains(&event_type) {
+        return Err(format!("Unknown event type '{}'", event_type));
+    }
+    Ok(())
+}
+
+//
+// -------------------- REGISTRATIONS --------------------
+//
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("list_webhooks"))]
+pub async fn list_webhooks() -> Result<Vec<Webhook>, ServerFnError> {
+    crate::server::session::require_permission(crate::server::auth::WEBHOOK_MANAGE).await?;
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let models = webhook::Entity::find()
+        .order_by_asc(webhook::Column::Id)
+        .all(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?;
+
+    Ok(models.into_iter().map(Into::into).collect())
+}
+
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("create_webhook"))]
+pub async fn create_webhook(url: String, event_type: String) -> Result<Webhook, ServerFnError> {
+    crate::server::session::require_permission(crate::server::auth::WEBHOOK_MANAGE).await?;
+    let url = url.trim().to_string();
+    validate_registration(&url, &event_type).map_err(ServerFnError::new)?;
+
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let new = webhook::ActiveModel {
+        url: Set(url),
+        event_type: Set(event_type),
+        secret: Set(crate::server::auth::random_password()),
+        is_active: Set(true),
+        ..Default::default()
+    };
+
+    let res = new
+        .insert(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Insert failed: {}", e)))?;
+
+    Ok(res.into())
+}
+
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("set_webhook_active"))]
+pub async fn set_webhook_active(id: i32, active: bool) -> Result<Webhook, ServerFnError> {
+    crate::server::session::require_permission(crate::server::auth::WEBHOOK_MANAGE).await?;
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let existing = webhook::Entity::find_by_id(id)
+        .one(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?
+        .ok_or_else(|| ServerFnError::new("Webhook not found"))?;
+
+    let mut model: webhook::ActiveModel = existing.into();
+    model.is_active = Set(active);
+
+    let updated = model
+        .update(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Update failed: {}", e)))?;
+
+    Ok(updated.into())
+}
+
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("delete_webhook"))]
+pub async fn delete_webhook(id: i32) -> Result<(), ServerFnError> {
+    crate::server::session::require_permission(crate::server::auth::WEBHOOK_MANAGE).await?;
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    // Pending deliveries and their log go with it (ON DELETE CASCADE)
+    webhook::Entity::delete_by_id(id)
+        .exec(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Delete failed: {}", e)))?;
+
+    Ok(())
+}
+
+//
+// -------------------- DELIVERY LOG --------------------
+//
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("list_webhook_deliveries"))]
+pub async fn list_webhook_deliveries(
+    webhook_id: Option<i32>,
+) -> Result<Vec<WebhookDelivery>, ServerFnError> {
+    crate::server::session::require_permission(crate::server::auth::WEBHOOK_MANAGE).await?;
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let mut query = webhook_delivery::Entity::find().find_also_related(webhook::Entity);
+    if let Some(webhook_id) = webhook_id {
+        query = query.filter(webhook_delivery::Column::WebhookId.eq(webhook_id));
+    }
+
+    let rows = query
+        .order_by_desc(webhook_delivery::Column::Id)
+        .limit(DELIVERY_LOG_LIMIT)
+        .all(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(d, w)| WebhookDelivery::from_models(d, w.map(|w| w.url).unwrap_or_default()))
+        .collect())
+}
+
+/// Queue a copy of a past delivery; the original log entry stays untouched
+#[server]
+#[middleware(crate::server::telemetry::ServerFnLayer::new("redeliver_webhook"))]
+pub async fn redeliver_webhook(delivery_id: i32) -> Result<WebhookDelivery, ServerFnError> {
+    crate::server::session::require_permission(crate::server::auth::WEBHOOK_MANAGE).await?;
+    let db = get_db()
+        .await
+        .map_err(|e| ServerFnError::new(format!("DB connection failed: {}", e)))?;
+
+    let (original, hook) = webhook_delivery::Entity::find_by_id(delivery_id)
+        .find_also_related(webhook::Entity)
+        .one(&db)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Query failed: {}", e)))?
+        .ok_or_else(|| ServerFnError::new("Delivery not found"))?;
+
+    let copy = webhook_delivery::ActiveModel {
+        webhook_id: Set(original.webhook_id),
+        event_type: Set(original.event_type),
+        payload: Set(original.payload),
+        ..Default::default()
+    }
+    .insert(&db)
+    .await
+    .map_err(|e| ServerFnError::new(format!("Insert failed: {}", e)))?;
+
+    worker::wake();
+
+    Ok(WebhookDelivery::from_models(
+        copy,
+        hook.map(|h| h.url).unwrap_or_default(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_registration;
+
+    #[test]
+    fn registration_requires_http_url_and_known_event() {
+        assert!(
+            validate_registration("https://payroll.example.com/hooks", "employee.created").is_ok()
+        );
+        assert!(validate_registration("http://localhost:9000", "user.role_changed").is_ok());
+
+        assert!(validate_registration("ftp://example.com", "employee.created").is_err());
+        assert!(validate_registration("https://", "employee.created").is_err());
+        assert!(validate_registration("https://exa mple.com", "employee.created").is_err());
+        assert!(validate_registration("https://example.com", "employee.hired").is_err());
+    }
+}
@@ -0,0 +1,489 @@
+//! Webhook delivery worker
+//!
+//! Polls the `webhook_delivery` outbox, POSTs due rows to their webhook and
+//! records the outcome. A short transaction claims due rows with `FOR UPDATE
+//! SKIP LOCKED` and pushes their `next_attempt_at` out by a lease, so several
+//! server instances can run the worker side by side. The requests go out
+//! after that commit, and each outcome is written on its own; no transaction
+//! stays open while a receiver answers. Should an instance die mid-batch, its
+//! rows come due again once the lease runs out, so a receiver may see a
+//! delivery twice and can tell by `X-Webhook-Delivery`.
+//!
+//! Every request carries
+//! - `X-Webhook-Event`: the event type, e.g. `employee.created`
+//! - `X-Webhook-Delivery`: the delivery id (stable across retries)
+//! - `X-Webhook-Signature`: `t=<unix seconds>,v1=<hex HMAC-SHA256>` over
+//!   `"<t>.<raw body>"`, keyed with the webhook secret
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::{Expr, LockBehavior, LockType};
+use sea_orm::*;
+use sha2::Sha256;
+use tokio::sync::Notify;
+
+use crate::entities::{webhook, webhook_delivery};
+use crate::server::db_connection::get_db;
+
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// Attempts before a delivery is marked `failed`
+pub const MAX_ATTEMPTS: i32 = 8;
+
+/// Idle time between outbox polls
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Deliveries claimed per poll
+const BATCH_SIZE: u64 = 20;
+
+/// Per-request timeout; slow receivers count as failed attempts
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a claimed row stays away from other pollers; a full batch of
+/// timed-out requests fits comfortably
+const LEASE: Duration = Duration::from_secs(5 * 60);
+
+/// Longest wait between two attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(6 * 60 * 60);
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn notify() -> &'static Notify {
+    static NOTIFY: OnceLock<Notify> = OnceLock::new();
+    NOTIFY.get_or_init(Notify::new)
+}
+
+/// Start the worker once per process
+pub fn spawn() {
+    static STARTED: OnceLock<()> = OnceLock::new();
+    STARTED.get_or_init(|| {
+        tokio::spawn(run());
+    });
+}
+
+/// Poll the outbox now instead of waiting for the next interval
+pub fn wake() {
+    notify().notify_one();
+}
+
+async fn run() {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("HTTP client configuration is valid");
+
+    loop {
+        match tick(&client).await {
+            // A full batch means more rows may be due right away
+            Ok(n) if n as u64 == BATCH_SIZE => continue,
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Webhook delivery failed: {}", e),
+        }
+        let _ = tokio::time::timeout(POLL_INTERVAL, notify().notified()).await;
+    }
+}
+
+/// Deliver one batch of due rows; returns how many were attempted
+pub async fn tick(client: &reqwest::Client) -> Result<usize, DbErr> {
+    let db = get_db().await?;
+    let due = claim(&db).await?;
+
+    let hooks: HashMap<i32, webhook::Model> = webhook::Entity::find()
+        .filter(webhook::Column::Id.is_in(due.iter().map(|d| d.webhook_id)))
+        .all(&db)
+        .await?
+        .into_iter()
+        .map(|w| (w.id, w))
+        .collect();
+
+    let attempted = due.len();
+    for delivery in due {
+        let outcome = match hooks.get(&delivery.webhook_id) {
+            Some(hook) => {
+                let body = delivery.payload.to_string();
+                send(client, hook, &delivery.event_type, delivery.id, &body).await
+            }
+            None => Err("Webhook no longer exists".to_string()),
+        };
+        record(&db, delivery, outcome).await?;
+    }
+    Ok(attempted)
+}
+
+/// Take up to a batch of due rows and lease them: their `next_attempt_at`
+/// moves `LEASE` ahead, so other pollers skip them until `record` sets it
+async fn claim<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+) -> Result<Vec<webhook_delivery::Model>, DbErr> {
+    let txn = db.begin().await?;
+
+    let due = webhook_delivery::Entity::find()
+        .filter(webhook_delivery::Column::Status.eq("pending"))
+        .filter(webhook_delivery::Column::NextAttemptAt.lte(now()))
+        .order_by_asc(webhook_delivery::Column::Id)
+        .limit(BATCH_SIZE)
+        .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+        .all(&txn)
+        .await?;
+
+    if !due.is_empty() {
+        let lease = chrono::Duration::from_std(LEASE).expect("lease fits into chrono::Duration");
+        webhook_delivery::Entity::update_many()
+            .col_expr(
+                webhook_delivery::Column::NextAttemptAt,
+                Expr::value(now() + lease),
+            )
+            .filter(webhook_delivery::Column::Id.is_in(due.iter().map(|d| d.id)))
+            .exec(&txn)
+            .await?;
+    }
+
+    txn.commit().await?;
+    Ok(due)
+}
+
+/// POST `body` to the webhook; `Ok` only for a 2xx response
+pub async fn send(
+    client: &reqwest::Client,
+    hook: &webhook::Model,
+    event_type: &str,
+    delivery_id: i32,
+    body: &str,
+) -> Result<u16, String> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    let response = client
+        .post(&hook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, event_type)
+        .header(DELIVERY_HEADER, delivery_id.to_string())
+        .header(
+            SIGNATURE_HEADER,
+            sign(&hook.secret, timestamp, body.as_bytes()),
+        )
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err(format!("Receiver answered {}", status))
+    }
+}
+
+async fn record<C: ConnectionTrait>(
+    db: &C,
+    delivery: webhook_delivery::Model,
+    outcome: Result<u16, String>,
+) -> Result<(), DbErr> {
+    let attempts = delivery.attempts + 1;
+    let mut model: webhook_delivery::ActiveModel = delivery.into();
+    model.attempts = Set(attempts);
+
+    match outcome {
+        Ok(status) => {
+            model.status = Set("delivered".into());
+            model.last_status = Set(Some(status.into()));
+            model.last_error = Set(None);
+            model.delivered_at = Set(Some(now()));
+        }
+        Err(error) => {
+            model.last_error = Set(Some(error));
+            if attempts >= MAX_ATTEMPTS {
+                model.status = Set("failed".into());
+            } else {
+                let wait = chrono::Duration::from_std(backoff(attempts))
+                    .expect("backoff fits into chrono::Duration");
+                model.next_attempt_at = Set(now() + wait);
+            }
+        }
+    }
+
+    model.update(db).await?;
+    Ok(())
+}
+
+fn now() -> DateTimeWithTimeZone {
+    chrono::Utc::now().fixed_offset()
+}
+
+/// Exponential backoff after `attempts` failures: 30s, 1m, 2m, ... capped at 6h
+pub fn backoff(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    (Duration::from_secs(30) * 2u32.pow(exponent)).min(MAX_BACKOFF)
+}
+
+/// Signature header value for `body` sent at `timestamp`
+pub fn sign(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    let digest = mac(secret, timestamp, body).finalize().into_bytes();
+    format!("t={},v1={}", timestamp, hex(&digest))
+}
+
+/// Check a signature header the way a receiver should
+pub fn verify(secret: &str, header: &str, body: &[u8]) -> bool {
+    let mut timestamp = None;
+    let mut signature = None;
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", t)) => timestamp = t.parse::<u64>().ok(),
+            Some(("v1", s)) => signature = unhex(s),
+            _ => {}
+        }
+    }
+    match (timestamp, signature) {
+        // verify_slice compares in constant time
+        (Some(timestamp), Some(signature)) => mac(secret, timestamp, body)
+            .verify_slice(&signature)
+            .is_ok(),
+        _ => false,
+    }
+}
+
+fn mac(secret: &str, timestamp: u64, body: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key");
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body);
+    mac
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn unhex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Bytes, extract::State, http::HeaderMap, routing::post};
+    use std::sync::{Arc, Mutex};
+
+    type Received = Arc<Mutex<Vec<(HeaderMap, Bytes)>>>;
+
+    /// Local HTTP receiver that records requests and answers with `status`
+    async fn receiver(status: u16) -> (String, Received) {
+        let received: Received = Arc::default();
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(
+                    move |State(received): State<Received>, headers: HeaderMap, body: Bytes| async move {
+                        received.lock().unwrap().push((headers, body));
+                        axum::http::StatusCode::from_u16(status).unwrap()
+                    },
+                ),
+            )
+            .with_state(received.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, received)
+    }
+
+    fn hook(url: String) -> webhook::Model {
+        webhook::Model {
+            id: 1,
+            url,
+            event_type: "employee.created".into(),
+            secret: "s3cret".into(),
+            is_active: true,
+            created_at: now(),
+        }
+    }
+
+    #[test]
+    fn signature_matches_reference_vector() {
+        // echo -n '1700000000.{"a":1}' | openssl dgst -sha256 -hmac s3cret
+        assert_eq!(
+            sign("s3cret", 1_700_000_000, br#"{"a":1}"#),
+            "t=1700000000,v1=1698a50bc74d1ff1db85c4e0a5297c2ad9fdba245d5737cdb789e4cc6e098940"
+        );
+    }
+
+    #[test]
+    fn verify_rejects_tampering() {
+        let header = sign("s3cret", 42, b"body");
+        assert!(verify("s3cret", &header, b"body"));
+        assert!(!verify("s3cret", &header, b"b0dy"));
+        assert!(!verify("other", &header, b"body"));
+        assert!(!verify("s3cret", &header.replace("t=42", "t=43"), b"body"));
+        assert!(!verify("s3cret", "garbage", b"body"));
+    }
+
+    #[test]
+    fn backoff_grows_and_caps() {
+        assert_eq!(backoff(1), Duration::from_secs(30));
+        assert_eq!(backoff(2), Duration::from_secs(60));
+        assert_eq!(backoff(4), Duration::from_secs(240));
+        assert_eq!(backoff(30), MAX_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn send_signs_request_for_local_receiver() {
+        let (url, received) = receiver(200).await;
+        let body = r#"{"event":"employee.created","data":{"id":7}}"#;
+
+        let result = send(
+            &reqwest::Client::new(),
+            &hook(url),
+            "employee.created",
+            99,
+            body,
+        )
+        .await;
+        assert_eq!(result, Ok(200));
+
+        let received = received.lock().unwrap();
+        let (headers, got) = &received[0];
+        assert_eq!(got.as_ref(), body.as_bytes());
+        assert_eq!(headers[EVENT_HEADER], "employee.created");
+        assert_eq!(headers[DELIVERY_HEADER], "99");
+        let signature = headers[SIGNATURE_HEADER].to_str().unwrap();
+        assert!(verify("s3cret", signature, got));
+    }
+
+    #[tokio::test]
+    async fn send_reports_non_2xx_as_failure() {
+        let (url, _) = receiver(503).await;
+        let result = send(
+            &reqwest::Client::new(),
+            &hook(url),
+            "employee.created",
+            1,
+            "{}",
+        )
+        .await;
+        assert!(result.unwrap_err().contains("503"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn claimed_rows_are_leased_until_recorded() {
+        let mut options = ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1);
+        let db = Database::connect(options).await.unwrap();
+        crate::server::migrate::create_sqlite_schema(&db)
+            .await
+            .unwrap();
+
+        let hook = webhook::ActiveModel {
+            url: Set("http://127.0.0.1:9/hook".into()),
+            event_type: Set("employee.created".into()),
+            secret: Set("s3cret".into()),
+            is_active: Set(true),
+            created_at: Set(now()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+        let delivery = webhook_delivery::ActiveModel {
+            webhook_id: Set(hook.id),
+            event_type: Set("employee.created".into()),
+            payload: Set(serde_json::json!({"event": "employee.created"})),
+            status: Set("pending".into()),
+            attempts: Set(0),
+            next_attempt_at: Set(now()),
+            created_at: Set(now()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let claimed = claim(&db).await.unwrap();
+        assert_eq!(claimed.len(), 1);
+        // Leased: a second poller finds nothing while the request is out
+        assert!(claim(&db).await.unwrap().is_empty());
+
+        record(&db, claimed[0].clone(), Err("timed out".into()))
+            .await
+            .unwrap();
+        let retried = webhook_delivery::Entity::find_by_id(delivery.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(retried.attempts, 1);
+        assert_eq!(retried.status, "pending");
+        assert!(retried.next_attempt_at > now());
+        assert!(retried.next_attempt_at < now() + chrono::Duration::minutes(1));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a local PostgreSQL database (DATABASE_URL)"]
+    async fn outbox_delivers_employee_created() {
+        use crate::entities::employee;
+
+        dotenvy::dotenv().ok();
+        let (url, received) = receiver(200).await;
+        let db = get_db().await.unwrap();
+
+        let hook = webhook::ActiveModel {
+            url: Set(url),
+            event_type: Set("employee.created".into()),
+            secret: Set("s3cret".into()),
+            is_active: Set(true),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let employee = employee::ActiveModel {
+            first_name: Set(Some("Hook".into())),
+            last_name: Set(Some("Test".into())),
+            email: Set(Some(format!("hook-{}@example.com", hook.id))),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        tick(&reqwest::Client::new()).await.unwrap();
+
+        let delivery = webhook_delivery::Entity::find()
+            .filter(webhook_delivery::Column::WebhookId.eq(hook.id))
+            .one(&db)
+            .await
+            .unwrap()
+            .expect("trigger enqueued a delivery");
+        assert_eq!(delivery.status, "delivered");
+        assert_eq!(delivery.attempts, 1);
+
+        let payload: serde_json::Value =
+            serde_json::from_slice(&received.lock().unwrap()[0].1).unwrap();
+        assert_eq!(payload["event"], "employee.created");
+        assert_eq!(payload["data"]["id"], employee.id);
+
+        employee::Entity::delete_by_id(employee.id)
+            .exec(&db)
+            .await
+            .unwrap();
+        webhook::Entity::delete_by_id(hook.id)
+            .exec(&db)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,109 @@
+//! Light, dark and system color themes
+//!
+//! `App` puts the current `Theme` on its root element as `data-theme`, and
+//! the `dark` variant in `tailwind.css` keys off that attribute, so
+//! components only add `dark:` classes. `System` follows the operating
+//! system through `prefers-color-scheme`, in CSS, without any script.
+//!
+//! The choice is stored like the language: a `theme` cookie for the browser
+//! and `users.theme` for a signed-in user, restored at login.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Cookie holding the chosen theme code
+pub const COOKIE: &str = "theme";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    Light,
+    Dark,
+    #[default]
+    System,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Light, Theme::Dark, Theme::System];
+
+    /// Value of `data-theme`, the cookie and `users.theme`
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+            Self::System => "system",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.code() == code.trim())
+    }
+
+    /// The theme the header toggle switches to
+    pub fn next(self) -> Self {
+        match self {
+            Self::Light => Self::Dark,
+            Self::Dark => Self::System,
+            Self::System => Self::Light,
+        }
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            Self::Light => "☀️",
+            Self::Dark => "🌙",
+            Self::System => "🖥️",
+        }
+    }
+
+    /// Catalog key of the theme's name
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Self::Light => "theme.light",
+            Self::Dark => "theme.dark",
+            Self::System => "theme.system",
+        }
+    }
+}
+
+/// The UI's current theme
+#[derive(Clone, Copy, PartialEq)]
+pub struct ThemeState {
+    pub theme: Signal<Theme>,
+}
+
+pub fn use_theme() -> ThemeState {
+    use_context::<ThemeState>()
+}
+
+/// Theme of the current request's cookie, `System` without one
+#[cfg(feature = "server")]
+pub fn request_theme() -> Theme {
+    crate::server::session::request_cookie(COOKIE)
+        .and_then(|code| Theme::from_code(&code))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_round_trip() {
+        for theme in Theme::ALL {
+            assert_eq!(Theme::from_code(theme.code()), Some(theme));
+        }
+        assert_eq!(Theme::from_code("sepia"), None);
+        assert_eq!(Theme::default(), Theme::System);
+    }
+
+    #[test]
+    fn toggle_cycles_through_every_theme() {
+        let mut theme = Theme::Light;
+        for expected in [Theme::Dark, Theme::System, Theme::Light] {
+            theme = theme.next();
+            assert_eq!(theme, expected);
+        }
+    }
+}
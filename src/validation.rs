@@ -0,0 +1,284 @@
+//! Field rules shared by the forms and the server
+//!
+//! Each input DTO declares its fields with a list of `Rule`s. The same
+//! function runs in the browser on every keystroke (inline errors, Save
+//! disabled) and on the server before anything is written, so a request
+//! that skips the UI is held to the same rules. The server reports failures
+//! as a `422` server function error whose details carry the `FieldErrors`;
+//! `FieldErrors::from_server` turns them back into per-field errors. Errors
+//! travel as `FieldError` variants, so each side shows them in its own
+//! language (`i18n::Translate`); `Display` is English, for the REST and
+//! GraphQL APIs and the CLI.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use dioxus::prelude::ServerFnError;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{Locale, Translate};
+
+/// Length of the `VARCHAR(100)` name and email columns
+pub const MAX_LEN: usize = 100;
+
+/// Status code of the server function error from `FieldErrors::into_result`
+const INVALID_INPUT: u16 = 422;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rule {
+    /// Not empty after trimming
+    Required,
+    /// At most this many characters
+    MaxLen(usize),
+    Email,
+    /// Digits with an optional leading `+` and spaces, dashes, slashes or
+    /// parentheses between them; 6 to 15 digits
+    Phone,
+}
+
+impl Rule {
+    /// The error for `value` breaking this rule. Only `Required` looks at
+    /// empty values; the other rules apply to whatever was entered.
+    fn check(self, value: &str) -> Option<FieldError> {
+        let value = value.trim();
+        let broken = match self {
+            Rule::Required => value.is_empty(),
+            _ if value.is_empty() => false,
+            Rule::MaxLen(max) => value.chars().count() > max,
+            Rule::Email => !is_email(value),
+            Rule::Phone => !is_phone(value),
+        };
+        broken.then_some(match self {
+            Rule::Required => FieldError::Required,
+            Rule::MaxLen(max) => FieldError::TooLong(max),
+            Rule::Email => FieldError::InvalidEmail,
+            Rule::Phone => FieldError::InvalidPhone,
+        })
+    }
+}
+
+/// How a field broke its rules
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FieldError {
+    Required,
+    TooLong(usize),
+    InvalidEmail,
+    InvalidPhone,
+}
+
+impl Translate for FieldError {
+    fn key(&self) -> &'static str {
+        match self {
+            Self::Required => "validation.required",
+            Self::TooLong(_) => "validation.too_long",
+            Self::InvalidEmail => "validation.email",
+            Self::InvalidPhone => "validation.phone",
+        }
+    }
+
+    fn args(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::TooLong(max) => vec![("max", max.to_string())],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message(Locale::En))
+    }
+}
+
+fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && !value.chars().any(char::is_whitespace)
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(|label| !label.is_empty())
+}
+
+fn is_phone(value: &str) -> bool {
+    let body = value.strip_prefix('+').unwrap_or(value);
+    let digits = body.chars().filter(char::is_ascii_digit).count();
+    body.chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '-' | '/' | '(' | ')'))
+        && (6..=15).contains(&digits)
+}
+
+/// The first broken rule per field, keyed by field name
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FieldErrors(BTreeMap<String, FieldError>);
+
+impl FieldErrors {
+    /// Check each `(field, value, rules)` and keep the first failure per field
+    pub fn check(fields: &[(&str, &str, &[Rule])]) -> Self {
+        Self(
+            fields
+                .iter()
+                .filter_map(|(field, value, rules)| {
+                    let error = rules.iter().find_map(|rule| rule.check(value))?;
+                    Some((field.to_string(), error))
+                })
+                .collect(),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, field: &str) -> Option<FieldError> {
+        self.0.get(field).copied()
+    }
+
+    pub fn remove(&mut self, field: &str) {
+        self.0.remove(field);
+    }
+
+    /// `Ok` when nothing failed, otherwise a server function error carrying
+    /// the field errors. The message is in the request's language.
+    #[cfg(feature = "server")]
+    pub fn into_result(self) -> Result<(), ServerFnError> {
+        self.into_result_in(crate::i18n::request_locale())
+    }
+
+    #[cfg_attr(not(feature = "server"), allow(dead_code))]
+    fn into_result_in(self, locale: Locale) -> Result<(), ServerFnError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        Err(ServerFnError::ServerError {
+            message: crate::i18n::t(locale, "validation.invalid_input"),
+            code: INVALID_INPUT,
+            details: serde_json::to_value(&self).ok(),
+        })
+    }
+
+    /// The field errors of a server function error from `into_result`
+    pub fn from_server(error: &ServerFnError) -> Option<Self> {
+        match error {
+            ServerFnError::ServerError {
+                code: INVALID_INPUT,
+                details: Some(details),
+                ..
+            } => serde_json::from_value(details.clone()).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FieldErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self
+            .0
+            .iter()
+            .map(|(field, error)| format!("{}: {}", field, error))
+            .collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+//
+// -------------------- DTO RULES --------------------
+//
+
+const NAME: &[Rule] = &[Rule::Required, Rule::MaxLen(MAX_LEN)];
+const EMAIL: &[Rule] = &[Rule::Required, Rule::MaxLen(MAX_LEN), Rule::Email];
+/// `app_role.name` is a `VARCHAR(50)`
+const ROLE_NAME: &[Rule] = &[Rule::Required, Rule::MaxLen(50)];
+
+/// Employee create / update input
+pub fn employee(first_name: &str, last_name: &str, email: &str) -> FieldErrors {
+    FieldErrors::check(&[
+        ("first_name", first_name, NAME),
+        ("last_name", last_name, NAME),
+        ("email", email, EMAIL),
+    ])
+}
+
+/// User account input
+pub fn user(email: &str) -> FieldErrors {
+    FieldErrors::check(&[("email", email, EMAIL)])
+}
+
+/// New role input
+pub fn role(name: &str) -> FieldErrors {
+    FieldErrors::check(&[("name", name, ROLE_NAME)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emails() {
+        for ok in ["ada@example.com", "a.b+c@mail.example.co.uk"] {
+            assert!(is_email(ok), "{}", ok);
+        }
+        for bad in [
+            "not-an-email",
+            "@example.com",
+            "ada@",
+            "ada@example",
+            "ada@example..com",
+            "ada @example.com",
+            "a@b@example.com",
+        ] {
+            assert!(!is_email(bad), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn phones() {
+        for ok in ["+49 30 1234567", "(030) 123-456", "0301234567"] {
+            assert!(is_phone(ok), "{}", ok);
+        }
+        for bad in ["12345", "+49 30 12a4567", "1+2345678", "1234567890123456"] {
+            assert!(!is_phone(bad), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn first_broken_rule_per_field() {
+        let long = "x".repeat(MAX_LEN + 1);
+        let errors = employee("  ", &long, "not-an-email");
+        assert_eq!(errors.get("first_name"), Some(FieldError::Required));
+        assert_eq!(errors.get("last_name"), Some(FieldError::TooLong(MAX_LEN)));
+        assert_eq!(errors.get("email"), Some(FieldError::InvalidEmail));
+
+        assert!(employee("Ada", "Lovelace", "ada@example.com").is_empty());
+        assert_eq!(user("").get("email"), Some(FieldError::Required));
+    }
+
+    #[test]
+    fn messages_follow_the_locale() {
+        let long = FieldError::TooLong(MAX_LEN);
+        assert_eq!(long.to_string(), "At most 100 characters");
+        assert_eq!(long.message(Locale::De), "Höchstens 100 Zeichen");
+        assert_eq!(
+            employee("", "Lovelace", "x").to_string(),
+            "email: Not a valid email address, first_name: Required"
+        );
+    }
+
+    #[test]
+    fn field_errors_survive_the_server_error() {
+        let errors = employee("", "Lovelace", "ada@example.com");
+        let error = errors.clone().into_result_in(Locale::De).unwrap_err();
+        assert!(matches!(
+            &error,
+            ServerFnError::ServerError { message, .. } if message == "Ungültige Eingabe"
+        ));
+        assert_eq!(FieldErrors::from_server(&error), Some(errors));
+
+        assert_eq!(
+            FieldErrors::from_server(&ServerFnError::new("Not signed in")),
+            None
+        );
+        assert!(FieldErrors::default().into_result_in(Locale::En).is_ok());
+    }
+}